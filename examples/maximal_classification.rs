@@ -47,7 +47,25 @@ fn main() {
                 .with_weight(KNNWeightFunction::Uniform),
         )
         .with_gaussian_nb_settings(GaussianNBParameters::default().with_priors(vec![1.0, 1.0]))
-        .with_categorical_nb_settings(CategoricalNBParameters::default().with_alpha(1.0));
+        .with_categorical_nb_settings(CategoricalNBParameters::default().with_alpha(1.0))
+        .with_gradient_boosting_classifier_settings(
+            GradientBoostingClassifierParameters::default()
+                .with_learning_rate(0.1)
+                .with_n_estimators(50)
+                .with_max_depth(3)
+                .with_subsample(0.8),
+        )
+        .with_extra_trees_classifier_settings(
+            ExtraTreesClassifierParameters::default()
+                .with_n_trees(50)
+                .with_min_samples_leaf(2),
+        )
+        .with_mlp_classifier_settings(
+            MLPClassifierParameters::default()
+                .with_hidden_layers(&[32])
+                .with_activation(Activation::Tanh)
+                .with_max_iter(100),
+        );
 
     // Save the settings for later use
     settings.save("examples/maximal_classification_settings.yaml");
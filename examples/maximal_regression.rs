@@ -66,6 +66,40 @@ fn main() {
                 .with_min_samples_split(2)
                 .with_max_depth(15)
                 .with_min_samples_leaf(1),
+        )
+        .with_gradient_boosting_regressor_settings(
+            GradientBoostingRegressorParameters::default()
+                .with_learning_rate(0.1)
+                .with_n_estimators(100)
+                .with_max_depth(3)
+                .with_validation_fraction(0.1)
+                .with_n_iter_no_change(10),
+        )
+        .with_extra_trees_regressor_settings(
+            ExtraTreesRegressorParameters::default()
+                .with_n_trees(50)
+                .with_max_depth(10)
+                .with_min_samples_leaf(2),
+        )
+        .with_mlp_regressor_settings(
+            MLPRegressorParameters::default()
+                .with_hidden_layers(&[64, 32])
+                .with_optimizer(Optimizer::Adam)
+                .with_batch_size(32)
+                .with_validation_fraction(0.1),
+        )
+        .with_poisson_regressor_settings(PoissonRegressorParameters::default().with_alpha(0.01))
+        .with_gamma_regressor_settings(GammaRegressorParameters::default().with_max_iter(200))
+        .with_tweedie_regressor_settings(
+            TweedieRegressorParameters::default()
+                .with_power(1.5)
+                .with_tol(1e-5),
+        )
+        .with_quantile_regressor_settings(QuantileRegressorParameters::default().with_alpha(0.01))
+        .with_gradient_boosting_quantile_regressor_settings(
+            GradientBoostingQuantileRegressorParameters::default()
+                .with_quantile(0.5)
+                .with_boosting(GradientBoostingParameters::default().with_n_estimators(50)),
         );
 
     // Save the settings for later use
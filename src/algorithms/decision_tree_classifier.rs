@@ -1,37 +1,14 @@
 use smartcore::{
     linalg::naive::dense_matrix::DenseMatrix,
-    model_selection::{cross_validate, CrossValidationResult},
     tree::decision_tree_classifier::DecisionTreeClassifier,
 };
 
-use crate::{Algorithm, Settings};
+use super::tree_structure::{ClassificationTree, Shap};
+use crate::Settings;
 
 pub(crate) struct DecisionTreeClassifierWrapper {}
 
 impl super::ModelWrapper for DecisionTreeClassifierWrapper {
-    fn cv(
-        x: &DenseMatrix<f32>,
-        y: &Vec<f32>,
-        settings: &Settings,
-    ) -> (CrossValidationResult<f32>, Algorithm) {
-        (
-            cross_validate(
-                DecisionTreeClassifier::fit,
-                x,
-                y,
-                settings
-                    .decision_tree_classifier_settings
-                    .as_ref()
-                    .unwrap()
-                    .clone(),
-                settings.get_kfolds(),
-                settings.get_metric(),
-            )
-            .unwrap(),
-            Algorithm::DecisionTreeClassifier,
-        )
-    }
-
     fn train(x: &DenseMatrix<f32>, y: &Vec<f32>, settings: &Settings) -> Vec<u8> {
         bincode::serialize(
             &DecisionTreeClassifier::fit(
@@ -52,4 +29,18 @@ impl super::ModelWrapper for DecisionTreeClassifierWrapper {
         let model: DecisionTreeClassifier<f32> = bincode::deserialize(&*final_model).unwrap();
         model.predict(x).unwrap()
     }
+
+    fn impurity_importances(x: &DenseMatrix<f32>, final_model: &Vec<u8>) -> Option<Vec<Vec<f32>>> {
+        let tree: ClassificationTree = bincode::deserialize(&*final_model).unwrap();
+        Some(vec![tree.importances(x)])
+    }
+
+    fn tree_shap(
+        background: &DenseMatrix<f32>,
+        x: &DenseMatrix<f32>,
+        final_model: &Vec<u8>,
+    ) -> Option<Shap> {
+        let tree: ClassificationTree = bincode::deserialize(&*final_model).unwrap();
+        Some(tree.shap(background, x))
+    }
 }
@@ -1,37 +1,13 @@
 use smartcore::{
-    linalg::naive::dense_matrix::DenseMatrix,
-    model_selection::{cross_validate, CrossValidationResult},
-    tree::decision_tree_regressor::DecisionTreeRegressor,
+    linalg::naive::dense_matrix::DenseMatrix, tree::decision_tree_regressor::DecisionTreeRegressor,
 };
 
-use crate::{Algorithm, Settings};
+use super::tree_structure::{RegressionTree, Shap};
+use crate::Settings;
 
 pub(crate) struct DecisionTreeRegressorWrapper {}
 
 impl super::ModelWrapper for DecisionTreeRegressorWrapper {
-    fn cv(
-        x: &DenseMatrix<f32>,
-        y: &Vec<f32>,
-        settings: &Settings,
-    ) -> (CrossValidationResult<f32>, Algorithm) {
-        (
-            cross_validate(
-                DecisionTreeRegressor::fit,
-                x,
-                y,
-                settings
-                    .decision_tree_regressor_settings
-                    .as_ref()
-                    .unwrap()
-                    .clone(),
-                settings.get_kfolds(),
-                settings.get_metric(),
-            )
-            .unwrap(),
-            Algorithm::DecisionTreeRegressor,
-        )
-    }
-
     fn train(x: &DenseMatrix<f32>, y: &Vec<f32>, settings: &Settings) -> Vec<u8> {
         bincode::serialize(
             &DecisionTreeRegressor::fit(
@@ -52,4 +28,18 @@ impl super::ModelWrapper for DecisionTreeRegressorWrapper {
         let model: DecisionTreeRegressor<f32> = bincode::deserialize(&*final_model).unwrap();
         model.predict(x).unwrap()
     }
+
+    fn impurity_importances(x: &DenseMatrix<f32>, final_model: &Vec<u8>) -> Option<Vec<Vec<f32>>> {
+        let tree: RegressionTree = bincode::deserialize(&*final_model).unwrap();
+        Some(vec![tree.importances(x)])
+    }
+
+    fn tree_shap(
+        background: &DenseMatrix<f32>,
+        x: &DenseMatrix<f32>,
+        final_model: &Vec<u8>,
+    ) -> Option<Shap> {
+        let tree: RegressionTree = bincode::deserialize(&*final_model).unwrap();
+        Some(tree.shap(background, x))
+    }
 }
@@ -1,32 +1,10 @@
-use smartcore::{
-    linalg::naive::dense_matrix::DenseMatrix, linear::elastic_net::ElasticNet,
-    model_selection::cross_validate, model_selection::CrossValidationResult,
-};
+use smartcore::{linalg::naive::dense_matrix::DenseMatrix, linear::elastic_net::ElasticNet};
 
-use crate::{Algorithm, Settings};
+use crate::Settings;
 
 pub(crate) struct ElasticNetRegressorWrapper {}
 
 impl super::ModelWrapper for ElasticNetRegressorWrapper {
-    fn cv(
-        x: &DenseMatrix<f32>,
-        y: &Vec<f32>,
-        settings: &Settings,
-    ) -> (CrossValidationResult<f32>, Algorithm) {
-        (
-            cross_validate(
-                ElasticNet::fit,
-                x,
-                y,
-                settings.elastic_net_settings.as_ref().unwrap().clone(),
-                settings.get_kfolds(),
-                settings.get_metric(),
-            )
-            .unwrap(),
-            Algorithm::ElasticNet,
-        )
-    }
-
     fn train(x: &DenseMatrix<f32>, y: &Vec<f32>, settings: &Settings) -> Vec<u8> {
         bincode::serialize(
             &ElasticNet::fit(
@@ -43,4 +21,12 @@ impl super::ModelWrapper for ElasticNetRegressorWrapper {
         let model: ElasticNet<f32, DenseMatrix<f32>> = bincode::deserialize(&*final_model).unwrap();
         model.predict(x).unwrap()
     }
+
+    fn coefficients(final_model: &Vec<u8>) -> Option<(Vec<Vec<f32>>, Vec<f32>)> {
+        let model: ElasticNet<f32, DenseMatrix<f32>> = bincode::deserialize(&*final_model).unwrap();
+        Some((
+            super::column_to_row(model.coefficients()),
+            vec![model.intercept()],
+        ))
+    }
 }
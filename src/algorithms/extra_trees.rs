@@ -0,0 +1,313 @@
+//! Extremely randomized trees (Geurts et al., 2006), shared by the regressor and the classifier.
+//! Unlike a random forest, every tree sees all of the training rows, and each split tries a
+//! single random threshold, drawn uniformly between the smallest and largest value in the node,
+//! for each of a random set of features. The best of those candidates is kept.
+//!
+//! Regression trees store the mean target in each node and split on the decrease in the sum of
+//! squares. Classification trees store the share of each class and split on the decrease in Gini
+//! impurity, weighted by the number of samples. The forest averages the outputs of its trees and,
+//! for classification, predicts the class with the largest average share.
+
+use rand::{seq::SliceRandom, Rng};
+use smartcore::linalg::{naive::dense_matrix::DenseMatrix, BaseMatrix};
+
+use super::tree_structure::{average_shap, importances, tree_shap, Node, Shap};
+
+/// How the trees of a forest are grown
+pub(crate) struct Growth {
+    /// The number of trees
+    pub(crate) n_trees: u16,
+    /// The maximum depth of each tree, if limited
+    pub(crate) max_depth: Option<u16>,
+    /// The minimum number of samples in a leaf
+    pub(crate) min_samples_leaf: usize,
+    /// The minimum number of samples needed to split a node
+    pub(crate) min_samples_split: usize,
+    /// The number of features tried at each split
+    pub(crate) m: usize,
+}
+
+/// A trained forest
+#[derive(serde::Serialize, serde::Deserialize)]
+pub(crate) struct ExtraTrees {
+    /// The sorted class labels, or nothing for regression
+    classes: Vec<f32>,
+    /// The nodes of each tree, with the root first
+    trees: Vec<Vec<Node<Vec<f32>>>>,
+}
+
+impl ExtraTrees {
+    /// Grow a forest for a continuous target
+    pub(crate) fn fit_regressor(x: &DenseMatrix<f32>, y: &[f32], growth: &Growth) -> Self {
+        let targets = Targets::Continuous(y.to_vec());
+        Self::fit(x, vec![], &targets, growth)
+    }
+
+    /// Grow a forest for class labels
+    pub(crate) fn fit_classifier(x: &DenseMatrix<f32>, y: &[f32], growth: &Growth) -> Self {
+        let mut classes = y.to_vec();
+        classes.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        classes.dedup();
+        let labels = y
+            .iter()
+            .map(|label| classes.iter().position(|class| class == label).unwrap())
+            .collect();
+        let targets = Targets::Classes(labels, classes.len());
+        Self::fit(x, classes, &targets, growth)
+    }
+
+    /// Predict continuous values, or class labels for a classifier
+    pub(crate) fn predict(&self, x: &DenseMatrix<f32>) -> Vec<f32> {
+        let (rows, width) = x.shape();
+        (0..rows)
+            .map(|row| {
+                let row: Vec<f32> = (0..width).map(|column| x.get(row, column)).collect();
+                let mut total = vec![0.0; self.classes.len().max(1)];
+                for tree in &self.trees {
+                    for (total, value) in total.iter_mut().zip(&tree[leaf(tree, &row)].output) {
+                        *total += value;
+                    }
+                }
+                if self.classes.is_empty() {
+                    total[0] / self.trees.len() as f32
+                } else {
+                    let mut best = 0;
+                    for (class, share) in total.iter().enumerate() {
+                        if *share > total[best] {
+                            best = class;
+                        }
+                    }
+                    self.classes[best]
+                }
+            })
+            .collect()
+    }
+
+    /// The sorted class labels and the average share of each over the trees for each row, or
+    /// `None` for regression
+    pub(crate) fn predict_proba(&self, x: &DenseMatrix<f32>) -> Option<super::ClassProbabilities> {
+        if self.classes.is_empty() {
+            return None;
+        }
+        let (rows, width) = x.shape();
+        let probabilities = (0..rows)
+            .map(|row| {
+                let row: Vec<f32> = (0..width).map(|column| x.get(row, column)).collect();
+                let mut total = vec![0.0; self.classes.len()];
+                for tree in &self.trees {
+                    for (total, value) in total.iter_mut().zip(&tree[leaf(tree, &row)].output) {
+                        *total += value;
+                    }
+                }
+                total
+                    .into_iter()
+                    .map(|share| share / self.trees.len() as f32)
+                    .collect()
+            })
+            .collect();
+        Some((self.classes.clone(), probabilities))
+    }
+
+    /// Importance of each feature in every tree that splits, normalized to sum to one. If no tree
+    /// splits at all, every feature gets an importance of zero.
+    pub(crate) fn importances(&self, x: &DenseMatrix<f32>) -> Vec<Vec<f32>> {
+        // A random threshold can leave a root without any valid split, and a tree that is a
+        // single leaf has no importances to average
+        let importances: Vec<Vec<f32>> = self
+            .trees
+            .iter()
+            .filter(|tree| tree.len() > 1)
+            // Split scores are already weighted by the number of samples in the node
+            .map(|tree| importances(tree, x, false))
+            .collect();
+        if importances.is_empty() {
+            let (_, width) = x.shape();
+            vec![vec![0.0; width]]
+        } else {
+            importances
+        }
+    }
+
+    /// SHAP values of each row of `x`, averaged over the trees. For classification each tree
+    /// is explained by the majority class label of its leaves, as for random forests, so the
+    /// values add up to the mean of those labels over the trees. That is not the predicted class,
+    /// which comes from the average class shares, and for more than two classes it is only
+    /// meaningful when the labels are ordered.
+    pub(crate) fn shap(&self, background: &DenseMatrix<f32>, x: &DenseMatrix<f32>) -> Shap {
+        average_shap(self.trees.iter().map(|tree| {
+            let values: Vec<f32> = tree
+                .iter()
+                .map(|node| {
+                    if self.classes.is_empty() {
+                        node.output[0]
+                    } else {
+                        let mut best = 0;
+                        for (class, share) in node.output.iter().enumerate() {
+                            if *share > node.output[best] {
+                                best = class;
+                            }
+                        }
+                        self.classes[best]
+                    }
+                })
+                .collect();
+            tree_shap(tree, &values, background, x)
+        }))
+    }
+
+    /// Grow every tree on all of the rows
+    fn fit(x: &DenseMatrix<f32>, classes: Vec<f32>, targets: &Targets, growth: &Growth) -> Self {
+        let (rows, width) = x.shape();
+        let columns: Vec<Vec<f32>> = (0..width).map(|column| x.get_col_as_vec(column)).collect();
+        let mut grower = Grower {
+            columns: &columns,
+            targets,
+            growth,
+            rng: rand::thread_rng(),
+            nodes: vec![],
+        };
+        let trees = (0..growth.n_trees)
+            .map(|_| {
+                grower.grow((0..rows).collect(), 0);
+                std::mem::take(&mut grower.nodes)
+            })
+            .collect();
+        Self { classes, trees }
+    }
+}
+
+/// Find the leaf a row ends up in
+fn leaf(tree: &[Node<Vec<f32>>], row: &[f32]) -> usize {
+    let mut id = 0;
+    while let Some(child) = tree[id].child(row[tree[id].split_feature]) {
+        id = child;
+    }
+    id
+}
+
+/// What the trees are fit to
+enum Targets {
+    /// Continuous values
+    Continuous(Vec<f32>),
+    /// The index of each class label, and the number of classes
+    Classes(Vec<usize>, usize),
+}
+
+impl Targets {
+    /// The output of a node holding these rows, and its impurity summed over the rows
+    fn summarize(&self, rows: &[usize]) -> (Vec<f32>, f32) {
+        let n = rows.len() as f32;
+        match self {
+            Targets::Continuous(y) => {
+                let mean = rows.iter().map(|row| y[*row] as f64).sum::<f64>() / n as f64;
+                let squares: f64 = rows.iter().map(|row| (y[*row] as f64 - mean).powi(2)).sum();
+                (vec![mean as f32], squares as f32)
+            }
+            Targets::Classes(labels, number_of_classes) => {
+                let mut counts = vec![0.0; *number_of_classes];
+                for row in rows {
+                    counts[labels[*row]] += 1.0;
+                }
+                let squares: f32 = counts.iter().map(|count| count * count).sum();
+                let shares = counts.iter().map(|count| count / n).collect();
+                (shares, n - squares / n)
+            }
+        }
+    }
+}
+
+/// The state used while growing one tree
+struct Grower<'a, R: Rng> {
+    /// The training data, one column per feature
+    columns: &'a [Vec<f32>],
+    /// The training targets
+    targets: &'a Targets,
+    /// How the tree is grown
+    growth: &'a Growth,
+    /// The source of the random features and thresholds
+    rng: R,
+    /// The nodes grown so far
+    nodes: Vec<Node<Vec<f32>>>,
+}
+
+impl<'a, R: Rng> Grower<'a, R> {
+    /// Grow a node for some rows, and then its children, returning the position of the node
+    fn grow(&mut self, rows: Vec<usize>, depth: u16) -> usize {
+        let (output, impurity) = self.targets.summarize(&rows);
+        let id = self.nodes.len();
+        self.nodes.push(Node {
+            _index: id,
+            output,
+            split_feature: 0,
+            split_value: None,
+            split_score: None,
+            true_child: None,
+            false_child: None,
+        });
+
+        let splittable = rows.len() >= self.growth.min_samples_split
+            && self.growth.max_depth.is_none_or(|max| depth < max)
+            && impurity > 0.0;
+        if !splittable {
+            return id;
+        }
+
+        if let Some((feature, threshold, score)) = self.best_split(&rows, impurity) {
+            let (left, right): (Vec<usize>, Vec<usize>) = rows
+                .into_iter()
+                .partition(|row| self.columns[feature][*row] <= threshold);
+            let true_child = self.grow(left, depth + 1);
+            let false_child = self.grow(right, depth + 1);
+            let node = &mut self.nodes[id];
+            node.split_feature = feature;
+            node.split_value = Some(threshold);
+            node.split_score = Some(score);
+            node.true_child = Some(true_child);
+            node.false_child = Some(false_child);
+        }
+        id
+    }
+
+    /// Draw a random threshold for each of `m` random features that vary in the node, and return
+    /// the feature, threshold and decrease in impurity of the best one that leaves enough samples
+    /// on each side
+    fn best_split(&mut self, rows: &[usize], impurity: f32) -> Option<(usize, f32, f32)> {
+        let mut features: Vec<usize> = (0..self.columns.len()).collect();
+        features.shuffle(&mut self.rng);
+
+        let mut best: Option<(usize, f32, f32)> = None;
+        let mut tried = 0;
+        for feature in features {
+            if tried == self.growth.m {
+                break;
+            }
+            let column = &self.columns[feature];
+            let (low, high) = rows
+                .iter()
+                .fold((f32::INFINITY, f32::NEG_INFINITY), |(low, high), row| {
+                    (low.min(column[*row]), high.max(column[*row]))
+                });
+            if low >= high {
+                continue;
+            }
+            tried += 1;
+
+            let threshold = self.rng.gen_range(low..high);
+            let (left, right): (Vec<usize>, Vec<usize>) = rows
+                .iter()
+                .copied()
+                .partition(|row| column[*row] <= threshold);
+            if left.len() < self.growth.min_samples_leaf
+                || right.len() < self.growth.min_samples_leaf
+            {
+                continue;
+            }
+            let score =
+                impurity - self.targets.summarize(&left).1 - self.targets.summarize(&right).1;
+            if best.is_none_or(|(_, _, best_score)| score > best_score) {
+                best = Some((feature, threshold, score));
+            }
+        }
+        best
+    }
+}
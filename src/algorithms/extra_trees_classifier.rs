@@ -0,0 +1,55 @@
+//! Extremely randomized trees classification
+
+use smartcore::linalg::{naive::dense_matrix::DenseMatrix, BaseMatrix};
+
+use super::extra_trees::{ExtraTrees, Growth};
+use super::tree_structure::Shap;
+use crate::Settings;
+
+/// The extremely randomized trees classifier
+pub(crate) struct ExtraTreesClassifierWrapper {}
+
+impl super::ModelWrapper for ExtraTreesClassifierWrapper {
+    fn train(x: &DenseMatrix<f32>, y: &Vec<f32>, settings: &Settings) -> Vec<u8> {
+        let parameters = settings.extra_trees_classifier_settings.as_ref().unwrap();
+        let (_, width) = x.shape();
+        let growth = Growth {
+            n_trees: parameters.n_trees,
+            max_depth: parameters.max_depth,
+            min_samples_leaf: parameters.min_samples_leaf,
+            min_samples_split: parameters.min_samples_split,
+            m: parameters
+                .m
+                .unwrap_or_else(|| (width as f32).sqrt().floor() as usize)
+                .clamp(1, width),
+        };
+        bincode::serialize(&ExtraTrees::fit_classifier(x, y, &growth)).unwrap()
+    }
+
+    fn predict(x: &DenseMatrix<f32>, final_model: &Vec<u8>, _settings: &Settings) -> Vec<f32> {
+        let model: ExtraTrees = bincode::deserialize(&*final_model).unwrap();
+        model.predict(x)
+    }
+
+    fn predict_proba(
+        x: &DenseMatrix<f32>,
+        final_model: &Vec<u8>,
+    ) -> Option<super::ClassProbabilities> {
+        let model: ExtraTrees = bincode::deserialize(final_model).unwrap();
+        model.predict_proba(x)
+    }
+
+    fn impurity_importances(x: &DenseMatrix<f32>, final_model: &Vec<u8>) -> Option<Vec<Vec<f32>>> {
+        let model: ExtraTrees = bincode::deserialize(&*final_model).unwrap();
+        Some(model.importances(x))
+    }
+
+    fn tree_shap(
+        background: &DenseMatrix<f32>,
+        x: &DenseMatrix<f32>,
+        final_model: &Vec<u8>,
+    ) -> Option<Shap> {
+        let model: ExtraTrees = bincode::deserialize(&*final_model).unwrap();
+        Some(model.shap(background, x))
+    }
+}
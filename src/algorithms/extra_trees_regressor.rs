@@ -0,0 +1,44 @@
+//! Extremely randomized trees regression
+
+use smartcore::linalg::{naive::dense_matrix::DenseMatrix, BaseMatrix};
+
+use super::extra_trees::{ExtraTrees, Growth};
+use super::tree_structure::Shap;
+use crate::Settings;
+
+/// The extremely randomized trees regressor
+pub(crate) struct ExtraTreesRegressorWrapper {}
+
+impl super::ModelWrapper for ExtraTreesRegressorWrapper {
+    fn train(x: &DenseMatrix<f32>, y: &Vec<f32>, settings: &Settings) -> Vec<u8> {
+        let parameters = settings.extra_trees_regressor_settings.as_ref().unwrap();
+        let (_, width) = x.shape();
+        let growth = Growth {
+            n_trees: parameters.n_trees,
+            max_depth: parameters.max_depth,
+            min_samples_leaf: parameters.min_samples_leaf,
+            min_samples_split: parameters.min_samples_split,
+            m: parameters.m.unwrap_or(width).clamp(1, width),
+        };
+        bincode::serialize(&ExtraTrees::fit_regressor(x, y, &growth)).unwrap()
+    }
+
+    fn predict(x: &DenseMatrix<f32>, final_model: &Vec<u8>, _settings: &Settings) -> Vec<f32> {
+        let model: ExtraTrees = bincode::deserialize(&*final_model).unwrap();
+        model.predict(x)
+    }
+
+    fn impurity_importances(x: &DenseMatrix<f32>, final_model: &Vec<u8>) -> Option<Vec<Vec<f32>>> {
+        let model: ExtraTrees = bincode::deserialize(&*final_model).unwrap();
+        Some(model.importances(x))
+    }
+
+    fn tree_shap(
+        background: &DenseMatrix<f32>,
+        x: &DenseMatrix<f32>,
+        final_model: &Vec<u8>,
+    ) -> Option<Shap> {
+        let model: ExtraTrees = bincode::deserialize(&*final_model).unwrap();
+        Some(model.shap(background, x))
+    }
+}
@@ -1,32 +1,10 @@
-use smartcore::{
-    linalg::naive::dense_matrix::DenseMatrix, model_selection::cross_validate,
-    model_selection::CrossValidationResult, naive_bayes::gaussian::GaussianNB,
-};
+use smartcore::{linalg::naive::dense_matrix::DenseMatrix, naive_bayes::gaussian::GaussianNB};
 
-use crate::{Algorithm, Settings};
+use crate::Settings;
 
 pub(crate) struct GaussianNaiveBayesClassifierWrapper {}
 
 impl super::ModelWrapper for GaussianNaiveBayesClassifierWrapper {
-    fn cv(
-        x: &DenseMatrix<f32>,
-        y: &Vec<f32>,
-        settings: &Settings,
-    ) -> (CrossValidationResult<f32>, Algorithm) {
-        (
-            cross_validate(
-                GaussianNB::fit,
-                x,
-                y,
-                settings.gaussian_nb_settings.as_ref().unwrap().clone(),
-                settings.get_kfolds(),
-                settings.get_metric(),
-            )
-            .unwrap(),
-            Algorithm::GaussianNaiveBayes,
-        )
-    }
-
     fn train(x: &DenseMatrix<f32>, y: &Vec<f32>, settings: &Settings) -> Vec<u8> {
         bincode::serialize(
             &GaussianNB::fit(
@@ -0,0 +1,158 @@
+//! A generalized linear model with a log link, shared by the Poisson, gamma and Tweedie
+//! regressors. The family is a Tweedie distribution with the given power, so a power of one is
+//! Poisson and a power of two is gamma. The inputs are standardized, and the coefficients minimize
+//! half the mean deviance plus an L2 penalty on everything but the intercept. They are found by
+//! Newton steps with the expected Hessian, which is iteratively reweighted least squares, halving
+//! each step until the objective decreases.
+
+use smartcore::linalg::{naive::dense_matrix::DenseMatrix, BaseMatrix};
+
+use super::least_squares::{dot, solve, LinearPredictor};
+use crate::utils::tweedie_unit_deviance;
+
+/// How a model is fit
+pub(crate) struct Fitting {
+    /// The power of the Tweedie distribution
+    pub(crate) power: f32,
+    /// The strength of the L2 penalty
+    pub(crate) alpha: f32,
+    /// The maximum number of Newton steps
+    pub(crate) max_iter: usize,
+    /// The largest gradient entry at which the fit has converged
+    pub(crate) tol: f32,
+}
+
+/// A fitted model, which predicts the exponential of a linear function of the inputs
+#[derive(serde::Serialize, serde::Deserialize)]
+pub(crate) struct GeneralizedLinearModel {
+    /// The linear predictor, on the scale of the log of the mean
+    linear: LinearPredictor,
+}
+
+impl GeneralizedLinearModel {
+    /// Fit a model, or explain why the targets cannot come from the family
+    pub(crate) fn fit(x: &DenseMatrix<f32>, y: &[f32], fitting: &Fitting) -> Result<Self, String> {
+        check_family(fitting.power, y)?;
+        let (rows, width) = x.shape();
+        let (linear, inputs) = LinearPredictor::standardize(x);
+        let y: Vec<f64> = y.iter().map(|value| *value as f64).collect();
+        let (power, alpha) = (fitting.power as f64, fitting.alpha as f64);
+
+        // Start from the model that predicts the mean everywhere
+        let mut parameters = vec![0.0; width + 1];
+        parameters[0] = (y.iter().sum::<f64>() / rows as f64).ln();
+        let mut loss = objective(&inputs, &y, &parameters, power, alpha);
+
+        for _ in 0..fitting.max_iter {
+            let mut gradient = vec![0.0; width + 1];
+            let mut hessian = vec![vec![0.0; width + 1]; width + 1];
+            for (row, target) in inputs.iter().zip(&y) {
+                let mean = dot(row, &parameters).exp();
+                let residual = (mean - target) * mean.powf(1.0 - power);
+                let weight = mean.powf(2.0 - power);
+                for i in 0..=width {
+                    gradient[i] += residual * row[i] / rows as f64;
+                    for j in 0..=i {
+                        hessian[i][j] += weight * row[i] * row[j] / rows as f64;
+                    }
+                }
+            }
+            for i in 1..=width {
+                gradient[i] += alpha * parameters[i];
+                hessian[i][i] += alpha;
+            }
+            if gradient
+                .iter()
+                .all(|entry| entry.abs() <= fitting.tol as f64)
+            {
+                break;
+            }
+
+            let step = solve(hessian, &gradient);
+            let mut scale = 1.0;
+            let mut improved = false;
+            while scale > 1e-10 {
+                let candidate: Vec<f64> = parameters
+                    .iter()
+                    .zip(&step)
+                    .map(|(parameter, change)| parameter - scale * change)
+                    .collect();
+                let candidate_loss = objective(&inputs, &y, &candidate, power, alpha);
+                if candidate_loss <= loss {
+                    parameters = candidate;
+                    loss = candidate_loss;
+                    improved = true;
+                    break;
+                }
+                scale /= 2.0;
+            }
+            if !improved {
+                break;
+            }
+        }
+
+        if !parameters.iter().all(|parameter| parameter.is_finite()) {
+            return Err("The generalized linear model did not converge.".to_string());
+        }
+        Ok(Self {
+            linear: linear.with_parameters(&parameters),
+        })
+    }
+
+    /// Predict the mean of the target for each row
+    pub(crate) fn predict(&self, x: &DenseMatrix<f32>) -> Vec<f32> {
+        self.linear
+            .evaluate(x)
+            .into_iter()
+            .map(|value| value.exp() as f32)
+            .collect()
+    }
+}
+
+/// Check that the Tweedie power is supported and that the targets can come from its distribution,
+/// explaining why not. Poisson and compound Poisson targets must not be negative and must not all
+/// be zero, and gamma and heavier tailed targets must all be positive.
+pub(crate) fn check_family(power: f32, y: &[f32]) -> Result<(), String> {
+    if power.is_nan() || power < 1.0 {
+        Err(format!(
+            "The Tweedie power must be at least one, not {}.",
+            power
+        ))
+    } else if power < 2.0 {
+        if y.iter().all(|value| *value >= 0.0) && y.iter().any(|value| *value > 0.0) {
+            Ok(())
+        } else {
+            Err(format!(
+                "A Tweedie distribution with power {} needs non-negative targets, not all zero.",
+                power
+            ))
+        }
+    } else if !y.is_empty() && y.iter().all(|value| *value > 0.0) {
+        Ok(())
+    } else {
+        Err(format!(
+            "A Tweedie distribution with power {} needs positive targets.",
+            power
+        ))
+    }
+}
+
+/// Half the mean deviance plus the penalty, for a linear predictor given by the parameters
+fn objective(inputs: &[Vec<f64>], y: &[f64], parameters: &[f64], power: f64, alpha: f64) -> f64 {
+    let deviance = inputs
+        .iter()
+        .zip(y)
+        .map(|(row, target)| tweedie_unit_deviance(*target, dot(row, parameters).exp(), power))
+        .sum::<f64>()
+        / y.len() as f64;
+    let penalty = parameters[1..]
+        .iter()
+        .map(|value| value * value)
+        .sum::<f64>();
+    let loss = deviance / 2.0 + alpha * penalty / 2.0;
+    if loss.is_nan() {
+        f64::INFINITY
+    } else {
+        loss
+    }
+}
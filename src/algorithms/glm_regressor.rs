@@ -0,0 +1,102 @@
+//! Poisson, gamma and Tweedie regression with a log link
+
+use smartcore::linalg::naive::dense_matrix::DenseMatrix;
+
+use super::glm::{Fitting, GeneralizedLinearModel};
+use crate::{settings::GLMParameters, Settings};
+
+/// Fit a generalized linear model with a Tweedie distribution of the given power
+fn fit(
+    x: &DenseMatrix<f32>,
+    y: &[f32],
+    parameters: &GLMParameters,
+    power: f32,
+) -> Result<Vec<u8>, String> {
+    let fitting = Fitting {
+        power,
+        alpha: parameters.alpha,
+        max_iter: parameters.max_iter,
+        tol: parameters.tol,
+    };
+    GeneralizedLinearModel::fit(x, y, &fitting).map(|model| bincode::serialize(&model).unwrap())
+}
+
+/// Predict with a saved generalized linear model
+fn predict(x: &DenseMatrix<f32>, final_model: &[u8]) -> Vec<f32> {
+    let model: GeneralizedLinearModel = bincode::deserialize(final_model).unwrap();
+    model.predict(x)
+}
+
+/// The Poisson regressor, for counts
+pub(crate) struct PoissonRegressorWrapper {}
+
+impl super::ModelWrapper for PoissonRegressorWrapper {
+    fn train(x: &DenseMatrix<f32>, y: &Vec<f32>, settings: &Settings) -> Vec<u8> {
+        Self::try_train(x, y, settings).unwrap_or_else(|reason| panic!("{}", reason))
+    }
+
+    fn try_train(
+        x: &DenseMatrix<f32>,
+        y: &Vec<f32>,
+        settings: &Settings,
+    ) -> Result<Vec<u8>, String> {
+        fit(
+            x,
+            y,
+            settings.poisson_regressor_settings.as_ref().unwrap(),
+            1.0,
+        )
+    }
+
+    fn predict(x: &DenseMatrix<f32>, final_model: &Vec<u8>, _settings: &Settings) -> Vec<f32> {
+        predict(x, final_model)
+    }
+}
+
+/// The gamma regressor, for positive skewed amounts
+pub(crate) struct GammaRegressorWrapper {}
+
+impl super::ModelWrapper for GammaRegressorWrapper {
+    fn train(x: &DenseMatrix<f32>, y: &Vec<f32>, settings: &Settings) -> Vec<u8> {
+        Self::try_train(x, y, settings).unwrap_or_else(|reason| panic!("{}", reason))
+    }
+
+    fn try_train(
+        x: &DenseMatrix<f32>,
+        y: &Vec<f32>,
+        settings: &Settings,
+    ) -> Result<Vec<u8>, String> {
+        fit(
+            x,
+            y,
+            settings.gamma_regressor_settings.as_ref().unwrap(),
+            2.0,
+        )
+    }
+
+    fn predict(x: &DenseMatrix<f32>, final_model: &Vec<u8>, _settings: &Settings) -> Vec<f32> {
+        predict(x, final_model)
+    }
+}
+
+/// The Tweedie regressor, for amounts that are often exactly zero
+pub(crate) struct TweedieRegressorWrapper {}
+
+impl super::ModelWrapper for TweedieRegressorWrapper {
+    fn train(x: &DenseMatrix<f32>, y: &Vec<f32>, settings: &Settings) -> Vec<u8> {
+        Self::try_train(x, y, settings).unwrap_or_else(|reason| panic!("{}", reason))
+    }
+
+    fn try_train(
+        x: &DenseMatrix<f32>,
+        y: &Vec<f32>,
+        settings: &Settings,
+    ) -> Result<Vec<u8>, String> {
+        let parameters = settings.tweedie_regressor_settings.as_ref().unwrap();
+        fit(x, y, parameters, parameters.power)
+    }
+
+    fn predict(x: &DenseMatrix<f32>, final_model: &Vec<u8>, _settings: &Settings) -> Vec<f32> {
+        predict(x, final_model)
+    }
+}
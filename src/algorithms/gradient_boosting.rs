@@ -0,0 +1,406 @@
+//! Gradient boosting shared by the regressors and the classifier. Each round fits one smartcore
+//! regression tree per output to the negative gradient of the loss, and adds its predictions,
+//! scaled by the learning rate, to the raw scores. Regression has a single output with squared
+//! error loss, binary classification a single log-odds output with logistic loss, and
+//! classification with more classes one output per class with softmax loss. Quantile regression
+//! has a single output with pinball loss, whose gradient only says which side of the prediction
+//! each target is on, so the leaves of each tree are then reset to the quantile of the residuals
+//! that reach them.
+
+use rand::seq::SliceRandom;
+use smartcore::{
+    linalg::{naive::dense_matrix::DenseMatrix, BaseMatrix},
+    tree::decision_tree_regressor::{DecisionTreeRegressor, DecisionTreeRegressorParameters},
+};
+
+use super::tree_structure::RegressionTree;
+use crate::{
+    settings::GradientBoostingParameters,
+    utils::{mean_pinball_loss, quantile_of},
+};
+
+/// How the trees of a booster are grown
+pub(crate) struct Boosting {
+    /// How much each tree contributes to the scores
+    pub(crate) learning_rate: f32,
+    /// The maximum number of rounds
+    pub(crate) n_estimators: usize,
+    /// The parameters of each tree
+    pub(crate) tree: DecisionTreeRegressorParameters,
+    /// The fraction of the training rows each round is fit to
+    pub(crate) subsample: f32,
+    /// The fraction of rows held out for early stopping, if any
+    pub(crate) validation_fraction: Option<f32>,
+    /// The number of rounds without improvement before stopping early
+    pub(crate) n_iter_no_change: usize,
+    /// The quantile fit with pinball loss, or nothing for the other losses
+    pub(crate) quantile: Option<f32>,
+}
+
+impl Boosting {
+    /// Grow trees with the given parameters, fitting a quantile if one is given
+    pub(crate) fn new(parameters: &GradientBoostingParameters, quantile: Option<f32>) -> Self {
+        Self {
+            learning_rate: parameters.learning_rate,
+            n_estimators: parameters.n_estimators,
+            tree: DecisionTreeRegressorParameters::default()
+                .with_max_depth(parameters.max_depth)
+                .with_min_samples_leaf(parameters.min_samples_leaf)
+                .with_min_samples_split(parameters.min_samples_split),
+            subsample: parameters.subsample,
+            validation_fraction: parameters.validation_fraction,
+            n_iter_no_change: parameters.n_iter_no_change,
+            quantile,
+        }
+    }
+}
+
+/// A trained booster
+#[derive(serde::Serialize, serde::Deserialize)]
+pub(crate) struct GradientBoosting {
+    /// The sorted class labels, or nothing for regression
+    classes: Vec<f32>,
+    /// The starting score of each output
+    initial: Vec<f32>,
+    /// How much each tree contributes to the scores
+    learning_rate: f32,
+    /// The trees of each round, with one tree per output
+    trees: Vec<Vec<DecisionTreeRegressor<f32>>>,
+    /// The quantile fit with pinball loss, which is only needed while fitting
+    #[serde(skip)]
+    quantile: Option<f32>,
+}
+
+impl GradientBoosting {
+    /// Fit a booster to the mean of a continuous target, or to a quantile of it
+    pub(crate) fn fit_regressor(x: &DenseMatrix<f32>, y: &[f32], boosting: &Boosting) -> Self {
+        let targets = vec![y.to_vec()];
+        let initial = match boosting.quantile {
+            Some(quantile) => quantile_of(y.to_vec(), quantile),
+            None => y.iter().sum::<f32>() / y.len() as f32,
+        };
+        Self::fit(x, vec![], targets, vec![initial], boosting)
+    }
+
+    /// Fit a booster to class labels, which must include at least two classes
+    pub(crate) fn fit_classifier(
+        x: &DenseMatrix<f32>,
+        y: &[f32],
+        boosting: &Boosting,
+    ) -> Result<Self, String> {
+        let mut classes = y.to_vec();
+        classes.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        classes.dedup();
+        if classes.len() < 2 {
+            return Err("Gradient boosting needs at least two classes.".to_string());
+        }
+
+        // Binary problems have a single output for the second class, others one per class
+        let outputs: Vec<f32> = if classes.len() == 2 {
+            classes[1..].to_vec()
+        } else {
+            classes.clone()
+        };
+        let targets: Vec<Vec<f32>> = outputs
+            .iter()
+            .map(|class| {
+                y.iter()
+                    .map(|label| if label == class { 1.0 } else { 0.0 })
+                    .collect()
+            })
+            .collect();
+        let initial = targets
+            .iter()
+            .map(|target| {
+                let prior = (target.iter().sum::<f32>() / y.len() as f32).clamp(1e-6, 1.0 - 1e-6);
+                if classes.len() == 2 {
+                    (prior / (1.0 - prior)).ln()
+                } else {
+                    prior.ln()
+                }
+            })
+            .collect();
+        Ok(Self::fit(x, classes, targets, initial, boosting))
+    }
+
+    /// Predict continuous values, or class labels for a classifier
+    pub(crate) fn predict(&self, x: &DenseMatrix<f32>) -> Vec<f32> {
+        let (rows, _) = x.shape();
+        let mut scores = self.starting_scores(rows);
+        for round in &self.trees {
+            self.add_round(&mut scores, round, x);
+        }
+        match self.classes.len() {
+            0 => scores.remove(0),
+            2 => scores[0]
+                .iter()
+                .map(|score| self.classes[usize::from(*score > 0.0)])
+                .collect(),
+            _ => (0..rows)
+                .map(|row| {
+                    let mut best = 0;
+                    for (class, output) in scores.iter().enumerate() {
+                        if output[row] > scores[best][row] {
+                            best = class;
+                        }
+                    }
+                    self.classes[best]
+                })
+                .collect(),
+        }
+    }
+
+    /// The sorted class labels and the probability of each for each row, or `None` for regression
+    pub(crate) fn predict_proba(&self, x: &DenseMatrix<f32>) -> Option<super::ClassProbabilities> {
+        if self.classes.is_empty() {
+            return None;
+        }
+        let (rows, _) = x.shape();
+        let mut scores = self.starting_scores(rows);
+        for round in &self.trees {
+            self.add_round(&mut scores, round, x);
+        }
+        let outputs = self.transform(&scores);
+        let probabilities = (0..rows)
+            .map(|row| match outputs.len() {
+                1 => vec![1.0 - outputs[0][row], outputs[0][row]],
+                _ => outputs.iter().map(|output| output[row]).collect(),
+            })
+            .collect();
+        Some((self.classes.clone(), probabilities))
+    }
+
+    /// Grow trees on the negative gradient of the loss until the rounds run out or the validation
+    /// loss stops improving
+    fn fit(
+        x: &DenseMatrix<f32>,
+        classes: Vec<f32>,
+        targets: Vec<Vec<f32>>,
+        initial: Vec<f32>,
+        boosting: &Boosting,
+    ) -> Self {
+        let mut model = Self {
+            classes,
+            initial,
+            learning_rate: boosting.learning_rate,
+            trees: Vec::with_capacity(boosting.n_estimators),
+            quantile: boosting.quantile,
+        };
+
+        // Hold out a random set of rows for early stopping
+        let (rows, _) = x.shape();
+        let mut rng = rand::thread_rng();
+        let mut order: Vec<usize> = (0..rows).collect();
+        let held_out = match boosting.validation_fraction {
+            // Both sides of the split need at least one row
+            Some(fraction) if rows > 1 => {
+                order.shuffle(&mut rng);
+                ((rows as f32 * fraction) as usize).clamp(1, rows - 1)
+            }
+            _ => 0,
+        };
+        let (validation, training) = order.split_at(held_out);
+        let mut training = training.to_vec();
+        training.sort_unstable();
+        let train_x = x.take(&training, 0);
+        let train_targets = model.take_targets(&targets, &training);
+        let validation_x = x.take(validation, 0);
+        let validation_targets = model.take_targets(&targets, validation);
+
+        let mut train_scores = model.starting_scores(training.len());
+        let mut validation_scores = model.starting_scores(validation.len());
+        // At least one round is kept, even if the validation loss is never finite
+        let mut best = (f32::INFINITY, 1);
+        let sample_size = ((training.len() as f32 * boosting.subsample) as usize).max(1);
+        let mut sample: Vec<usize> = (0..training.len()).collect();
+
+        for round in 0..boosting.n_estimators {
+            // Fit each tree to a random sample of the training rows
+            sample.shuffle(&mut rng);
+            let mut chosen = sample[..sample_size].to_vec();
+            chosen.sort_unstable();
+            let sample_x = train_x.take(&chosen, 0);
+            let gradients = model.negative_gradients(&train_targets, &train_scores);
+            let trees: Vec<DecisionTreeRegressor<f32>> = gradients
+                .iter()
+                .map(|gradient| {
+                    let gradient = chosen.iter().map(|row| gradient[*row]).collect();
+                    let tree =
+                        DecisionTreeRegressor::fit(&sample_x, &gradient, boosting.tree.clone())
+                            .unwrap();
+                    match model.quantile {
+                        Some(quantile) => {
+                            let residuals: Vec<f32> = chosen
+                                .iter()
+                                .map(|row| train_targets[0][*row] - train_scores[0][*row])
+                                .collect();
+                            quantile_leaves(tree, &sample_x, &residuals, quantile)
+                        }
+                        None => tree,
+                    }
+                })
+                .collect();
+
+            model.add_round(&mut train_scores, &trees, &train_x);
+            model.trees.push(trees);
+
+            if held_out > 0 {
+                model.add_round(
+                    &mut validation_scores,
+                    model.trees.last().unwrap(),
+                    &validation_x,
+                );
+                let loss = model.loss(&validation_targets, &validation_scores);
+                if loss < best.0 {
+                    best = (loss, round + 1);
+                } else if round + 1 - best.1 >= boosting.n_iter_no_change {
+                    break;
+                }
+            }
+        }
+
+        if held_out > 0 {
+            model.trees.truncate(best.1);
+        }
+        model
+    }
+
+    /// Pick out some rows of each target
+    fn take_targets(&self, targets: &[Vec<f32>], rows: &[usize]) -> Vec<Vec<f32>> {
+        targets
+            .iter()
+            .map(|target| rows.iter().map(|row| target[*row]).collect())
+            .collect()
+    }
+
+    /// The scores of each output before any trees are added
+    fn starting_scores(&self, rows: usize) -> Vec<Vec<f32>> {
+        self.initial
+            .iter()
+            .map(|score| vec![*score; rows])
+            .collect()
+    }
+
+    /// Add the scaled predictions of one round of trees to the scores
+    fn add_round(
+        &self,
+        scores: &mut [Vec<f32>],
+        trees: &[DecisionTreeRegressor<f32>],
+        x: &DenseMatrix<f32>,
+    ) {
+        for (output, tree) in scores.iter_mut().zip(trees) {
+            for (score, step) in output.iter_mut().zip(tree.predict(x).unwrap()) {
+                *score += self.learning_rate * step;
+            }
+        }
+    }
+
+    /// Turn raw scores into predictions on the scale of the targets
+    fn transform(&self, scores: &[Vec<f32>]) -> Vec<Vec<f32>> {
+        match self.classes.len() {
+            0 => scores.to_vec(),
+            2 => vec![scores[0]
+                .iter()
+                .map(|score| 1.0 / (1.0 + (-score).exp()))
+                .collect()],
+            _ => {
+                let rows = scores[0].len();
+                let mut probabilities = vec![vec![0.0; rows]; scores.len()];
+                for row in 0..rows {
+                    let max = scores
+                        .iter()
+                        .map(|output| output[row])
+                        .fold(f32::NEG_INFINITY, f32::max);
+                    let total: f32 = scores.iter().map(|output| (output[row] - max).exp()).sum();
+                    for (class, output) in scores.iter().enumerate() {
+                        probabilities[class][row] = (output[row] - max).exp() / total;
+                    }
+                }
+                probabilities
+            }
+        }
+    }
+
+    /// The negative gradient of the loss, which for the squared error and cross entropy losses is
+    /// the target minus the prediction, and for the pinball loss the quantile for targets above
+    /// the prediction and the quantile minus one for those below
+    fn negative_gradients(&self, targets: &[Vec<f32>], scores: &[Vec<f32>]) -> Vec<Vec<f32>> {
+        if let Some(quantile) = self.quantile {
+            return vec![targets[0]
+                .iter()
+                .zip(&scores[0])
+                .map(|(target, score)| {
+                    if target < score {
+                        quantile - 1.0
+                    } else {
+                        quantile
+                    }
+                })
+                .collect()];
+        }
+        targets
+            .iter()
+            .zip(self.transform(scores))
+            .map(|(target, prediction)| {
+                target
+                    .iter()
+                    .zip(prediction)
+                    .map(|(target, prediction)| target - prediction)
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// The mean loss, which is the squared error or pinball loss for regression and the cross
+    /// entropy otherwise
+    fn loss(&self, targets: &[Vec<f32>], scores: &[Vec<f32>]) -> f32 {
+        if let Some(quantile) = self.quantile {
+            return mean_pinball_loss(&targets[0], &scores[0], quantile);
+        }
+        let rows = targets[0].len() as f32;
+        let predictions = self.transform(scores);
+        let total: f32 = match self.classes.len() {
+            0 => targets[0]
+                .iter()
+                .zip(&predictions[0])
+                .map(|(target, prediction)| (target - prediction).powi(2))
+                .sum(),
+            2 => targets[0]
+                .iter()
+                .zip(&predictions[0])
+                .map(|(target, probability)| {
+                    let probability = probability.clamp(1e-7, 1.0 - 1e-7);
+                    -(target * probability.ln() + (1.0 - target) * (1.0 - probability).ln())
+                })
+                .sum(),
+            _ => targets
+                .iter()
+                .zip(&predictions)
+                .flat_map(|(target, probabilities)| target.iter().zip(probabilities))
+                .map(|(target, probability)| -target * probability.max(1e-7).ln())
+                .sum(),
+        };
+        total / rows
+    }
+}
+
+/// Reset each leaf of a tree to the quantile of the residuals of the rows that reach it
+fn quantile_leaves(
+    tree: DecisionTreeRegressor<f32>,
+    x: &DenseMatrix<f32>,
+    residuals: &[f32],
+    quantile: f32,
+) -> DecisionTreeRegressor<f32> {
+    let mut structure: RegressionTree =
+        bincode::deserialize(&bincode::serialize(&tree).unwrap()).unwrap();
+    let mut reaching: Vec<Vec<f32>> = vec![vec![]; structure.size()];
+    for (leaf, residual) in structure.leaves(x).into_iter().zip(residuals) {
+        reaching[leaf].push(*residual);
+    }
+    for (node, residuals) in reaching.into_iter().enumerate() {
+        if !residuals.is_empty() {
+            structure.set_output(node, quantile_of(residuals, quantile));
+        }
+    }
+    bincode::deserialize(&bincode::serialize(&structure).unwrap()).unwrap()
+}
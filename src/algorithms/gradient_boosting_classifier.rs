@@ -0,0 +1,42 @@
+//! Gradient boosted tree classification, with logistic or softmax loss
+
+use smartcore::linalg::naive::dense_matrix::DenseMatrix;
+
+use super::gradient_boosting::{Boosting, GradientBoosting};
+use crate::Settings;
+
+/// The gradient boosted tree classifier
+pub(crate) struct GradientBoostingClassifierWrapper {}
+
+impl super::ModelWrapper for GradientBoostingClassifierWrapper {
+    fn train(x: &DenseMatrix<f32>, y: &Vec<f32>, settings: &Settings) -> Vec<u8> {
+        Self::try_train(x, y, settings).unwrap_or_else(|reason| panic!("{}", reason))
+    }
+
+    fn try_train(
+        x: &DenseMatrix<f32>,
+        y: &Vec<f32>,
+        settings: &Settings,
+    ) -> Result<Vec<u8>, String> {
+        let parameters = settings
+            .gradient_boosting_classifier_settings
+            .as_ref()
+            .unwrap();
+        let boosting = Boosting::new(parameters, None);
+        GradientBoosting::fit_classifier(x, y, &boosting)
+            .map(|model| bincode::serialize(&model).unwrap())
+    }
+
+    fn predict(x: &DenseMatrix<f32>, final_model: &Vec<u8>, _settings: &Settings) -> Vec<f32> {
+        let model: GradientBoosting = bincode::deserialize(&*final_model).unwrap();
+        model.predict(x)
+    }
+
+    fn predict_proba(
+        x: &DenseMatrix<f32>,
+        final_model: &Vec<u8>,
+    ) -> Option<super::ClassProbabilities> {
+        let model: GradientBoosting = bincode::deserialize(final_model).unwrap();
+        model.predict_proba(x)
+    }
+}
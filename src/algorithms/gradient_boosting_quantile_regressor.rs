@@ -0,0 +1,54 @@
+//! Gradient boosted tree regression of a quantile, with pinball loss
+
+use smartcore::linalg::naive::dense_matrix::DenseMatrix;
+
+use super::gradient_boosting::{Boosting, GradientBoosting};
+use super::quantile_levels::QuantileLevels;
+use crate::{interval::IntervalError, Settings};
+
+/// The gradient boosted tree quantile regressor
+pub(crate) struct GradientBoostingQuantileRegressorWrapper {}
+
+impl super::ModelWrapper for GradientBoostingQuantileRegressorWrapper {
+    fn train(x: &DenseMatrix<f32>, y: &Vec<f32>, settings: &Settings) -> Vec<u8> {
+        let parameters = settings
+            .gradient_boosting_quantile_regressor_settings
+            .as_ref()
+            .unwrap();
+        let boosting = Boosting::new(&parameters.boosting, Some(parameters.quantile));
+        bincode::serialize(&GradientBoosting::fit_regressor(x, y, &boosting)).unwrap()
+    }
+
+    fn predict(x: &DenseMatrix<f32>, final_model: &Vec<u8>, _settings: &Settings) -> Vec<f32> {
+        let model: GradientBoosting = bincode::deserialize(final_model).unwrap();
+        model.predict(x)
+    }
+
+    fn train_quantiles(
+        x: &DenseMatrix<f32>,
+        y: &Vec<f32>,
+        _final_model: &Vec<u8>,
+        settings: &Settings,
+    ) -> Option<Vec<u8>> {
+        let parameters = settings
+            .gradient_boosting_quantile_regressor_settings
+            .as_ref()?;
+        let models = QuantileLevels::fit(|level| {
+            GradientBoosting::fit_regressor(x, y, &Boosting::new(&parameters.boosting, Some(level)))
+        });
+        Some(bincode::serialize(&models).unwrap())
+    }
+
+    fn predict_quantiles(
+        x: &DenseMatrix<f32>,
+        _final_model: &Vec<u8>,
+        quantile_models: &Vec<u8>,
+        quantiles: &[f32],
+    ) -> Result<Option<Vec<Vec<f32>>>, IntervalError> {
+        let models: QuantileLevels<GradientBoosting> =
+            bincode::deserialize(quantile_models).unwrap();
+        models
+            .predict(quantiles, |model| model.predict(x))
+            .map(Some)
+    }
+}
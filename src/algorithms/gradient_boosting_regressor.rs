@@ -0,0 +1,25 @@
+//! Gradient boosted tree regression, with squared error loss
+
+use smartcore::linalg::naive::dense_matrix::DenseMatrix;
+
+use super::gradient_boosting::{Boosting, GradientBoosting};
+use crate::Settings;
+
+/// The gradient boosted tree regressor
+pub(crate) struct GradientBoostingRegressorWrapper {}
+
+impl super::ModelWrapper for GradientBoostingRegressorWrapper {
+    fn train(x: &DenseMatrix<f32>, y: &Vec<f32>, settings: &Settings) -> Vec<u8> {
+        let parameters = settings
+            .gradient_boosting_regressor_settings
+            .as_ref()
+            .unwrap();
+        let boosting = Boosting::new(parameters, None);
+        bincode::serialize(&GradientBoosting::fit_regressor(x, y, &boosting)).unwrap()
+    }
+
+    fn predict(x: &DenseMatrix<f32>, final_model: &Vec<u8>, _settings: &Settings) -> Vec<f32> {
+        let model: GradientBoosting = bincode::deserialize(&*final_model).unwrap();
+        model.predict(x)
+    }
+}
@@ -4,164 +4,16 @@ use smartcore::{
         euclidian::Euclidian, hamming::Hamming, mahalanobis::Mahalanobis, manhattan::Manhattan,
         minkowski::Minkowski, Distances,
     },
-    model_selection::cross_validate,
     neighbors::knn_classifier::{
         KNNClassifier, KNNClassifierParameters as SmartcoreKNNClassifierParameters,
     },
 };
 
-use crate::{Algorithm, Distance, Settings};
-use smartcore::model_selection::CrossValidationResult;
+use crate::{Distance, Settings};
 
 pub(crate) struct KNNClassifierWrapper {}
 
 impl super::ModelWrapper for KNNClassifierWrapper {
-    fn cv(
-        x: &DenseMatrix<f32>,
-        y: &Vec<f32>,
-        settings: &Settings,
-    ) -> (CrossValidationResult<f32>, Algorithm) {
-        let cv = match settings.knn_classifier_settings.as_ref().unwrap().distance {
-            Distance::Euclidean => cross_validate(
-                KNNClassifier::fit,
-                x,
-                y,
-                SmartcoreKNNClassifierParameters::default()
-                    .with_k(settings.knn_classifier_settings.as_ref().unwrap().k)
-                    .with_weight(
-                        settings
-                            .knn_classifier_settings
-                            .as_ref()
-                            .unwrap()
-                            .weight
-                            .clone(),
-                    )
-                    .with_algorithm(
-                        settings
-                            .knn_classifier_settings
-                            .as_ref()
-                            .unwrap()
-                            .algorithm
-                            .clone(),
-                    )
-                    .with_distance(Distances::euclidian()),
-                settings.get_kfolds(),
-                settings.get_metric(),
-            )
-            .unwrap(),
-            Distance::Manhattan => cross_validate(
-                KNNClassifier::fit,
-                x,
-                y,
-                SmartcoreKNNClassifierParameters::default()
-                    .with_k(settings.knn_classifier_settings.as_ref().unwrap().k)
-                    .with_weight(
-                        settings
-                            .knn_classifier_settings
-                            .as_ref()
-                            .unwrap()
-                            .weight
-                            .clone(),
-                    )
-                    .with_algorithm(
-                        settings
-                            .knn_classifier_settings
-                            .as_ref()
-                            .unwrap()
-                            .algorithm
-                            .clone(),
-                    )
-                    .with_distance(Distances::manhattan()),
-                settings.get_kfolds(),
-                settings.get_metric(),
-            )
-            .unwrap(),
-            Distance::Minkowski(p) => cross_validate(
-                KNNClassifier::fit,
-                x,
-                y,
-                SmartcoreKNNClassifierParameters::default()
-                    .with_k(settings.knn_classifier_settings.as_ref().unwrap().k)
-                    .with_weight(
-                        settings
-                            .knn_classifier_settings
-                            .as_ref()
-                            .unwrap()
-                            .weight
-                            .clone(),
-                    )
-                    .with_algorithm(
-                        settings
-                            .knn_classifier_settings
-                            .as_ref()
-                            .unwrap()
-                            .algorithm
-                            .clone(),
-                    )
-                    .with_distance(Distances::minkowski(p)),
-                settings.get_kfolds(),
-                settings.get_metric(),
-            )
-            .unwrap(),
-            Distance::Mahalanobis => cross_validate(
-                KNNClassifier::fit,
-                x,
-                y,
-                SmartcoreKNNClassifierParameters::default()
-                    .with_k(settings.knn_classifier_settings.as_ref().unwrap().k)
-                    .with_weight(
-                        settings
-                            .knn_classifier_settings
-                            .as_ref()
-                            .unwrap()
-                            .weight
-                            .clone(),
-                    )
-                    .with_algorithm(
-                        settings
-                            .knn_classifier_settings
-                            .as_ref()
-                            .unwrap()
-                            .algorithm
-                            .clone(),
-                    )
-                    .with_distance(Distances::mahalanobis(x)),
-                settings.get_kfolds(),
-                settings.get_metric(),
-            )
-            .unwrap(),
-            Distance::Hamming => cross_validate(
-                KNNClassifier::fit,
-                x,
-                y,
-                SmartcoreKNNClassifierParameters::default()
-                    .with_k(settings.knn_classifier_settings.as_ref().unwrap().k)
-                    .with_weight(
-                        settings
-                            .knn_classifier_settings
-                            .as_ref()
-                            .unwrap()
-                            .weight
-                            .clone(),
-                    )
-                    .with_algorithm(
-                        settings
-                            .knn_classifier_settings
-                            .as_ref()
-                            .unwrap()
-                            .algorithm
-                            .clone(),
-                    )
-                    .with_distance(Distances::hamming()),
-                settings.get_kfolds(),
-                settings.get_metric(),
-            )
-            .unwrap(),
-        };
-
-        (cv, Algorithm::KNNClassifier)
-    }
-
     fn train(x: &DenseMatrix<f32>, y: &Vec<f32>, settings: &Settings) -> Vec<u8> {
         match settings.knn_classifier_settings.as_ref().unwrap().distance {
             Distance::Euclidean => {
@@ -0,0 +1,111 @@
+//! Shared pieces of the linear models fit in this crate by Newton steps or by iteratively
+//! reweighted least squares. The inputs are standardized before fitting, and the systems solved
+//! along the way have one row per feature, so small dense solvers are enough.
+
+use smartcore::linalg::{naive::dense_matrix::DenseMatrix, BaseMatrix};
+
+/// A linear function of the standardized inputs
+#[derive(serde::Serialize, serde::Deserialize)]
+pub(super) struct LinearPredictor {
+    /// The mean of each input
+    x_mean: Vec<f64>,
+    /// The standard deviation of each input
+    x_scale: Vec<f64>,
+    /// The intercept
+    intercept: f64,
+    /// The coefficient of each standardized input
+    coefficients: Vec<f64>,
+}
+
+impl LinearPredictor {
+    /// Standardize the inputs, giving each row with a leading one for the intercept and a
+    /// predictor that is zero until its parameters are set
+    pub(super) fn standardize(x: &DenseMatrix<f32>) -> (Self, Vec<Vec<f64>>) {
+        let (rows, width) = x.shape();
+        let (x_mean, x_scale): (Vec<f64>, Vec<f64>) = (0..width)
+            .map(|column| standardization((0..rows).map(|row| x.get(row, column) as f64)))
+            .unzip();
+        let predictor = Self {
+            x_mean,
+            x_scale,
+            intercept: 0.0,
+            coefficients: vec![0.0; width],
+        };
+        let inputs = (0..rows)
+            .map(|row| {
+                std::iter::once(1.0)
+                    .chain((0..width).map(|column| predictor.standardized(x, row, column)))
+                    .collect()
+            })
+            .collect();
+        (predictor, inputs)
+    }
+
+    /// Set the intercept and coefficients, given with the intercept first
+    pub(super) fn with_parameters(mut self, parameters: &[f64]) -> Self {
+        self.intercept = parameters[0];
+        self.coefficients = parameters[1..].to_vec();
+        self
+    }
+
+    /// The value of the linear function for each row
+    pub(super) fn evaluate(&self, x: &DenseMatrix<f32>) -> Vec<f64> {
+        let (rows, width) = x.shape();
+        (0..rows)
+            .map(|row| {
+                self.intercept
+                    + (0..width)
+                        .map(|column| self.coefficients[column] * self.standardized(x, row, column))
+                        .sum::<f64>()
+            })
+            .collect()
+    }
+
+    /// One standardized input
+    fn standardized(&self, x: &DenseMatrix<f32>, row: usize, column: usize) -> f64 {
+        (x.get(row, column) as f64 - self.x_mean[column]) / self.x_scale[column]
+    }
+}
+
+/// The mean and standard deviation of a column, with a scale of one for constant columns
+fn standardization(values: impl Iterator<Item = f64> + Clone) -> (f64, f64) {
+    let n = values.clone().count() as f64;
+    let mean = values.clone().sum::<f64>() / n;
+    let variance = values.map(|value| (value - mean).powi(2)).sum::<f64>() / n;
+    let scale = if variance > 0.0 { variance.sqrt() } else { 1.0 };
+    (mean, scale)
+}
+
+/// The dot product of two vectors
+pub(super) fn dot(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(a, b)| a * b).sum()
+}
+
+/// Solve a symmetric positive definite system, given by its lower triangle, with a Cholesky
+/// factorization. A small ridge keeps the factorization stable when columns are constant.
+pub(super) fn solve(mut matrix: Vec<Vec<f64>>, vector: &[f64]) -> Vec<f64> {
+    let size = vector.len();
+    let jitter = 1e-10 * (1.0 + (0..size).map(|i| matrix[i][i]).sum::<f64>() / size as f64);
+    for i in 0..size {
+        for j in 0..=i {
+            let sum = matrix[i][j] + if i == j { jitter } else { 0.0 }
+                - dot(&matrix[i][..j], &matrix[j][..j]);
+            matrix[i][j] = if i == j {
+                sum.max(jitter).sqrt()
+            } else {
+                sum / matrix[j][j]
+            };
+        }
+    }
+    let mut solution = vector.to_vec();
+    for i in 0..size {
+        solution[i] = (solution[i] - dot(&matrix[i][..i], &solution[..i])) / matrix[i][i];
+    }
+    for i in (0..size).rev() {
+        let known = (i + 1..size)
+            .map(|k| matrix[k][i] * solution[k])
+            .sum::<f64>();
+        solution[i] = (solution[i] - known) / matrix[i][i];
+    }
+    solution
+}
@@ -1,32 +1,12 @@
 use smartcore::{
     linalg::naive::dense_matrix::DenseMatrix, linear::linear_regression::LinearRegression,
-    model_selection::cross_validate, model_selection::CrossValidationResult,
 };
 
-use crate::{Algorithm, Settings};
+use crate::Settings;
 
 pub(crate) struct LinearRegressorWrapper {}
 
 impl super::ModelWrapper for LinearRegressorWrapper {
-    fn cv(
-        x: &DenseMatrix<f32>,
-        y: &Vec<f32>,
-        settings: &Settings,
-    ) -> (CrossValidationResult<f32>, Algorithm) {
-        (
-            cross_validate(
-                LinearRegression::fit,
-                x,
-                y,
-                settings.linear_settings.as_ref().unwrap().clone(),
-                settings.get_kfolds(),
-                settings.get_metric(),
-            )
-            .unwrap(),
-            Algorithm::Linear,
-        )
-    }
-
     fn train(x: &DenseMatrix<f32>, y: &Vec<f32>, settings: &Settings) -> Vec<u8> {
         bincode::serialize(
             &LinearRegression::fit(x, y, settings.linear_settings.as_ref().unwrap().clone())
@@ -40,4 +20,13 @@ impl super::ModelWrapper for LinearRegressorWrapper {
             bincode::deserialize(&*final_model).unwrap();
         model.predict(x).unwrap()
     }
+
+    fn coefficients(final_model: &Vec<u8>) -> Option<(Vec<Vec<f32>>, Vec<f32>)> {
+        let model: LinearRegression<f32, DenseMatrix<f32>> =
+            bincode::deserialize(&*final_model).unwrap();
+        Some((
+            super::column_to_row(model.coefficients()),
+            vec![model.intercept()],
+        ))
+    }
 }
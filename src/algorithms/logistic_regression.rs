@@ -1,31 +1,28 @@
-use crate::{Algorithm, Settings};
+use crate::Settings;
 use smartcore::{
-    linalg::naive::dense_matrix::DenseMatrix, linear::logistic_regression::LogisticRegression,
-    model_selection::cross_validate, model_selection::CrossValidationResult,
+    linalg::{naive::dense_matrix::DenseMatrix, BaseMatrix},
+    linear::logistic_regression::LogisticRegression,
 };
 
 pub(crate) struct LogisticRegressionWrapper {}
 
-impl super::ModelWrapper for LogisticRegressionWrapper {
-    fn cv(
-        x: &DenseMatrix<f32>,
-        y: &Vec<f32>,
-        settings: &Settings,
-    ) -> (CrossValidationResult<f32>, Algorithm) {
-        (
-            cross_validate(
-                LogisticRegression::fit,
-                x,
-                y,
-                settings.logistic_settings.as_ref().unwrap().clone(),
-                settings.get_kfolds(),
-                settings.get_metric(),
-            )
-            .unwrap(),
-            Algorithm::LogisticRegression,
-        )
-    }
+/// Mirror of `smartcore::linear::logistic_regression::LogisticRegression<f32, DenseMatrix<f32>>`,
+/// whose class labels are private
+#[derive(serde::Deserialize)]
+struct LogisticMirror {
+    /// One row of coefficients for a binary model, or one per class
+    coefficients: DenseMatrix<f32>,
+    /// One intercept for a binary model, or one per class
+    intercept: DenseMatrix<f32>,
+    /// Sorted class labels
+    classes: Vec<f32>,
+    /// Number of features
+    _num_attributes: usize,
+    /// Number of classes
+    _num_classes: usize,
+}
 
+impl super::ModelWrapper for LogisticRegressionWrapper {
     fn train(x: &DenseMatrix<f32>, y: &Vec<f32>, settings: &Settings) -> Vec<u8> {
         bincode::serialize(
             &LogisticRegression::fit(x, y, settings.logistic_settings.as_ref().unwrap().clone())
@@ -39,4 +36,48 @@ impl super::ModelWrapper for LogisticRegressionWrapper {
             bincode::deserialize(&*final_model).unwrap();
         model.predict(x).unwrap()
     }
+
+    fn coefficients(final_model: &Vec<u8>) -> Option<(Vec<Vec<f32>>, Vec<f32>)> {
+        let model: LogisticRegression<f32, DenseMatrix<f32>> =
+            bincode::deserialize(&*final_model).unwrap();
+        let (number_of_rows, _) = model.coefficients().shape();
+        Some((
+            (0..number_of_rows)
+                .map(|row| model.coefficients().get_row_as_vec(row))
+                .collect(),
+            model.intercept().get_col_as_vec(0),
+        ))
+    }
+
+    fn predict_proba(
+        x: &DenseMatrix<f32>,
+        final_model: &Vec<u8>,
+    ) -> Option<super::ClassProbabilities> {
+        let model: LogisticMirror = bincode::deserialize(final_model).unwrap();
+        let (rows, _) = x.shape();
+        let (outputs, width) = model.coefficients.shape();
+        let scores = |row: usize, output: usize| {
+            (0..width)
+                .map(|column| x.get(row, column) * model.coefficients.get(output, column))
+                .sum::<f32>()
+                + model.intercept.get(output, 0)
+        };
+        let probabilities = (0..rows)
+            .map(|row| {
+                if model.classes.len() == 2 {
+                    let probability = 1.0 / (1.0 + (-scores(row, 0)).exp());
+                    vec![1.0 - probability, probability]
+                } else {
+                    let scores: Vec<f32> = (0..outputs).map(|output| scores(row, output)).collect();
+                    let max = scores.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+                    let total: f32 = scores.iter().map(|score| (score - max).exp()).sum();
+                    scores
+                        .iter()
+                        .map(|score| (score - max).exp() / total)
+                        .collect()
+                }
+            })
+            .collect();
+        Some((model.classes, probabilities))
+    }
 }
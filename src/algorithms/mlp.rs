@@ -0,0 +1,533 @@
+//! A multilayer perceptron shared by the regressor and the classifier. The inputs are
+//! standardized, and then pass through fully connected hidden layers with the chosen activation
+//! and a linear output layer. Regression has one output, fit to the standardized target with
+//! squared error loss, and classification one output per class with softmax loss. The weights
+//! are trained on shuffled minibatches with an L2 penalty, and training stops once the loss has
+//! not improved for a number of passes over the data.
+
+use rand::{seq::SliceRandom, Rng};
+use smartcore::linalg::{naive::dense_matrix::DenseMatrix, BaseMatrix};
+
+use crate::utils::{Activation, Optimizer};
+
+/// How a network is trained
+pub(crate) struct Training {
+    /// The number of neurons in each hidden layer
+    pub(crate) hidden_layers: Vec<usize>,
+    /// The activation of the hidden layers
+    pub(crate) activation: Activation,
+    /// The rule used to update the weights
+    pub(crate) optimizer: Optimizer,
+    /// The step size of the optimizer
+    pub(crate) learning_rate: f32,
+    /// The strength of the L2 penalty
+    pub(crate) alpha: f32,
+    /// The number of rows in each minibatch, if set
+    pub(crate) batch_size: Option<usize>,
+    /// The maximum number of passes over the training rows
+    pub(crate) max_iter: usize,
+    /// The smallest decrease in loss that counts as an improvement
+    pub(crate) tol: f32,
+    /// The fraction of rows held out to judge improvement, if any
+    pub(crate) validation_fraction: Option<f32>,
+    /// The number of passes without improvement before stopping early
+    pub(crate) n_iter_no_change: usize,
+}
+
+/// A trained network
+#[derive(serde::Serialize, serde::Deserialize)]
+pub(crate) struct MultilayerPerceptron {
+    /// The sorted class labels, or nothing for regression
+    classes: Vec<f32>,
+    /// The activation of the hidden layers
+    activation: Activation,
+    /// The mean of each input
+    x_mean: Vec<f32>,
+    /// The standard deviation of each input
+    x_scale: Vec<f32>,
+    /// The mean of the target, for regression
+    y_mean: f32,
+    /// The standard deviation of the target, for regression
+    y_scale: f32,
+    /// The hidden layers followed by the output layer
+    layers: Vec<Layer>,
+}
+
+/// A fully connected layer
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+struct Layer {
+    /// The number of inputs
+    inputs: usize,
+    /// The weights from each input, one row of inputs per output
+    weights: Vec<f32>,
+    /// The bias of each output
+    biases: Vec<f32>,
+}
+
+impl MultilayerPerceptron {
+    /// Train a network for a continuous target
+    pub(crate) fn fit_regressor(x: &DenseMatrix<f32>, y: &[f32], training: &Training) -> Self {
+        let (y_mean, y_scale) = standardization(y);
+        let targets = y.iter().map(|value| (value - y_mean) / y_scale).collect();
+        let mut model = Self::new(x, vec![], y_mean, y_scale, training);
+        model.fit(x, targets, 1, training);
+        model
+    }
+
+    /// Train a network for class labels
+    pub(crate) fn fit_classifier(x: &DenseMatrix<f32>, y: &[f32], training: &Training) -> Self {
+        let mut classes = y.to_vec();
+        classes.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        classes.dedup();
+        let outputs = classes.len();
+        let mut targets = vec![0.0; y.len() * outputs];
+        for (row, label) in y.iter().enumerate() {
+            let class = classes.iter().position(|class| class == label).unwrap();
+            targets[row * outputs + class] = 1.0;
+        }
+        let mut model = Self::new(x, classes, 0.0, 1.0, training);
+        model.fit(x, targets, outputs, training);
+        model
+    }
+
+    /// Predict continuous values, or class labels for a classifier
+    pub(crate) fn predict(&self, x: &DenseMatrix<f32>) -> Vec<f32> {
+        let (rows, _) = x.shape();
+        let inputs = self.standardize(x, &(0..rows).collect::<Vec<usize>>());
+        let outputs = self.forward(inputs).pop().unwrap();
+        if self.classes.is_empty() {
+            outputs
+                .iter()
+                .map(|output| output * self.y_scale + self.y_mean)
+                .collect()
+        } else {
+            outputs
+                .chunks(self.classes.len())
+                .map(|scores| {
+                    let mut best = 0;
+                    for (class, score) in scores.iter().enumerate() {
+                        if *score > scores[best] {
+                            best = class;
+                        }
+                    }
+                    self.classes[best]
+                })
+                .collect()
+        }
+    }
+
+    /// The sorted class labels and the probability of each for each row, or `None` for regression
+    pub(crate) fn predict_proba(&self, x: &DenseMatrix<f32>) -> Option<super::ClassProbabilities> {
+        if self.classes.is_empty() {
+            return None;
+        }
+        let (rows, _) = x.shape();
+        let inputs = self.standardize(x, &(0..rows).collect::<Vec<usize>>());
+        let outputs = self.forward(inputs).pop().unwrap();
+        let probabilities = softmax(&outputs, self.classes.len())
+            .chunks(self.classes.len())
+            .map(|row| row.to_vec())
+            .collect();
+        Some((self.classes.clone(), probabilities))
+    }
+
+    /// Set up the scaling and randomly initialized layers of a network
+    fn new(
+        x: &DenseMatrix<f32>,
+        classes: Vec<f32>,
+        y_mean: f32,
+        y_scale: f32,
+        training: &Training,
+    ) -> Self {
+        let (_, width) = x.shape();
+        let (x_mean, x_scale) = (0..width)
+            .map(|column| standardization(&x.get_col_as_vec(column)))
+            .unzip();
+        let mut sizes = vec![width];
+        sizes.extend(&training.hidden_layers);
+        sizes.push(classes.len().max(1));
+
+        // Draw the weights uniformly with the scale suggested by Glorot and Bengio
+        let mut rng = rand::thread_rng();
+        let layers = sizes
+            .windows(2)
+            .map(|size| {
+                let (inputs, outputs) = (size[0], size[1]);
+                let factor = match training.activation {
+                    Activation::Logistic => 2.0,
+                    _ => 6.0,
+                };
+                let bound = (factor / (inputs + outputs) as f32).sqrt();
+                Layer {
+                    inputs,
+                    weights: (0..inputs * outputs)
+                        .map(|_| rng.gen_range(-bound..bound))
+                        .collect(),
+                    biases: (0..outputs).map(|_| rng.gen_range(-bound..bound)).collect(),
+                }
+            })
+            .collect();
+
+        Self {
+            classes,
+            activation: training.activation,
+            x_mean,
+            x_scale,
+            y_mean,
+            y_scale,
+            layers,
+        }
+    }
+
+    /// Train the weights on minibatches until the passes run out or the loss stops improving
+    fn fit(
+        &mut self,
+        x: &DenseMatrix<f32>,
+        targets: Vec<f32>,
+        outputs: usize,
+        training: &Training,
+    ) {
+        // Hold out a random set of rows to judge improvement
+        let (rows, _) = x.shape();
+        let mut rng = rand::thread_rng();
+        let mut order: Vec<usize> = (0..rows).collect();
+        let held_out = match training.validation_fraction {
+            // Both sides of the split need at least one row
+            Some(fraction) if rows > 1 => {
+                order.shuffle(&mut rng);
+                ((rows as f32 * fraction) as usize).clamp(1, rows - 1)
+            }
+            _ => 0,
+        };
+        let (validation, training_rows) = order.split_at(held_out);
+        let validation_x = self.standardize(x, validation);
+        let validation_targets = take_rows(&targets, outputs, validation);
+        let train_x = self.standardize(x, training_rows);
+        let train_targets = take_rows(&targets, outputs, training_rows);
+        let width = self.layers[0].inputs;
+
+        let batch_size = training
+            .batch_size
+            .unwrap_or(200)
+            .min(training_rows.len())
+            .max(1);
+        let mut optimizer = State::new(training.optimizer, &self.layers);
+        let mut best = (f32::INFINITY, 0, self.layers.clone());
+        let mut positions: Vec<usize> = (0..training_rows.len()).collect();
+
+        for pass in 0..training.max_iter {
+            positions.shuffle(&mut rng);
+            let mut total_loss = 0.0;
+            for batch in positions.chunks(batch_size) {
+                let batch_x = take_rows(&train_x, width, batch);
+                let batch_targets = take_rows(&train_targets, outputs, batch);
+                let (loss, gradients) = self.gradients(batch_x, &batch_targets, training.alpha);
+                total_loss += loss * batch.len() as f32;
+                optimizer.step(&mut self.layers, &gradients, training.learning_rate);
+            }
+
+            let loss = if held_out > 0 {
+                let predictions = self.forward(validation_x.clone()).pop().unwrap();
+                self.loss(&predictions, &validation_targets)
+            } else {
+                total_loss / training_rows.len() as f32
+            };
+            if !loss.is_finite() {
+                break;
+            }
+            if loss < best.0 - training.tol {
+                best = (loss, pass, self.layers.clone());
+            } else if pass - best.1 >= training.n_iter_no_change {
+                break;
+            }
+        }
+
+        // Judged on held out rows, the best pass is kept rather than the last one
+        if held_out > 0 && best.0.is_finite() {
+            self.layers = best.2;
+        }
+    }
+
+    /// Standardize some rows of the inputs, flattened row by row
+    fn standardize(&self, x: &DenseMatrix<f32>, rows: &[usize]) -> Vec<f32> {
+        let width = self.x_mean.len();
+        let mut inputs = Vec::with_capacity(rows.len() * width);
+        for row in rows {
+            for column in 0..width {
+                inputs.push((x.get(*row, column) - self.x_mean[column]) / self.x_scale[column]);
+            }
+        }
+        inputs
+    }
+
+    /// The values of every layer for a batch of standardized inputs, starting with the inputs and
+    /// ending with the raw outputs
+    fn forward(&self, inputs: Vec<f32>) -> Vec<Vec<f32>> {
+        let rows = inputs.len() / self.layers[0].inputs;
+        let mut values = vec![inputs];
+        for (depth, layer) in self.layers.iter().enumerate() {
+            let previous = values.last().unwrap();
+            let outputs = layer.biases.len();
+            let mut next = vec![0.0; rows * outputs];
+            for (row, input) in previous.chunks(layer.inputs).enumerate() {
+                for (output, weights) in layer.weights.chunks(layer.inputs).enumerate() {
+                    next[row * outputs + output] = dot(weights, input) + layer.biases[output];
+                }
+            }
+            if depth + 1 < self.layers.len() {
+                for value in next.iter_mut() {
+                    *value = activate(self.activation, *value);
+                }
+            }
+            values.push(next);
+        }
+        values
+    }
+
+    /// The mean loss of raw outputs, which is half the squared error for regression and the cross
+    /// entropy of the softmax otherwise
+    fn loss(&self, outputs: &[f32], targets: &[f32]) -> f32 {
+        if self.classes.is_empty() {
+            let total: f32 = outputs
+                .iter()
+                .zip(targets)
+                .map(|(output, target)| (output - target).powi(2))
+                .sum();
+            total / (2.0 * outputs.len() as f32)
+        } else {
+            let classes = self.classes.len();
+            let probabilities = softmax(outputs, classes);
+            let total: f32 = probabilities
+                .iter()
+                .zip(targets)
+                .map(|(probability, target)| -target * probability.max(1e-7).ln())
+                .sum();
+            total / (outputs.len() / classes) as f32
+        }
+    }
+
+    /// The loss of a batch and the gradient of the penalized loss with respect to the weights and
+    /// biases of each layer
+    fn gradients(&self, inputs: Vec<f32>, targets: &[f32], alpha: f32) -> (f32, Vec<Layer>) {
+        let mut values = self.forward(inputs);
+        let outputs = values.pop().unwrap();
+        let loss = self.loss(&outputs, targets);
+        let rows = (values[0].len() / self.layers[0].inputs) as f32;
+
+        // Both losses have the prediction minus the target as the gradient of the raw outputs
+        let predictions = if self.classes.is_empty() {
+            outputs
+        } else {
+            softmax(&outputs, self.classes.len())
+        };
+        let mut deltas: Vec<f32> = predictions
+            .iter()
+            .zip(targets)
+            .map(|(prediction, target)| (prediction - target) / rows)
+            .collect();
+
+        let mut gradients = Vec::with_capacity(self.layers.len());
+        for (depth, (layer, previous)) in self.layers.iter().zip(&values).enumerate().rev() {
+            let outputs = layer.biases.len();
+            let mut gradient = Layer {
+                inputs: layer.inputs,
+                weights: layer.weights.iter().map(|w| alpha * w / rows).collect(),
+                biases: vec![0.0; outputs],
+            };
+            for (delta, input) in deltas.chunks(outputs).zip(previous.chunks(layer.inputs)) {
+                for ((d, bias), weights) in delta
+                    .iter()
+                    .zip(gradient.biases.iter_mut())
+                    .zip(gradient.weights.chunks_mut(layer.inputs))
+                {
+                    *bias += d;
+                    for (g, x) in weights.iter_mut().zip(input) {
+                        *g += d * x;
+                    }
+                }
+            }
+
+            // The inputs have no weights of their own, so they need no deltas
+            if depth > 0 {
+                let mut backward = vec![0.0; previous.len()];
+                for (delta, back) in deltas
+                    .chunks(outputs)
+                    .zip(backward.chunks_mut(layer.inputs))
+                {
+                    for (d, weights) in delta.iter().zip(layer.weights.chunks(layer.inputs)) {
+                        for (b, w) in back.iter_mut().zip(weights) {
+                            *b += d * w;
+                        }
+                    }
+                }
+                for (back, value) in backward.iter_mut().zip(previous) {
+                    *back *= derivative(self.activation, *value);
+                }
+                deltas = backward;
+            }
+            gradients.push(gradient);
+        }
+        gradients.reverse();
+        (loss, gradients)
+    }
+}
+
+/// The state of an optimizer, with one entry per layer
+struct State {
+    /// The rule used to update the weights
+    optimizer: Optimizer,
+    /// The velocity for gradient descent with momentum, or the first moment for Adam
+    first: Vec<Layer>,
+    /// The second moment for Adam
+    second: Vec<Layer>,
+    /// The number of steps taken
+    steps: i32,
+}
+
+impl State {
+    /// Start with every moment at zero
+    fn new(optimizer: Optimizer, layers: &[Layer]) -> Self {
+        let zeros: Vec<Layer> = layers
+            .iter()
+            .map(|layer| Layer {
+                inputs: layer.inputs,
+                weights: vec![0.0; layer.weights.len()],
+                biases: vec![0.0; layer.biases.len()],
+            })
+            .collect();
+        Self {
+            optimizer,
+            first: zeros.clone(),
+            second: zeros,
+            steps: 0,
+        }
+    }
+
+    /// Update the weights and biases of every layer along their gradients
+    fn step(&mut self, layers: &mut [Layer], gradients: &[Layer], learning_rate: f32) {
+        self.steps += 1;
+        let step_size = match self.optimizer {
+            Optimizer::SGD(_) => learning_rate,
+            Optimizer::Adam => {
+                learning_rate * (1.0 - 0.999f32.powi(self.steps)).sqrt()
+                    / (1.0 - 0.9f32.powi(self.steps))
+            }
+        };
+        for (((layer, gradient), first), second) in layers
+            .iter_mut()
+            .zip(gradients)
+            .zip(&mut self.first)
+            .zip(&mut self.second)
+        {
+            for (parameters, gradients, first, second) in [
+                (
+                    &mut layer.weights,
+                    &gradient.weights,
+                    &mut first.weights,
+                    &mut second.weights,
+                ),
+                (
+                    &mut layer.biases,
+                    &gradient.biases,
+                    &mut first.biases,
+                    &mut second.biases,
+                ),
+            ] {
+                for (((parameter, gradient), first), second) in parameters
+                    .iter_mut()
+                    .zip(gradients)
+                    .zip(first.iter_mut())
+                    .zip(second.iter_mut())
+                {
+                    match self.optimizer {
+                        Optimizer::SGD(momentum) => {
+                            *first = momentum * *first - step_size * gradient;
+                            *parameter += *first;
+                        }
+                        Optimizer::Adam => {
+                            *first = 0.9 * *first + 0.1 * gradient;
+                            *second = 0.999 * *second + 0.001 * gradient * gradient;
+                            *parameter -= step_size * *first / (second.sqrt() + 1e-8);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// The mean and standard deviation of some values, with a scale of one for constant values
+fn standardization(values: &[f32]) -> (f32, f32) {
+    let n = values.len() as f64;
+    let mean = values.iter().map(|value| *value as f64).sum::<f64>() / n;
+    let variance = values
+        .iter()
+        .map(|value| (*value as f64 - mean).powi(2))
+        .sum::<f64>()
+        / n;
+    let scale = if variance > 0.0 { variance.sqrt() } else { 1.0 };
+    (mean as f32, scale as f32)
+}
+
+/// Pick out some rows of a matrix flattened row by row
+fn take_rows(values: &[f32], width: usize, rows: &[usize]) -> Vec<f32> {
+    rows.iter()
+        .flat_map(|row| &values[row * width..(row + 1) * width])
+        .copied()
+        .collect()
+}
+
+/// The dot product of two vectors, summed in eight lanes so that it can be vectorized
+fn dot(a: &[f32], b: &[f32]) -> f32 {
+    let mut lanes = [0.0; 8];
+    let (a_chunks, b_chunks) = (a.chunks_exact(8), b.chunks_exact(8));
+    let tail: f32 = a_chunks
+        .remainder()
+        .iter()
+        .zip(b_chunks.remainder())
+        .map(|(a, b)| a * b)
+        .sum();
+    for (a, b) in a_chunks.zip(b_chunks) {
+        for lane in 0..8 {
+            lanes[lane] += a[lane] * b[lane];
+        }
+    }
+    lanes.iter().sum::<f32>() + tail
+}
+
+/// Apply an activation function
+fn activate(activation: Activation, value: f32) -> f32 {
+    match activation {
+        Activation::Identity => value,
+        Activation::Logistic => 1.0 / (1.0 + (-value).exp()),
+        Activation::Tanh => value.tanh(),
+        Activation::ReLU => value.max(0.0),
+    }
+}
+
+/// The derivative of an activation function, given its output
+fn derivative(activation: Activation, output: f32) -> f32 {
+    match activation {
+        Activation::Identity => 1.0,
+        Activation::Logistic => output * (1.0 - output),
+        Activation::Tanh => 1.0 - output * output,
+        Activation::ReLU => {
+            if output > 0.0 {
+                1.0
+            } else {
+                0.0
+            }
+        }
+    }
+}
+
+/// Turn raw outputs, flattened row by row, into class probabilities
+fn softmax(outputs: &[f32], classes: usize) -> Vec<f32> {
+    let mut probabilities = Vec::with_capacity(outputs.len());
+    for scores in outputs.chunks(classes) {
+        let max = scores.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+        let total: f32 = scores.iter().map(|score| (score - max).exp()).sum();
+        probabilities.extend(scores.iter().map(|score| (score - max).exp() / total));
+    }
+    probabilities
+}
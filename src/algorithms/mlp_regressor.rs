@@ -0,0 +1,33 @@
+//! Multilayer perceptron regression
+
+use smartcore::linalg::naive::dense_matrix::DenseMatrix;
+
+use super::mlp::{MultilayerPerceptron, Training};
+use crate::Settings;
+
+/// The multilayer perceptron regressor
+pub(crate) struct MLPRegressorWrapper {}
+
+impl super::ModelWrapper for MLPRegressorWrapper {
+    fn train(x: &DenseMatrix<f32>, y: &Vec<f32>, settings: &Settings) -> Vec<u8> {
+        let parameters = settings.mlp_regressor_settings.as_ref().unwrap();
+        let training = Training {
+            hidden_layers: parameters.hidden_layers.clone(),
+            activation: parameters.activation,
+            optimizer: parameters.optimizer,
+            learning_rate: parameters.learning_rate,
+            alpha: parameters.alpha,
+            batch_size: parameters.batch_size,
+            max_iter: parameters.max_iter,
+            tol: parameters.tol,
+            validation_fraction: parameters.validation_fraction,
+            n_iter_no_change: parameters.n_iter_no_change,
+        };
+        bincode::serialize(&MultilayerPerceptron::fit_regressor(x, y, &training)).unwrap()
+    }
+
+    fn predict(x: &DenseMatrix<f32>, final_model: &Vec<u8>, _settings: &Settings) -> Vec<f32> {
+        let model: MultilayerPerceptron = bincode::deserialize(&*final_model).unwrap();
+        model.predict(x)
+    }
+}
@@ -38,48 +38,229 @@ mod categorical_naive_bayes_classifier;
 pub(crate) use categorical_naive_bayes_classifier::CategoricalNaiveBayesClassifierWrapper;
 
 mod support_vector_classifier;
-pub(crate) use support_vector_classifier::SupportVectorClassifierWrapper;
+pub(crate) use support_vector_classifier::{tag_svc, SupportVectorClassifierWrapper};
 
 mod support_vector_regressor;
 pub(crate) use support_vector_regressor::SupportVectorRegressorWrapper;
 
-use crate::{Algorithm, Settings};
-use smartcore::linalg::naive::dense_matrix::DenseMatrix;
-use smartcore::model_selection::CrossValidationResult;
+mod gradient_boosting;
+
+mod gradient_boosting_regressor;
+pub(crate) use gradient_boosting_regressor::GradientBoostingRegressorWrapper;
+
+mod gradient_boosting_classifier;
+pub(crate) use gradient_boosting_classifier::GradientBoostingClassifierWrapper;
+
+mod extra_trees;
+
+mod extra_trees_regressor;
+pub(crate) use extra_trees_regressor::ExtraTreesRegressorWrapper;
+
+mod extra_trees_classifier;
+pub(crate) use extra_trees_classifier::ExtraTreesClassifierWrapper;
+
+mod mlp;
+
+mod mlp_regressor;
+pub(crate) use mlp_regressor::MLPRegressorWrapper;
+
+mod mlp_classifier;
+pub(crate) use mlp_classifier::MLPClassifierWrapper;
+
+mod least_squares;
+
+mod glm;
+pub(crate) use glm::check_family;
+
+mod glm_regressor;
+pub(crate) use glm_regressor::{
+    GammaRegressorWrapper, PoissonRegressorWrapper, TweedieRegressorWrapper,
+};
+
+mod quantile_levels;
+
+mod quantile_regressor;
+pub(crate) use quantile_regressor::QuantileRegressorWrapper;
+
+mod gradient_boosting_quantile_regressor;
+pub(crate) use gradient_boosting_quantile_regressor::GradientBoostingQuantileRegressorWrapper;
+
+mod tree_structure;
+pub(crate) use tree_structure::Shap;
+
+/// The sorted class labels, and the probability of each label for each row
+pub(crate) type ClassProbabilities = (Vec<f32>, Vec<Vec<f32>>);
+
+use crate::{
+    interval::IntervalError,
+    progress::{Monitor, Status},
+    Algorithm, Model, Settings,
+};
+use smartcore::linalg::{naive::dense_matrix::DenseMatrix, BaseMatrix, BaseVector};
+use smartcore::model_selection::{BaseKFold, CrossValidationResult};
 
 use crate::settings::FinalModel;
-use std::time::{Duration, Instant};
+use std::time::Instant;
 
 pub trait ModelWrapper {
     fn cv_model(
+        algorithm: Algorithm,
         x: &DenseMatrix<f32>,
         y: &Vec<f32>,
         settings: &Settings,
-    ) -> (CrossValidationResult<f32>, Algorithm, Duration, Vec<u8>) {
+        monitor: &mut Monitor,
+    ) -> Model {
         let start = Instant::now();
-        let results = Self::cv(x, y, settings);
+        let (score, status) = Self::cv(algorithm, x, y, settings, monitor);
         let end = Instant::now();
-        (
-            results.0,
-            results.1,
-            end.duration_since(start),
-            match settings.final_model_approach {
+        Model {
+            score,
+            name: algorithm,
+            duration: end.duration_since(start),
+            model: match settings.final_model_approach {
                 FinalModel::None => vec![],
+                _ if status != Status::Completed => vec![],
                 _ => Self::train(x, y, settings),
             },
-        )
+            status,
+            interval_models: vec![],
+        }
     }
 
-    // Perform cross-validation
+    /// Perform cross-validation, reporting the scores of each fold as it finishes and stopping
+    /// early if the monitor says so
     fn cv(
+        algorithm: Algorithm,
         x: &DenseMatrix<f32>,
         y: &Vec<f32>,
         settings: &Settings,
-    ) -> (CrossValidationResult<f32>, Algorithm);
+        monitor: &mut Monitor,
+    ) -> (CrossValidationResult<f32>, Status) {
+        let kfolds = settings.get_kfolds();
+        let number_of_folds = kfolds.n_splits();
+        let metric = settings.get_metric();
+        let mut test_score = Vec::with_capacity(number_of_folds);
+        let mut train_score = Vec::with_capacity(number_of_folds);
+
+        let mut status = Status::Completed;
+
+        for (fold, (train_idx, test_idx)) in kfolds.split(x).enumerate() {
+            if fold > 0 {
+                if let Some(reason) = monitor.algorithm_interruption() {
+                    status = Status::CutShort(reason);
+                    break;
+                }
+            }
+
+            let train_x = x.take(&train_idx, 0);
+            let train_y = y.take(&train_idx);
+            let test_x = x.take(&test_idx, 0);
+            let test_y = y.take(&test_idx);
+
+            let model = match Self::try_train(&train_x, &train_y, settings) {
+                Ok(model) => model,
+                Err(reason) => {
+                    status = Status::Failed(reason);
+                    break;
+                }
+            };
+            train_score.push((*metric)(
+                &train_y,
+                &Self::predict(&train_x, &model, settings),
+            ));
+            test_score.push((*metric)(
+                &test_y,
+                &Self::predict(&test_x, &model, settings),
+            ));
+
+            monitor.fold_end(
+                algorithm,
+                fold + 1,
+                number_of_folds,
+                train_score[fold],
+                test_score[fold],
+            );
+        }
 
-    // Train a model
+        (
+            CrossValidationResult {
+                test_score,
+                train_score,
+            },
+            status,
+        )
+    }
+
+    /// Train a model
     fn train(x: &DenseMatrix<f32>, y: &Vec<f32>, settings: &Settings) -> Vec<u8>;
 
-    // Perform a prediction
+    /// Train a model, or explain why it cannot be fit to the data instead of panicking, so that
+    /// cross-validation can record the failure and move on to the next algorithm
+    fn try_train(
+        x: &DenseMatrix<f32>,
+        y: &Vec<f32>,
+        settings: &Settings,
+    ) -> Result<Vec<u8>, String> {
+        Ok(Self::train(x, y, settings))
+    }
+
+    /// Perform a prediction
     fn predict(x: &DenseMatrix<f32>, final_model: &Vec<u8>, settings: &Settings) -> Vec<f32>;
+
+    /// Get the coefficients of a linear model, with one row per class, and the intercepts
+    fn coefficients(_final_model: &Vec<u8>) -> Option<(Vec<Vec<f32>>, Vec<f32>)> {
+        None
+    }
+
+    /// Get the impurity-based importance of each feature for every tree in a tree model, using the
+    /// training data to count how many samples reach each node
+    fn impurity_importances(
+        _x: &DenseMatrix<f32>,
+        _final_model: &Vec<u8>,
+    ) -> Option<Vec<Vec<f32>>> {
+        None
+    }
+
+    /// Get exact TreeSHAP values for each row of x from a tree model, counting how many background
+    /// samples reach each node
+    fn tree_shap(
+        _background: &DenseMatrix<f32>,
+        _x: &DenseMatrix<f32>,
+        _final_model: &Vec<u8>,
+    ) -> Option<tree_structure::Shap> {
+        None
+    }
+
+    /// Predict the probability of each class for each row of x from a classifier that can, with
+    /// one row of probabilities per row of x, along with the sorted class labels they belong to
+    fn predict_proba(_x: &DenseMatrix<f32>, _final_model: &Vec<u8>) -> Option<ClassProbabilities> {
+        None
+    }
+
+    /// Train the models behind prediction intervals on all of the training data, if the algorithm
+    /// can predict quantiles of the target
+    fn train_quantiles(
+        _x: &DenseMatrix<f32>,
+        _y: &Vec<f32>,
+        _final_model: &Vec<u8>,
+        _settings: &Settings,
+    ) -> Option<Vec<u8>> {
+        None
+    }
+
+    /// Predict several quantiles of the target for each row of x from the models trained by
+    /// `train_quantiles`, with one row of predictions per quantile
+    fn predict_quantiles(
+        _x: &DenseMatrix<f32>,
+        _final_model: &Vec<u8>,
+        _quantile_models: &Vec<u8>,
+        _quantiles: &[f32],
+    ) -> Result<Option<Vec<Vec<f32>>>, IntervalError> {
+        Ok(None)
+    }
+}
+
+/// Split a column of coefficients into a single row
+fn column_to_row(coefficients: &DenseMatrix<f32>) -> Vec<Vec<f32>> {
+    vec![coefficients.get_col_as_vec(0)]
 }
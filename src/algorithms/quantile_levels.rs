@@ -0,0 +1,72 @@
+//! Quantile loss models fit once at a fixed set of quantiles, so that prediction intervals can
+//! interpolate between them without refitting the model or keeping the training data
+
+use crate::interval::IntervalError;
+
+/// The quantiles that quantile loss models are fit at for prediction intervals, which cover every
+/// interval up to a coverage of 0.95
+pub(super) const LEVELS: [f32; 9] = [0.025, 0.05, 0.1, 0.25, 0.5, 0.75, 0.9, 0.95, 0.975];
+
+/// Requested quantiles this close to the fitted range are clamped to it, so that rounding in the
+/// bounds of an interval does not put them just outside
+const TOLERANCE: f32 = 1e-5;
+
+/// A model fit at each of the levels, in increasing order of quantile
+#[derive(serde::Serialize, serde::Deserialize)]
+pub(super) struct QuantileLevels<M> {
+    /// The quantile of each model, with the model
+    models: Vec<(f32, M)>,
+}
+
+impl<M> QuantileLevels<M> {
+    /// Fit a model at every level
+    pub(super) fn fit(fit: impl Fn(f32) -> M) -> Self {
+        Self {
+            models: LEVELS.iter().map(|level| (*level, fit(*level))).collect(),
+        }
+    }
+
+    /// Predict several quantiles, with one row of predictions per quantile, interpolating linearly
+    /// between the models at the levels on either side of each quantile
+    pub(super) fn predict(
+        &self,
+        quantiles: &[f32],
+        predict: impl Fn(&M) -> Vec<f32>,
+    ) -> Result<Vec<Vec<f32>>, IntervalError> {
+        let lowest = self.models[0].0;
+        let highest = self.models[self.models.len() - 1].0;
+        let mut predictions: Vec<Option<Vec<f32>>> = vec![None; self.models.len()];
+        let mut level_predictions = |index: usize| -> Vec<f32> {
+            predictions[index]
+                .get_or_insert_with(|| predict(&self.models[index].1))
+                .clone()
+        };
+
+        quantiles
+            .iter()
+            .map(|quantile| {
+                if *quantile < lowest - TOLERANCE || *quantile > highest + TOLERANCE {
+                    return Err(IntervalError::OutOfRange {
+                        quantile: *quantile,
+                        lowest,
+                        highest,
+                    });
+                }
+                let quantile = quantile.clamp(lowest, highest);
+                let upper = self
+                    .models
+                    .iter()
+                    .position(|(level, _)| *level >= quantile)
+                    .unwrap_or(self.models.len() - 1)
+                    .max(1);
+                let (below, above) = (self.models[upper - 1].0, self.models[upper].0);
+                let weight = ((quantile - below) / (above - below)).clamp(0.0, 1.0);
+                Ok(level_predictions(upper - 1)
+                    .into_iter()
+                    .zip(level_predictions(upper))
+                    .map(|(low, high)| low + weight * (high - low))
+                    .collect())
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,132 @@
+//! Linear quantile regression, which fits a quantile of the target instead of its mean by
+//! minimizing the pinball loss plus an L2 penalty on the coefficients of the standardized inputs.
+//! The pinball loss is not smooth, so it is minimized by iteratively reweighted least squares,
+//! weighting each row by the slope of the loss divided by the size of its residual.
+
+use smartcore::linalg::naive::dense_matrix::DenseMatrix;
+
+use super::least_squares::{dot, solve, LinearPredictor};
+use super::quantile_levels::QuantileLevels;
+use crate::{
+    interval::IntervalError, settings::QuantileRegressorParameters, utils::quantile_of, Settings,
+};
+
+/// A fitted linear quantile model
+#[derive(serde::Serialize, serde::Deserialize)]
+pub(crate) struct LinearQuantileModel {
+    /// The linear function that predicts the quantile
+    linear: LinearPredictor,
+}
+
+impl LinearQuantileModel {
+    /// Fit a model of the given quantile of the target
+    pub(crate) fn fit(
+        x: &DenseMatrix<f32>,
+        y: &[f32],
+        parameters: &QuantileRegressorParameters,
+        quantile: f32,
+    ) -> Self {
+        let (linear, inputs) = LinearPredictor::standardize(x);
+        let width = inputs.first().map_or(1, |row| row.len());
+        let rows = y.len() as f64;
+        let (quantile, alpha) = (quantile as f64, parameters.alpha as f64);
+
+        // Residuals smaller than this are treated as this size, so that no weight is infinite
+        let epsilon = 1e-6 * (1.0 + y.iter().map(|value| value.abs() as f64).sum::<f64>() / rows);
+
+        // Start from the model that predicts the quantile of the target everywhere
+        let mut estimate = vec![0.0; width];
+        estimate[0] = quantile_of(y.to_vec(), quantile as f32) as f64;
+
+        for _ in 0..parameters.max_iter {
+            let mut matrix = vec![vec![0.0; width]; width];
+            let mut vector = vec![0.0; width];
+            for (row, target) in inputs.iter().zip(y) {
+                let target = *target as f64;
+                let residual = target - dot(row, &estimate);
+                let slope = if residual < 0.0 {
+                    1.0 - quantile
+                } else {
+                    quantile
+                };
+                let weight = slope / residual.abs().max(epsilon) / rows;
+                for i in 0..width {
+                    vector[i] += weight * row[i] * target;
+                    for j in 0..=i {
+                        matrix[i][j] += weight * row[i] * row[j];
+                    }
+                }
+            }
+            for (i, row) in matrix.iter_mut().enumerate().skip(1) {
+                row[i] += alpha;
+            }
+
+            let next = solve(matrix, &vector);
+            let largest = estimate.iter().fold(1.0_f64, |a, b| a.max(b.abs()));
+            let change = next
+                .iter()
+                .zip(&estimate)
+                .fold(0.0_f64, |a, (new, old)| a.max((new - old).abs()));
+            if !next.iter().all(|parameter| parameter.is_finite()) {
+                break;
+            }
+            estimate = next;
+            if change <= parameters.tol as f64 * largest {
+                break;
+            }
+        }
+
+        Self {
+            linear: linear.with_parameters(&estimate),
+        }
+    }
+
+    /// Predict the quantile for each row
+    pub(crate) fn predict(&self, x: &DenseMatrix<f32>) -> Vec<f32> {
+        self.linear
+            .evaluate(x)
+            .into_iter()
+            .map(|value| value as f32)
+            .collect()
+    }
+}
+
+/// The linear quantile regressor
+pub(crate) struct QuantileRegressorWrapper {}
+
+impl super::ModelWrapper for QuantileRegressorWrapper {
+    fn train(x: &DenseMatrix<f32>, y: &Vec<f32>, settings: &Settings) -> Vec<u8> {
+        let parameters = settings.quantile_regressor_settings.as_ref().unwrap();
+        let model = LinearQuantileModel::fit(x, y, parameters, parameters.quantile);
+        bincode::serialize(&model).unwrap()
+    }
+
+    fn predict(x: &DenseMatrix<f32>, final_model: &Vec<u8>, _settings: &Settings) -> Vec<f32> {
+        let model: LinearQuantileModel = bincode::deserialize(final_model).unwrap();
+        model.predict(x)
+    }
+
+    fn train_quantiles(
+        x: &DenseMatrix<f32>,
+        y: &Vec<f32>,
+        _final_model: &Vec<u8>,
+        settings: &Settings,
+    ) -> Option<Vec<u8>> {
+        let parameters = settings.quantile_regressor_settings.as_ref()?;
+        let models = QuantileLevels::fit(|level| LinearQuantileModel::fit(x, y, parameters, level));
+        Some(bincode::serialize(&models).unwrap())
+    }
+
+    fn predict_quantiles(
+        x: &DenseMatrix<f32>,
+        _final_model: &Vec<u8>,
+        quantile_models: &Vec<u8>,
+        quantiles: &[f32],
+    ) -> Result<Option<Vec<Vec<f32>>>, IntervalError> {
+        let models: QuantileLevels<LinearQuantileModel> =
+            bincode::deserialize(quantile_models).unwrap();
+        models
+            .predict(quantiles, |model| model.predict(x))
+            .map(Some)
+    }
+}
@@ -1,37 +1,14 @@
 use smartcore::{
     ensemble::random_forest_classifier::RandomForestClassifier,
-    linalg::naive::dense_matrix::DenseMatrix, model_selection::cross_validate,
-    model_selection::CrossValidationResult,
+    linalg::naive::dense_matrix::DenseMatrix,
 };
 
-use crate::{Algorithm, Settings};
+use super::tree_structure::{ClassificationForest, Shap};
+use crate::Settings;
 
 pub(crate) struct RandomForestClassifierWrapper {}
 
 impl super::ModelWrapper for RandomForestClassifierWrapper {
-    fn cv(
-        x: &DenseMatrix<f32>,
-        y: &Vec<f32>,
-        settings: &Settings,
-    ) -> (CrossValidationResult<f32>, Algorithm) {
-        (
-            cross_validate(
-                RandomForestClassifier::fit,
-                x,
-                y,
-                settings
-                    .random_forest_classifier_settings
-                    .as_ref()
-                    .unwrap()
-                    .clone(),
-                settings.get_kfolds(),
-                settings.get_metric(),
-            )
-            .unwrap(),
-            Algorithm::RandomForestClassifier,
-        )
-    }
-
     fn train(x: &DenseMatrix<f32>, y: &Vec<f32>, settings: &Settings) -> Vec<u8> {
         bincode::serialize(
             &RandomForestClassifier::fit(
@@ -52,4 +29,32 @@ impl super::ModelWrapper for RandomForestClassifierWrapper {
         let model: RandomForestClassifier<f32> = bincode::deserialize(&*final_model).unwrap();
         model.predict(x).unwrap()
     }
+
+    fn predict_proba(
+        x: &DenseMatrix<f32>,
+        final_model: &Vec<u8>,
+    ) -> Option<super::ClassProbabilities> {
+        let forest: ClassificationForest = bincode::deserialize(final_model).unwrap();
+        Some(forest.predict_proba(x))
+    }
+
+    fn impurity_importances(x: &DenseMatrix<f32>, final_model: &Vec<u8>) -> Option<Vec<Vec<f32>>> {
+        let forest: ClassificationForest = bincode::deserialize(&*final_model).unwrap();
+        Some(
+            forest
+                .trees
+                .iter()
+                .map(|tree| tree.importances(x))
+                .collect(),
+        )
+    }
+
+    fn tree_shap(
+        background: &DenseMatrix<f32>,
+        x: &DenseMatrix<f32>,
+        final_model: &Vec<u8>,
+    ) -> Option<Shap> {
+        let forest: ClassificationForest = bincode::deserialize(&*final_model).unwrap();
+        Some(forest.shap(background, x))
+    }
 }
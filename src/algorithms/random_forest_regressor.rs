@@ -1,37 +1,14 @@
 use smartcore::{
     ensemble::random_forest_regressor::RandomForestRegressor,
-    linalg::naive::dense_matrix::DenseMatrix, model_selection::cross_validate,
-    model_selection::CrossValidationResult,
+    linalg::naive::dense_matrix::DenseMatrix,
 };
 
-use crate::{Algorithm, Settings};
+use super::tree_structure::{LeafTargets, RegressionForest, Shap};
+use crate::{interval::IntervalError, Settings};
 
 pub(crate) struct RandomForestRegressorWrapper {}
 
 impl super::ModelWrapper for RandomForestRegressorWrapper {
-    fn cv(
-        x: &DenseMatrix<f32>,
-        y: &Vec<f32>,
-        settings: &Settings,
-    ) -> (CrossValidationResult<f32>, Algorithm) {
-        (
-            cross_validate(
-                RandomForestRegressor::fit,
-                x,
-                y,
-                settings
-                    .random_forest_regressor_settings
-                    .as_ref()
-                    .unwrap()
-                    .clone(),
-                settings.get_kfolds(),
-                settings.get_metric(),
-            )
-            .unwrap(),
-            Algorithm::RandomForestRegressor,
-        )
-    }
-
     fn train(x: &DenseMatrix<f32>, y: &Vec<f32>, settings: &Settings) -> Vec<u8> {
         bincode::serialize(
             &RandomForestRegressor::fit(
@@ -52,4 +29,45 @@ impl super::ModelWrapper for RandomForestRegressorWrapper {
         let model: RandomForestRegressor<f32> = bincode::deserialize(&*final_model).unwrap();
         model.predict(x).unwrap()
     }
+
+    fn impurity_importances(x: &DenseMatrix<f32>, final_model: &Vec<u8>) -> Option<Vec<Vec<f32>>> {
+        let forest: RegressionForest = bincode::deserialize(&*final_model).unwrap();
+        Some(
+            forest
+                .trees
+                .iter()
+                .map(|tree| tree.importances(x))
+                .collect(),
+        )
+    }
+
+    fn tree_shap(
+        background: &DenseMatrix<f32>,
+        x: &DenseMatrix<f32>,
+        final_model: &Vec<u8>,
+    ) -> Option<Shap> {
+        let forest: RegressionForest = bincode::deserialize(&*final_model).unwrap();
+        Some(forest.shap(background, x))
+    }
+
+    fn train_quantiles(
+        x: &DenseMatrix<f32>,
+        y: &Vec<f32>,
+        final_model: &Vec<u8>,
+        _settings: &Settings,
+    ) -> Option<Vec<u8>> {
+        let forest: RegressionForest = bincode::deserialize(final_model).unwrap();
+        Some(bincode::serialize(&forest.leaf_targets(x, y)).unwrap())
+    }
+
+    fn predict_quantiles(
+        x: &DenseMatrix<f32>,
+        final_model: &Vec<u8>,
+        quantile_models: &Vec<u8>,
+        quantiles: &[f32],
+    ) -> Result<Option<Vec<Vec<f32>>>, IntervalError> {
+        let forest: RegressionForest = bincode::deserialize(final_model).unwrap();
+        let leaf_targets: LeafTargets = bincode::deserialize(quantile_models).unwrap();
+        Ok(Some(forest.quantiles(&leaf_targets, x, quantiles)))
+    }
 }
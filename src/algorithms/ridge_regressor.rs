@@ -1,32 +1,12 @@
 use smartcore::{
     linalg::naive::dense_matrix::DenseMatrix, linear::ridge_regression::RidgeRegression,
-    model_selection::cross_validate, model_selection::CrossValidationResult,
 };
 
-use crate::{Algorithm, Settings};
+use crate::Settings;
 
 pub(crate) struct RidgeRegressorWrapper {}
 
 impl super::ModelWrapper for RidgeRegressorWrapper {
-    fn cv(
-        x: &DenseMatrix<f32>,
-        y: &Vec<f32>,
-        settings: &Settings,
-    ) -> (CrossValidationResult<f32>, Algorithm) {
-        (
-            cross_validate(
-                RidgeRegression::fit,
-                x,
-                y,
-                settings.ridge_settings.as_ref().unwrap().clone(),
-                settings.get_kfolds(),
-                settings.get_metric(),
-            )
-            .unwrap(),
-            Algorithm::Ridge,
-        )
-    }
-
     fn train(x: &DenseMatrix<f32>, y: &Vec<f32>, settings: &Settings) -> Vec<u8> {
         bincode::serialize(
             &RidgeRegression::fit(x, y, settings.ridge_settings.as_ref().unwrap().clone()).unwrap(),
@@ -39,4 +19,13 @@ impl super::ModelWrapper for RidgeRegressorWrapper {
             bincode::deserialize(&*final_model).unwrap();
         model.predict(x).unwrap()
     }
+
+    fn coefficients(final_model: &Vec<u8>) -> Option<(Vec<Vec<f32>>, Vec<f32>)> {
+        let model: RidgeRegression<f32, DenseMatrix<f32>> =
+            bincode::deserialize(&*final_model).unwrap();
+        Some((
+            super::column_to_row(model.coefficients()),
+            vec![model.intercept()],
+        ))
+    }
 }
@@ -1,143 +1,258 @@
+//! Smartcore's SVC only separates two classes, so problems with more classes are split into
+//! binary problems using the strategy in the settings. Every model is saved as a [`SavedSVC`],
+//! which holds either a plain smartcore model for a binary problem or a [`MulticlassSVC`] holding
+//! every binary model.
+
+use serde::{de::DeserializeOwned, Serialize};
 use smartcore::{
-    linalg::naive::dense_matrix::DenseMatrix,
-    model_selection::cross_validate,
-    model_selection::CrossValidationResult,
+    linalg::{naive::dense_matrix::DenseMatrix, BaseMatrix},
     svm::{
         svc::{SVCParameters as SmartcoreSVCParameters, SVC},
-        Kernels, LinearKernel, PolynomialKernel, RBFKernel, SigmoidKernel,
+        Kernel as SmartcoreKernel, Kernels, LinearKernel, PolynomialKernel, RBFKernel,
+        SigmoidKernel,
     },
 };
 
-use crate::{Algorithm, Kernel, Settings};
+use crate::{settings::MulticlassStrategy, Algorithm, Kernel, Settings};
 
 pub(crate) struct SupportVectorClassifierWrapper {}
 
 impl super::ModelWrapper for SupportVectorClassifierWrapper {
-    fn cv(
-        x: &DenseMatrix<f32>,
-        y: &Vec<f32>,
-        settings: &Settings,
-    ) -> (CrossValidationResult<f32>, Algorithm) {
-        let cv = match settings.svc_settings.as_ref().unwrap().kernel {
-            Kernel::Linear => cross_validate(
-                SVC::fit,
-                x,
-                y,
-                SmartcoreSVCParameters::default()
-                    .with_tol(settings.svc_settings.as_ref().unwrap().tol)
-                    .with_c(settings.svc_settings.as_ref().unwrap().c)
-                    .with_epoch(settings.svc_settings.as_ref().unwrap().epoch)
-                    .with_kernel(Kernels::linear()),
-                settings.get_kfolds(),
-                settings.get_metric(),
-            )
-            .unwrap(),
-            Kernel::Polynomial(degree, gamma, coef) => cross_validate(
-                SVC::fit,
-                x,
-                y,
-                SmartcoreSVCParameters::default()
-                    .with_tol(settings.svc_settings.as_ref().unwrap().tol)
-                    .with_c(settings.svc_settings.as_ref().unwrap().c)
-                    .with_epoch(settings.svc_settings.as_ref().unwrap().epoch)
-                    .with_kernel(Kernels::polynomial(degree, gamma, coef)),
-                settings.get_kfolds(),
-                settings.get_metric(),
-            )
-            .unwrap(),
-            Kernel::RBF(gamma) => cross_validate(
-                SVC::fit,
-                x,
-                y,
-                SmartcoreSVCParameters::default()
-                    .with_tol(settings.svc_settings.as_ref().unwrap().tol)
-                    .with_c(settings.svc_settings.as_ref().unwrap().c)
-                    .with_epoch(settings.svc_settings.as_ref().unwrap().epoch)
-                    .with_kernel(Kernels::rbf(gamma)),
-                settings.get_kfolds(),
-                settings.get_metric(),
-            )
-            .unwrap(),
-            Kernel::Sigmoid(gamma, coef) => cross_validate(
-                SVC::fit,
-                x,
-                y,
-                SmartcoreSVCParameters::default()
-                    .with_tol(settings.svc_settings.as_ref().unwrap().tol)
-                    .with_c(settings.svc_settings.as_ref().unwrap().c)
-                    .with_epoch(settings.svc_settings.as_ref().unwrap().epoch)
-                    .with_kernel(Kernels::sigmoid(gamma, coef)),
-                settings.get_kfolds(),
-                settings.get_metric(),
-            )
-            .unwrap(),
-        };
-        (cv, Algorithm::SVC)
-    }
-
     fn train(x: &DenseMatrix<f32>, y: &Vec<f32>, settings: &Settings) -> Vec<u8> {
-        match settings.svc_settings.as_ref().unwrap().kernel {
+        let svc_settings = settings.svc_settings.as_ref().unwrap();
+        let strategy = svc_settings.strategy;
+        match svc_settings.kernel {
             Kernel::Linear => {
                 let params = SmartcoreSVCParameters::default()
-                    .with_tol(settings.svc_settings.as_ref().unwrap().tol)
-                    .with_c(settings.svc_settings.as_ref().unwrap().c)
-                    .with_epoch(settings.svc_settings.as_ref().unwrap().epoch)
+                    .with_tol(svc_settings.tol)
+                    .with_c(svc_settings.c)
+                    .with_epoch(svc_settings.epoch)
                     .with_kernel(Kernels::linear());
 
-                bincode::serialize(&SVC::fit(x, y, params).unwrap()).unwrap()
+                fit(x, y, params, strategy)
             }
             Kernel::Polynomial(degree, gamma, coef) => {
                 let params = SmartcoreSVCParameters::default()
-                    .with_tol(settings.svc_settings.as_ref().unwrap().tol)
-                    .with_c(settings.svc_settings.as_ref().unwrap().c)
-                    .with_epoch(settings.svc_settings.as_ref().unwrap().epoch)
+                    .with_tol(svc_settings.tol)
+                    .with_c(svc_settings.c)
+                    .with_epoch(svc_settings.epoch)
                     .with_kernel(Kernels::polynomial(degree, gamma, coef));
 
-                bincode::serialize(&SVC::fit(x, y, params).unwrap()).unwrap()
+                fit(x, y, params, strategy)
             }
             Kernel::RBF(gamma) => {
                 let params = SmartcoreSVCParameters::default()
-                    .with_tol(settings.svc_settings.as_ref().unwrap().tol)
-                    .with_c(settings.svc_settings.as_ref().unwrap().c)
-                    .with_epoch(settings.svc_settings.as_ref().unwrap().epoch)
+                    .with_tol(svc_settings.tol)
+                    .with_c(svc_settings.c)
+                    .with_epoch(svc_settings.epoch)
                     .with_kernel(Kernels::rbf(gamma));
 
-                bincode::serialize(&SVC::fit(x, y, params).unwrap()).unwrap()
+                fit(x, y, params, strategy)
             }
             Kernel::Sigmoid(gamma, coef) => {
                 let params = SmartcoreSVCParameters::default()
-                    .with_tol(settings.svc_settings.as_ref().unwrap().tol)
-                    .with_c(settings.svc_settings.as_ref().unwrap().c)
-                    .with_epoch(settings.svc_settings.as_ref().unwrap().epoch)
+                    .with_tol(svc_settings.tol)
+                    .with_c(svc_settings.c)
+                    .with_epoch(svc_settings.epoch)
                     .with_kernel(Kernels::sigmoid(gamma, coef));
 
-                bincode::serialize(&SVC::fit(x, y, params).unwrap()).unwrap()
+                fit(x, y, params, strategy)
             }
         }
     }
 
     fn predict(x: &DenseMatrix<f32>, final_model: &Vec<u8>, settings: &Settings) -> Vec<f32> {
         match settings.svc_settings.as_ref().unwrap().kernel {
-            Kernel::Linear => {
-                let model: SVC<f32, DenseMatrix<f32>, LinearKernel> =
-                    bincode::deserialize(&*final_model).unwrap();
-                model.predict(x).unwrap()
-            }
-            Kernel::Polynomial(_, _, _) => {
-                let model: SVC<f32, DenseMatrix<f32>, PolynomialKernel<f32>> =
-                    bincode::deserialize(&*final_model).unwrap();
-                model.predict(x).unwrap()
-            }
-            Kernel::RBF(_) => {
-                let model: SVC<f32, DenseMatrix<f32>, RBFKernel<f32>> =
-                    bincode::deserialize(&*final_model).unwrap();
-                model.predict(x).unwrap()
+            Kernel::Linear => predict::<LinearKernel>(x, final_model),
+            Kernel::Polynomial(_, _, _) => predict::<PolynomialKernel<f32>>(x, final_model),
+            Kernel::RBF(_) => predict::<RBFKernel<f32>>(x, final_model),
+            Kernel::Sigmoid(_, _) => predict::<SigmoidKernel<f32>>(x, final_model),
+        }
+    }
+}
+
+/// A trained SVC, tagged with whether it separates two classes or more
+#[derive(serde::Serialize, serde::Deserialize)]
+enum SavedSVC {
+    /// A serialized smartcore model for two classes
+    Binary(Vec<u8>),
+    /// Several binary models for more than two classes
+    Multiclass(MulticlassSVC),
+}
+
+/// Tag an SVC saved before format version 7, when a model was known to be multiclass only by
+/// having more than two class labels at its start. Models of other algorithms, and algorithms
+/// that were never trained, are left as they are.
+pub(crate) fn tag_svc(algorithm: Algorithm, model: &mut Vec<u8>) -> Result<(), bincode::Error> {
+    if algorithm != Algorithm::SVC || model.is_empty() {
+        return Ok(());
+    }
+    let number_of_classes: u64 = bincode::deserialize(model)?;
+    let saved = if number_of_classes <= 2 {
+        SavedSVC::Binary(std::mem::take(model))
+    } else {
+        SavedSVC::Multiclass(bincode::deserialize(model)?)
+    };
+    *model = bincode::serialize(&saved)?;
+    Ok(())
+}
+
+/// Several binary SVC models that together separate more than two classes
+#[derive(serde::Serialize, serde::Deserialize)]
+struct MulticlassSVC {
+    /// The sorted class labels, of which there are always more than two
+    classes: Vec<f32>,
+    /// How the binary models split up the classes
+    strategy: MulticlassStrategy,
+    /// The serialized binary models. For one vs rest there is one per class, trained to tell that
+    /// class from the others. For one vs one there is one per pair of classes, in the order
+    /// (0, 1), (0, 2), ..., (1, 2), ...
+    models: Vec<Vec<u8>>,
+}
+
+/// Mirror of `smartcore::svm::svc::SVC<f32, DenseMatrix<f32>, K>`, whose decision function is
+/// private
+#[derive(serde::Deserialize)]
+struct DecisionFunction<K> {
+    /// The two class labels
+    _classes: Vec<f32>,
+    /// The kernel
+    kernel: K,
+    /// The support vectors
+    instances: Vec<Vec<f32>>,
+    /// The weights of the support vectors
+    w: Vec<f32>,
+    /// The intercept
+    b: f32,
+}
+
+impl<K: SmartcoreKernel<f32, Vec<f32>>> DecisionFunction<K> {
+    /// The signed decision value for a row, which is positive for the second class
+    fn value(&self, row: &Vec<f32>) -> f32 {
+        self.instances
+            .iter()
+            .zip(&self.w)
+            .fold(self.b, |value, (instance, w)| {
+                value + w * self.kernel.apply(row, instance)
+            })
+    }
+}
+
+/// Train a binary model, or several binary models if there are more than two classes
+fn fit<K>(
+    x: &DenseMatrix<f32>,
+    y: &Vec<f32>,
+    params: SmartcoreSVCParameters<f32, DenseMatrix<f32>, K>,
+    strategy: MulticlassStrategy,
+) -> Vec<u8>
+where
+    K: SmartcoreKernel<f32, Vec<f32>> + Serialize + DeserializeOwned + Clone,
+{
+    let mut classes = y.clone();
+    classes.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    classes.dedup();
+    if classes.len() <= 2 {
+        let model = bincode::serialize(&SVC::fit(x, y, params).unwrap()).unwrap();
+        return bincode::serialize(&SavedSVC::Binary(model)).unwrap();
+    }
+
+    let models = match strategy {
+        MulticlassStrategy::OneVsRest => classes
+            .iter()
+            .map(|class| {
+                let y = y
+                    .iter()
+                    .map(|label| if label == class { 1.0 } else { 0.0 })
+                    .collect();
+                bincode::serialize(&SVC::fit(x, &y, params.clone()).unwrap()).unwrap()
+            })
+            .collect(),
+        MulticlassStrategy::OneVsOne => {
+            let mut models = vec![];
+            for (i, first) in classes.iter().enumerate() {
+                for second in &classes[i + 1..] {
+                    let rows: Vec<usize> = (0..y.len())
+                        .filter(|row| y[*row] == *first || y[*row] == *second)
+                        .collect();
+                    let x = DenseMatrix::from_2d_vec(
+                        &rows.iter().map(|row| x.get_row_as_vec(*row)).collect(),
+                    );
+                    let y = rows.iter().map(|row| y[*row]).collect();
+                    models.push(
+                        bincode::serialize(&SVC::fit(&x, &y, params.clone()).unwrap()).unwrap(),
+                    );
+                }
             }
-            Kernel::Sigmoid(_, _) => {
-                let model: SVC<f32, DenseMatrix<f32>, SigmoidKernel<f32>> =
-                    bincode::deserialize(&*final_model).unwrap();
-                model.predict(x).unwrap()
+            models
+        }
+    };
+
+    bincode::serialize(&SavedSVC::Multiclass(MulticlassSVC {
+        classes,
+        strategy,
+        models,
+    }))
+    .unwrap()
+}
+
+/// Predict with a binary model, or combine the predictions of several binary models
+fn predict<K>(x: &DenseMatrix<f32>, final_model: &[u8]) -> Vec<f32>
+where
+    K: SmartcoreKernel<f32, Vec<f32>> + Serialize + DeserializeOwned,
+{
+    let multiclass = match bincode::deserialize(final_model).unwrap() {
+        SavedSVC::Binary(model) => {
+            let model: SVC<f32, DenseMatrix<f32>, K> = bincode::deserialize(&model).unwrap();
+            return model.predict(x).unwrap();
+        }
+        SavedSVC::Multiclass(multiclass) => multiclass,
+    };
+    let (rows, _) = x.shape();
+    match multiclass.strategy {
+        MulticlassStrategy::OneVsRest => {
+            let models: Vec<DecisionFunction<K>> = multiclass
+                .models
+                .iter()
+                .map(|model| bincode::deserialize(model).unwrap())
+                .collect();
+            (0..rows)
+                .map(|row| {
+                    let row = x.get_row_as_vec(row);
+                    let mut best = 0;
+                    let mut best_value = f32::NEG_INFINITY;
+                    for (class, model) in models.iter().enumerate() {
+                        let value = model.value(&row);
+                        if value > best_value {
+                            best = class;
+                            best_value = value;
+                        }
+                    }
+                    multiclass.classes[best]
+                })
+                .collect()
+        }
+        MulticlassStrategy::OneVsOne => {
+            let mut votes = vec![vec![0; multiclass.classes.len()]; rows];
+            for model in &multiclass.models {
+                let model: SVC<f32, DenseMatrix<f32>, K> = bincode::deserialize(model).unwrap();
+                for (row, label) in model.predict(x).unwrap().iter().enumerate() {
+                    let class = multiclass
+                        .classes
+                        .iter()
+                        .position(|class| class == label)
+                        .unwrap();
+                    votes[row][class] += 1;
+                }
             }
+            votes
+                .iter()
+                .map(|votes| {
+                    // The first class with the most votes wins, so ties go to the smaller label
+                    let most = *votes.iter().max().unwrap();
+                    let class = votes.iter().position(|count| *count == most).unwrap();
+                    multiclass.classes[class]
+                })
+                .collect()
         }
     }
 }
@@ -1,80 +1,16 @@
 use smartcore::{
     linalg::naive::dense_matrix::DenseMatrix,
-    model_selection::cross_validate,
-    model_selection::CrossValidationResult,
     svm::{
         svr::{SVRParameters as SmartcoreSVRParameters, SVR},
         Kernels, LinearKernel, PolynomialKernel, RBFKernel, SigmoidKernel,
     },
 };
 
-use crate::{Algorithm, Kernel, Settings};
+use crate::{Kernel, Settings};
 
 pub(crate) struct SupportVectorRegressorWrapper {}
 
 impl super::ModelWrapper for SupportVectorRegressorWrapper {
-    fn cv(
-        x: &DenseMatrix<f32>,
-        y: &Vec<f32>,
-        settings: &Settings,
-    ) -> (CrossValidationResult<f32>, Algorithm) {
-        let cv = match settings.svr_settings.as_ref().unwrap().kernel {
-            Kernel::Linear => cross_validate(
-                SVR::fit,
-                x,
-                y,
-                SmartcoreSVRParameters::default()
-                    .with_tol(settings.svr_settings.as_ref().unwrap().tol)
-                    .with_c(settings.svr_settings.as_ref().unwrap().c)
-                    .with_eps(settings.svr_settings.as_ref().unwrap().c)
-                    .with_kernel(Kernels::linear()),
-                settings.get_kfolds(),
-                settings.get_metric(),
-            )
-            .unwrap(),
-            Kernel::Polynomial(degree, gamma, coef) => cross_validate(
-                SVR::fit,
-                x,
-                y,
-                SmartcoreSVRParameters::default()
-                    .with_tol(settings.svr_settings.as_ref().unwrap().tol)
-                    .with_c(settings.svr_settings.as_ref().unwrap().c)
-                    .with_eps(settings.svr_settings.as_ref().unwrap().c)
-                    .with_kernel(Kernels::polynomial(degree, gamma, coef)),
-                settings.get_kfolds(),
-                settings.get_metric(),
-            )
-            .unwrap(),
-            Kernel::RBF(gamma) => cross_validate(
-                SVR::fit,
-                x,
-                y,
-                SmartcoreSVRParameters::default()
-                    .with_tol(settings.svr_settings.as_ref().unwrap().tol)
-                    .with_c(settings.svr_settings.as_ref().unwrap().c)
-                    .with_eps(settings.svr_settings.as_ref().unwrap().c)
-                    .with_kernel(Kernels::rbf(gamma)),
-                settings.get_kfolds(),
-                settings.get_metric(),
-            )
-            .unwrap(),
-            Kernel::Sigmoid(gamma, coef) => cross_validate(
-                SVR::fit,
-                x,
-                y,
-                SmartcoreSVRParameters::default()
-                    .with_tol(settings.svr_settings.as_ref().unwrap().tol)
-                    .with_c(settings.svr_settings.as_ref().unwrap().c)
-                    .with_eps(settings.svr_settings.as_ref().unwrap().c)
-                    .with_kernel(Kernels::sigmoid(gamma, coef)),
-                settings.get_kfolds(),
-                settings.get_metric(),
-            )
-            .unwrap(),
-        };
-        (cv, Algorithm::SVR)
-    }
-
     fn train(x: &DenseMatrix<f32>, y: &Vec<f32>, settings: &Settings) -> Vec<u8> {
         match settings.svr_settings.as_ref().unwrap().kernel {
             Kernel::Linear => {
@@ -0,0 +1,551 @@
+//! Smartcore keeps the nodes of its trees private, so these structs mirror the serialized layout
+//! of the smartcore 0.2 trees and forests. Deserializing a saved model into them gives access to
+//! the splits, which is all that is needed for impurity-based feature importances, TreeSHAP and
+//! quantile regression forests. The trees grown in this crate use the same nodes, so they share
+//! the same explanations, and regression trees can be serialized back after changing their leaves.
+
+use smartcore::{
+    ensemble::{
+        random_forest_classifier::RandomForestClassifierParameters,
+        random_forest_regressor::RandomForestRegressorParameters,
+    },
+    linalg::{naive::dense_matrix::DenseMatrix, BaseMatrix},
+    tree::{
+        decision_tree_classifier::DecisionTreeClassifierParameters,
+        decision_tree_regressor::DecisionTreeRegressorParameters,
+    },
+};
+
+/// A node of a tree, where `O` is the type of the output stored in leaves
+#[derive(serde::Serialize, serde::Deserialize)]
+pub(super) struct Node<O> {
+    /// Position of the node in the tree
+    pub(super) _index: usize,
+    /// Output of the node when it is a leaf
+    pub(super) output: O,
+    /// Feature used to split the node
+    pub(super) split_feature: usize,
+    /// Samples with a feature value up to this go to the true child
+    pub(super) split_value: Option<f32>,
+    /// Decrease in impurity from the split
+    pub(super) split_score: Option<f32>,
+    /// Child for samples at or below the split value
+    pub(super) true_child: Option<usize>,
+    /// Child for samples above the split value
+    pub(super) false_child: Option<usize>,
+}
+
+impl<O> Node<O> {
+    /// The child a sample with this value of the split feature goes to, or `None` for a leaf
+    pub(super) fn child(&self, value: f32) -> Option<usize> {
+        match (self.true_child, self.false_child) {
+            (Some(true_child), Some(false_child)) => {
+                if value <= self.split_value.unwrap_or(f32::NAN) {
+                    Some(true_child)
+                } else {
+                    Some(false_child)
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Whether the node is a leaf
+    fn is_leaf(&self) -> bool {
+        self.true_child.is_none() || self.false_child.is_none()
+    }
+}
+
+/// Mirror of `smartcore::tree::decision_tree_regressor::DecisionTreeRegressor<f32>`
+#[derive(serde::Serialize, serde::Deserialize)]
+pub(crate) struct RegressionTree {
+    /// The nodes, with the root first
+    nodes: Vec<Node<f32>>,
+    /// Parameters used to grow the tree
+    _parameters: DecisionTreeRegressorParameters,
+    /// Depth of the tree
+    _depth: u16,
+}
+
+impl RegressionTree {
+    /// Importance of each feature, normalized to sum to one
+    pub(crate) fn importances(&self, x: &DenseMatrix<f32>) -> Vec<f32> {
+        // Regression split scores are reductions in the sum of squares, so already grow with the
+        // number of samples in the node
+        importances(&self.nodes, x, false)
+    }
+
+    /// SHAP values of each row of `x`, with node covers counted from `background`
+    pub(crate) fn shap(&self, background: &DenseMatrix<f32>, x: &DenseMatrix<f32>) -> Shap {
+        let values: Vec<f32> = self.nodes.iter().map(|node| node.output).collect();
+        tree_shap(&self.nodes, &values, background, x)
+    }
+
+    /// Number of nodes in the tree
+    pub(crate) fn size(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// The leaf each row of `x` reaches
+    pub(crate) fn leaves(&self, x: &DenseMatrix<f32>) -> Vec<usize> {
+        let (height, _) = x.shape();
+        (0..height)
+            .map(|row| {
+                let mut id = 0;
+                while let Some(child) =
+                    self.nodes[id].child(x.get(row, self.nodes[id].split_feature))
+                {
+                    id = child;
+                }
+                id
+            })
+            .collect()
+    }
+
+    /// Change the output of a node
+    pub(crate) fn set_output(&mut self, node: usize, output: f32) {
+        self.nodes[node].output = output;
+    }
+}
+
+/// Mirror of `smartcore::tree::decision_tree_classifier::DecisionTreeClassifier<f32>`
+#[derive(serde::Deserialize)]
+pub(crate) struct ClassificationTree {
+    /// The nodes, with the root first
+    nodes: Vec<Node<usize>>,
+    /// Parameters used to grow the tree
+    _parameters: DecisionTreeClassifierParameters,
+    /// Number of classes
+    _num_classes: usize,
+    /// Class labels
+    classes: Vec<f32>,
+    /// Depth of the tree
+    _depth: u16,
+}
+
+impl ClassificationTree {
+    /// Importance of each feature, normalized to sum to one
+    pub(crate) fn importances(&self, x: &DenseMatrix<f32>) -> Vec<f32> {
+        // Classification split scores are per-sample impurity decreases, so weight them by the
+        // number of samples reaching each node
+        importances(&self.nodes, x, true)
+    }
+
+    /// SHAP values of each row of `x` for the predicted class label, with node covers counted
+    /// from `background`
+    pub(crate) fn shap(&self, background: &DenseMatrix<f32>, x: &DenseMatrix<f32>) -> Shap {
+        let values: Vec<f32> = self
+            .nodes
+            .iter()
+            .map(|node| self.classes[node.output])
+            .collect();
+        tree_shap(&self.nodes, &values, background, x)
+    }
+
+    /// The position of the class label predicted for each row of `x`
+    fn predict_positions(&self, x: &DenseMatrix<f32>) -> Vec<usize> {
+        let (height, _) = x.shape();
+        (0..height)
+            .map(|row| {
+                let mut id = 0;
+                while let Some(child) =
+                    self.nodes[id].child(x.get(row, self.nodes[id].split_feature))
+                {
+                    id = child;
+                }
+                self.nodes[id].output
+            })
+            .collect()
+    }
+}
+
+/// Mirror of `smartcore::ensemble::random_forest_regressor::RandomForestRegressor<f32>`
+#[derive(serde::Deserialize)]
+pub(crate) struct RegressionForest {
+    /// Parameters used to grow the forest
+    _parameters: RandomForestRegressorParameters,
+    /// The trees in the forest
+    pub(crate) trees: Vec<RegressionTree>,
+    /// Bootstrap samples used by each tree, if kept
+    _samples: Option<Vec<Vec<bool>>>,
+}
+
+impl RegressionForest {
+    /// SHAP values of each row of `x`, averaged over the trees like the predictions are
+    pub(crate) fn shap(&self, background: &DenseMatrix<f32>, x: &DenseMatrix<f32>) -> Shap {
+        average_shap(self.trees.iter().map(|tree| tree.shap(background, x)))
+    }
+
+    /// The training targets that land in each leaf of each tree, which turn the forest into a
+    /// quantile regression forest (Meinshausen, 2006)
+    pub(crate) fn leaf_targets(&self, x_train: &DenseMatrix<f32>, y_train: &[f32]) -> LeafTargets {
+        LeafTargets {
+            targets: self
+                .trees
+                .iter()
+                .map(|tree| {
+                    let mut targets = vec![vec![]; tree.size()];
+                    for (leaf, target) in tree.leaves(x_train).into_iter().zip(y_train) {
+                        targets[leaf].push(*target);
+                    }
+                    targets
+                })
+                .collect(),
+        }
+    }
+
+    /// Quantiles of the target for each row of `x`, with one row of predictions per quantile.
+    /// Every training target is weighted by how often it shares a leaf with the row, divided by
+    /// the size of the leaf and averaged over the trees, and the quantiles are read from the
+    /// weighted distribution of the training targets.
+    pub(crate) fn quantiles(
+        &self,
+        leaf_targets: &LeafTargets,
+        x: &DenseMatrix<f32>,
+        quantiles: &[f32],
+    ) -> Vec<Vec<f32>> {
+        let leaves: Vec<Vec<usize>> = self.trees.iter().map(|tree| tree.leaves(x)).collect();
+
+        let (height, _) = x.shape();
+        let mut predictions = vec![Vec::with_capacity(height); quantiles.len()];
+        for row in 0..height {
+            let mut weighted: Vec<(f32, f64)> = vec![];
+            for (targets, leaves) in leaf_targets.targets.iter().zip(&leaves) {
+                let leaf = &targets[leaves[row]];
+                weighted.extend(leaf.iter().map(|target| (*target, 1.0 / leaf.len() as f64)));
+            }
+            weighted.sort_by(|a, b| a.0.total_cmp(&b.0));
+            let total: f64 = weighted.iter().map(|(_, weight)| weight).sum();
+            for (prediction, quantile) in predictions.iter_mut().zip(quantiles) {
+                let target = *quantile as f64 * total;
+                let mut cumulative = 0.0;
+                let mut value = weighted.last().map_or(f32::NAN, |(target, _)| *target);
+                for (target_value, weight) in &weighted {
+                    cumulative += weight;
+                    if cumulative >= target {
+                        value = *target_value;
+                        break;
+                    }
+                }
+                prediction.push(value);
+            }
+        }
+        predictions
+    }
+}
+
+/// The training targets in each node of each tree of a regression forest, kept with the model so
+/// that prediction intervals do not need the training data
+#[derive(serde::Serialize, serde::Deserialize)]
+pub(crate) struct LeafTargets {
+    /// The targets for each tree, indexed by node, with none for the nodes that are not leaves
+    targets: Vec<Vec<Vec<f32>>>,
+}
+
+/// Mirror of `smartcore::ensemble::random_forest_classifier::RandomForestClassifier<f32>`
+#[derive(serde::Deserialize)]
+pub(crate) struct ClassificationForest {
+    /// Parameters used to grow the forest
+    _parameters: RandomForestClassifierParameters,
+    /// The trees in the forest
+    pub(crate) trees: Vec<ClassificationTree>,
+    /// Class labels
+    classes: Vec<f32>,
+    /// Bootstrap samples used by each tree, if kept
+    _samples: Option<Vec<Vec<bool>>>,
+}
+
+impl ClassificationForest {
+    /// SHAP values of each row of `x` for the class label averaged over the trees. The forest
+    /// predicts by majority vote, so this explains the mean vote rather than the predicted label.
+    pub(crate) fn shap(&self, background: &DenseMatrix<f32>, x: &DenseMatrix<f32>) -> Shap {
+        average_shap(self.trees.iter().map(|tree| tree.shap(background, x)))
+    }
+
+    /// The sorted class labels and the share of the trees voting for each for each row of `x`
+    pub(crate) fn predict_proba(&self, x: &DenseMatrix<f32>) -> super::ClassProbabilities {
+        let (height, _) = x.shape();
+        let mut probabilities = vec![vec![0.0; self.classes.len()]; height];
+        for tree in &self.trees {
+            for (row, position) in tree.predict_positions(x).into_iter().enumerate() {
+                probabilities[row][position] += 1.0 / self.trees.len() as f32;
+            }
+        }
+        (self.classes.clone(), probabilities)
+    }
+}
+
+/// Average the SHAP values of several trees
+pub(super) fn average_shap(trees: impl Iterator<Item = Shap>) -> Shap {
+    let mut number_of_trees = 0;
+    let mut total: Option<Shap> = None;
+    for (base, contributions) in trees {
+        number_of_trees += 1;
+        total = Some(match total {
+            None => (base, contributions),
+            Some((total_base, mut total_contributions)) => {
+                for (total_row, row) in total_contributions.iter_mut().zip(contributions) {
+                    for (total_value, value) in total_row.iter_mut().zip(row) {
+                        *total_value += value;
+                    }
+                }
+                (total_base + base, total_contributions)
+            }
+        });
+    }
+    let (base, mut contributions) = total.expect("A forest must contain at least one tree.");
+    let scale = 1.0 / number_of_trees as f32;
+    for value in contributions.iter_mut().flatten() {
+        *value *= scale;
+    }
+    (base * scale, contributions)
+}
+
+/// Count how many rows of `x` reach each node, routing them the same way smartcore does when
+/// predicting
+fn covers<O>(nodes: &[Node<O>], x: &DenseMatrix<f32>) -> Vec<usize> {
+    let (height, _) = x.shape();
+    let mut counts = vec![0_usize; nodes.len()];
+    for row in 0..height {
+        let mut id = 0;
+        loop {
+            counts[id] += 1;
+            let node = &nodes[id];
+            match node.child(x.get(row, node.split_feature)) {
+                Some(child) => id = child,
+                None => break,
+            }
+        }
+    }
+    counts
+}
+
+/// Sum the split scores of each feature, optionally weighted by the samples reaching each node
+pub(super) fn importances<O>(
+    nodes: &[Node<O>],
+    x: &DenseMatrix<f32>,
+    weight_by_samples: bool,
+) -> Vec<f32> {
+    let (_, width) = x.shape();
+    let counts = covers(nodes, x);
+
+    let mut importances = vec![0.0; width];
+    for (node, count) in nodes.iter().zip(counts) {
+        if let (Some(_), Some(_), Some(score)) =
+            (node.true_child, node.false_child, node.split_score)
+        {
+            importances[node.split_feature] += if weight_by_samples {
+                count as f32 * score
+            } else {
+                score
+            };
+        }
+    }
+
+    let total: f32 = importances.iter().sum();
+    if total > 0.0 {
+        for importance in importances.iter_mut() {
+            *importance /= total;
+        }
+    }
+    importances
+}
+
+/// The expected output of a model over the background data, and the contribution of each feature
+/// for every explained row
+pub(crate) type Shap = (f32, Vec<Vec<f32>>);
+
+/// One feature on the path from the root to the current node
+#[derive(Clone)]
+struct PathElement {
+    /// The feature split on, or `usize::MAX` for the root
+    feature: usize,
+    /// Fraction of the background data that follows the path when the feature is unknown
+    zero_fraction: f64,
+    /// Whether the explained row follows the path when the feature is known
+    one_fraction: f64,
+    /// Weight of the permutations the path takes part in
+    weight: f64,
+}
+
+/// Exact TreeSHAP (Lundberg et al., 2018, algorithm 2) for every row of `x`
+pub(super) fn tree_shap<O>(
+    nodes: &[Node<O>],
+    values: &[f32],
+    background: &DenseMatrix<f32>,
+    x: &DenseMatrix<f32>,
+) -> Shap {
+    let covers: Vec<f64> = covers(nodes, background)
+        .into_iter()
+        .map(|count| count as f64)
+        .collect();
+
+    // The expected value is the cover-weighted mean of the leaves
+    let base = nodes
+        .iter()
+        .enumerate()
+        .filter(|(_, node)| node.is_leaf())
+        .map(|(id, _)| covers[id] * values[id] as f64)
+        .sum::<f64>()
+        / covers[0].max(1.0);
+
+    let (height, width) = x.shape();
+    let contributions = (0..height)
+        .map(|row| {
+            let row: Vec<f32> = (0..width).map(|column| x.get(row, column)).collect();
+            let mut phi = vec![0.0; width];
+            let tree = TreeShap {
+                nodes,
+                values,
+                covers: &covers,
+                x: &row,
+            };
+            tree.recurse(0, &[], 1.0, 1.0, usize::MAX, &mut phi);
+            phi.into_iter().map(|value| value as f32).collect()
+        })
+        .collect();
+    (base as f32, contributions)
+}
+
+/// The state shared by every step of TreeSHAP for one explained row
+struct TreeShap<'a, O> {
+    /// The nodes of the tree
+    nodes: &'a [Node<O>],
+    /// Output of each node when it is a leaf
+    values: &'a [f32],
+    /// Number of background rows reaching each node
+    covers: &'a [f64],
+    /// The explained row
+    x: &'a [f32],
+}
+
+impl<'a, O> TreeShap<'a, O> {
+    /// Walk the tree, accumulating the contribution of each feature into `phi`
+    fn recurse(
+        &self,
+        id: usize,
+        parent_path: &[PathElement],
+        zero_fraction: f64,
+        one_fraction: f64,
+        feature: usize,
+        phi: &mut Vec<f64>,
+    ) {
+        // Branches that neither the background data nor the explained row reach add nothing
+        if zero_fraction == 0.0 && one_fraction == 0.0 {
+            return;
+        }
+
+        let mut path = parent_path.to_vec();
+        extend(&mut path, zero_fraction, one_fraction, feature);
+        let node = &self.nodes[id];
+
+        match node.child(self.x[node.split_feature]) {
+            None => {
+                // Each feature on the path contributes its share of the leaf output
+                for i in 1..path.len() {
+                    let weight = unwound_sum(&path, i);
+                    phi[path[i].feature] += weight
+                        * (path[i].one_fraction - path[i].zero_fraction)
+                        * self.values[id] as f64;
+                }
+            }
+            Some(hot) => {
+                let cold = if Some(hot) == node.true_child {
+                    node.false_child.unwrap()
+                } else {
+                    node.true_child.unwrap()
+                };
+                let cover = self.covers[id];
+                let fraction = |child: usize| {
+                    if cover > 0.0 {
+                        self.covers[child] / cover
+                    } else {
+                        0.0
+                    }
+                };
+
+                // Undo an earlier split on the same feature so that it is only counted once
+                let mut incoming = (1.0, 1.0);
+                if let Some(index) = path.iter().position(|e| e.feature == node.split_feature) {
+                    incoming = (path[index].zero_fraction, path[index].one_fraction);
+                    unwind(&mut path, index);
+                }
+
+                self.recurse(
+                    hot,
+                    &path,
+                    fraction(hot) * incoming.0,
+                    incoming.1,
+                    node.split_feature,
+                    phi,
+                );
+                self.recurse(
+                    cold,
+                    &path,
+                    fraction(cold) * incoming.0,
+                    0.0,
+                    node.split_feature,
+                    phi,
+                );
+            }
+        }
+    }
+}
+
+/// Add a feature to the path, updating the permutation weights
+fn extend(path: &mut Vec<PathElement>, zero_fraction: f64, one_fraction: f64, feature: usize) {
+    let depth = path.len();
+    path.push(PathElement {
+        feature,
+        zero_fraction,
+        one_fraction,
+        weight: if depth == 0 { 1.0 } else { 0.0 },
+    });
+    for i in (0..depth).rev() {
+        path[i + 1].weight += one_fraction * path[i].weight * (i + 1) as f64 / (depth + 1) as f64;
+        path[i].weight = zero_fraction * path[i].weight * (depth - i) as f64 / (depth + 1) as f64;
+    }
+}
+
+/// Remove a feature from the path, undoing its effect on the permutation weights
+fn unwind(path: &mut Vec<PathElement>, index: usize) {
+    let depth = path.len() - 1;
+    let (zero_fraction, one_fraction) = (path[index].zero_fraction, path[index].one_fraction);
+    let mut next = path[depth].weight;
+    for i in (0..depth).rev() {
+        if one_fraction != 0.0 {
+            let weight = path[i].weight;
+            path[i].weight = next * (depth + 1) as f64 / ((i + 1) as f64 * one_fraction);
+            next =
+                weight - path[i].weight * zero_fraction * (depth - i) as f64 / (depth + 1) as f64;
+        } else {
+            path[i].weight =
+                path[i].weight * (depth + 1) as f64 / (zero_fraction * (depth - i) as f64);
+        }
+    }
+    for i in index..depth {
+        path[i].feature = path[i + 1].feature;
+        path[i].zero_fraction = path[i + 1].zero_fraction;
+        path[i].one_fraction = path[i + 1].one_fraction;
+    }
+    path.pop();
+}
+
+/// Total permutation weight of the path if the feature at `index` were removed
+fn unwound_sum(path: &[PathElement], index: usize) -> f64 {
+    let depth = path.len() - 1;
+    let (zero_fraction, one_fraction) = (path[index].zero_fraction, path[index].one_fraction);
+    let mut next = path[depth].weight;
+    let mut total = 0.0;
+    for i in (0..depth).rev() {
+        if one_fraction != 0.0 {
+            let weight = next * (depth + 1) as f64 / ((i + 1) as f64 * one_fraction);
+            total += weight;
+            next =
+                path[i].weight - weight * zero_fraction * (depth - i) as f64 / (depth + 1) as f64;
+        } else {
+            total += path[i].weight / zero_fraction * (depth + 1) as f64 / (depth - i) as f64;
+        }
+    }
+    total
+}
@@ -0,0 +1,564 @@
+//! # Batch prediction
+//! This module predicts over CSV files that are too large to load at once. The file is read a
+//! chunk of rows at a time, each chunk goes through the stored preprocessing and the final model,
+//! and the predictions are either returned through an iterator or written straight to another CSV
+//! file, so memory use depends on the chunk size rather than the size of the file. An ID column can
+//! be passed through so that each prediction can be matched to its input row.
+//!
+//! Rows are parsed line by line with the [`CsvOptions`] the model was built with, so null values,
+//! comment lines, quoting and ignored columns are handled as they were in training, and other
+//! options can be passed for a single call. Quoted fields cannot contain line breaks. Classifiers
+//! whose final model is a logistic regression, random forest, gradient boosting, extra trees or
+//! multilayer perceptron can add the probability of each class to each prediction.
+//! ```
+//! # use automl::{SupervisedModel, Settings};
+//! use automl::batch::BatchOptions;
+//! let mut model = SupervisedModel::new_from_dataset(
+//!     smartcore::dataset::diabetes::load_dataset(),
+//!     Settings::default_regression(),
+//! );
+//! model.train();
+//! # let csv = "id,a,b,c,d,e,f,g,h,i,j\n7,0,0,0,0,0,0,0,0,0,0\n";
+//! # std::fs::write("tests/batch_input.csv", csv).unwrap();
+//! let rows = model
+//!     .predict_csv_to_file(
+//!         "tests/batch_input.csv",
+//!         "tests/batch_output.csv",
+//!         BatchOptions::default().with_id_column("id"),
+//!     )
+//!     .unwrap();
+//! # std::fs::remove_file("tests/batch_input.csv");
+//! # std::fs::remove_file("tests/batch_output.csv");
+//! ```
+
+use crate::algorithms::ClassProbabilities;
+use crate::csv_options::CsvOptions;
+use crate::schema::{Schema, SchemaError};
+use crate::utils::csv_field;
+use smartcore::linalg::naive::dense_matrix::DenseMatrix;
+use std::fmt::{Display, Formatter};
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Split, Write};
+
+/// Predicts the sorted class labels and the probability of each for each row of a chunk
+type ProbabilityPredictor<'a> = Box<dyn Fn(&DenseMatrix<f32>) -> Option<ClassProbabilities> + 'a>;
+
+/// Options for reading a CSV file in batches
+#[derive(Clone, Debug)]
+pub struct BatchOptions {
+    /// The number of rows read and predicted at a time
+    chunk_size: usize,
+    /// Whether the first line holds column names
+    header: bool,
+    /// Options that replace the CSV options saved with the model
+    csv_options: Option<CsvOptions>,
+    /// A field separator that replaces the one in the CSV options
+    delimiter: Option<char>,
+    /// The name of a column to copy to the output instead of using it as a feature
+    id_column: Option<String>,
+    /// Whether to add the probability of each class to each prediction
+    probabilities: bool,
+}
+
+impl Default for BatchOptions {
+    fn default() -> Self {
+        Self {
+            chunk_size: 10_000,
+            header: true,
+            csv_options: None,
+            delimiter: None,
+            id_column: None,
+            probabilities: false,
+        }
+    }
+}
+
+impl BatchOptions {
+    /// Set the number of rows read and predicted at a time
+    /// ```
+    /// # use automl::batch::BatchOptions;
+    /// let options = BatchOptions::default().with_chunk_size(1000);
+    /// ```
+    pub fn with_chunk_size(mut self, chunk_size: usize) -> Self {
+        assert!(chunk_size > 0, "The chunk size must be at least one row.");
+        self.chunk_size = chunk_size;
+        self
+    }
+
+    /// Set whether the first line holds column names
+    /// ```
+    /// # use automl::batch::BatchOptions;
+    /// let options = BatchOptions::default().with_header(false);
+    /// ```
+    pub fn with_header(mut self, header: bool) -> Self {
+        self.header = header;
+        self
+    }
+
+    /// Parse the file with these options instead of the ones saved with the model
+    /// ```
+    /// # use automl::batch::BatchOptions;
+    /// use automl::csv_options::CsvOptions;
+    /// let csv_options = CsvOptions::default().with_null_values(&["NA"]).with_missing_value(0.0);
+    /// let options = BatchOptions::default().with_csv_options(csv_options);
+    /// ```
+    pub fn with_csv_options(mut self, csv_options: CsvOptions) -> Self {
+        self.csv_options = Some(csv_options);
+        self
+    }
+
+    /// Set the field separator, which must be an ASCII character, in place of the one in the CSV
+    /// options
+    /// ```
+    /// # use automl::batch::BatchOptions;
+    /// let options = BatchOptions::default().with_delimiter(';');
+    /// ```
+    pub fn with_delimiter(mut self, delimiter: char) -> Self {
+        assert!(delimiter.is_ascii(), "The delimiter must be ASCII.");
+        self.delimiter = Some(delimiter);
+        self
+    }
+
+    /// Copy a column to the output next to each prediction instead of using it as a feature. The
+    /// file must have a header.
+    /// ```
+    /// # use automl::batch::BatchOptions;
+    /// let options = BatchOptions::default().with_id_column("customer_id");
+    /// ```
+    pub fn with_id_column(mut self, name: &str) -> Self {
+        self.id_column = Some(name.to_string());
+        self
+    }
+
+    /// Add the probability of each class to each prediction. Only a classifier trained with the
+    /// best model as its final model can predict probabilities, and only if that model is a
+    /// logistic regression, random forest, gradient boosting, extra trees or multilayer
+    /// perceptron.
+    /// ```
+    /// # use automl::batch::BatchOptions;
+    /// let options = BatchOptions::default().with_probabilities(true);
+    /// ```
+    pub fn with_probabilities(mut self, probabilities: bool) -> Self {
+        self.probabilities = probabilities;
+        self
+    }
+
+    /// The CSV options to parse the file with, given the ones saved with the model
+    fn csv_options(&self, saved: &CsvOptions) -> CsvOptions {
+        let csv_options = self.csv_options.as_ref().unwrap_or(saved).clone();
+        match self.delimiter {
+            Some(delimiter) => csv_options.with_delimiter(delimiter),
+            None => csv_options,
+        }
+    }
+}
+
+/// A single prediction from a batch
+#[derive(Clone, Debug, PartialEq)]
+pub struct Prediction {
+    /// The value of the ID column, if one was requested
+    id: Option<String>,
+    /// The predicted value
+    value: f32,
+    /// Each class label with its probability, if they were requested
+    probabilities: Option<Vec<(f32, f32)>>,
+}
+
+impl Prediction {
+    /// Get the value of the ID column, if one was requested
+    /// ```
+    /// # use automl::{SupervisedModel, Settings};
+    /// # use automl::batch::BatchOptions;
+    /// # let mut model = SupervisedModel::new_from_dataset(
+    /// #     smartcore::dataset::diabetes::load_dataset(),
+    /// #     Settings::default_regression(),
+    /// # );
+    /// # model.train();
+    /// # let csv = "id,a,b,c,d,e,f,g,h,i,j\n7,0,0,0,0,0,0,0,0,0,0\n";
+    /// # std::fs::write("tests/prediction_id.csv", csv).unwrap();
+    /// let options = BatchOptions::default().with_id_column("id");
+    /// let predictions = model.predict_from_csv_in_batches("tests/prediction_id.csv", options);
+    /// for prediction in predictions.unwrap() {
+    ///     assert_eq!(prediction.unwrap().id(), Some("7"));
+    /// }
+    /// # std::fs::remove_file("tests/prediction_id.csv");
+    /// ```
+    pub fn id(&self) -> Option<&str> {
+        self.id.as_deref()
+    }
+
+    /// Get the predicted value
+    /// ```
+    /// # use automl::{SupervisedModel, Settings};
+    /// # use automl::batch::BatchOptions;
+    /// # let mut model = SupervisedModel::new_from_dataset(
+    /// #     smartcore::dataset::diabetes::load_dataset(),
+    /// #     Settings::default_regression(),
+    /// # );
+    /// # model.train();
+    /// # let csv = "a,b,c,d,e,f,g,h,i,j\n0,0,0,0,0,0,0,0,0,0\n";
+    /// # std::fs::write("tests/prediction_value.csv", csv).unwrap();
+    /// let options = BatchOptions::default();
+    /// let predictions = model.predict_from_csv_in_batches("tests/prediction_value.csv", options);
+    /// for prediction in predictions.unwrap() {
+    ///     println!("{}", prediction.unwrap().value());
+    /// }
+    /// # std::fs::remove_file("tests/prediction_value.csv");
+    /// ```
+    pub fn value(&self) -> f32 {
+        self.value
+    }
+
+    /// Get each class label with its probability, in order of label, if they were requested
+    /// ```
+    /// # use automl::{SupervisedModel, Settings};
+    /// # use automl::batch::BatchOptions;
+    /// # use automl::settings::Algorithm;
+    /// # let mut model = SupervisedModel::new_from_dataset(
+    /// #     smartcore::dataset::breast_cancer::load_dataset(),
+    /// #     Settings::default_classification().only(Algorithm::LogisticRegression),
+    /// # );
+    /// # model.train();
+    /// # let names = model.schema().names().join(",");
+    /// # let csv = format!("{}\n{}\n", names, vec!["1"; model.schema().len()].join(","));
+    /// # std::fs::write("tests/prediction_probabilities.csv", csv).unwrap();
+    /// let options = BatchOptions::default().with_probabilities(true);
+    /// let file_name = "tests/prediction_probabilities.csv";
+    /// for prediction in model.predict_from_csv_in_batches(file_name, options).unwrap() {
+    ///     for (label, probability) in prediction.unwrap().probabilities().unwrap() {
+    ///         println!("{}: {}", label, probability);
+    ///     }
+    /// }
+    /// # std::fs::remove_file("tests/prediction_probabilities.csv");
+    /// ```
+    pub fn probabilities(&self) -> Option<&[(f32, f32)]> {
+        self.probabilities.as_deref()
+    }
+}
+
+/// Describes why a batch prediction failed
+#[derive(Debug)]
+pub enum BatchError {
+    /// A file could not be read or written
+    Io(std::io::Error),
+    /// The columns do not match the schema of the model
+    Schema(SchemaError),
+    /// The requested ID column is not in the header, or the file has no header
+    MissingIdColumn(String),
+    /// Probabilities were requested from a model that cannot predict them
+    NoProbabilities,
+    /// A line is not valid UTF-8 and the CSV options check the encoding
+    Encoding {
+        /// The line of the file, counting from 1
+        line: usize,
+    },
+    /// A line has the wrong number of fields
+    RowLength {
+        /// The line of the file, counting from 1
+        line: usize,
+        /// The number of features the model expects
+        expected: usize,
+        /// The number of features on the line, not counting the ID column or ignored columns
+        found: usize,
+    },
+    /// A value could not be read as a number
+    Parse {
+        /// The line of the file, counting from 1
+        line: usize,
+        /// The position of the feature, counting from 0 and skipping the ID column and ignored
+        /// columns
+        field: usize,
+        /// The text that could not be read
+        value: String,
+    },
+}
+
+impl Display for BatchError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BatchError::Io(error) => write!(f, "Cannot read or write the file: {}", error),
+            BatchError::Schema(error) => write!(f, "{}", error),
+            BatchError::MissingIdColumn(name) => {
+                write!(f, "The ID column {} is not in the header.", name)
+            }
+            BatchError::NoProbabilities => {
+                write!(f, "The final model cannot predict class probabilities.")
+            }
+            BatchError::Encoding { line } => write!(f, "Line {} is not valid UTF-8.", line),
+            BatchError::RowLength {
+                line,
+                expected,
+                found,
+            } => write!(
+                f,
+                "Line {} has {} features but the model expects {}.",
+                line, found, expected
+            ),
+            BatchError::Parse { line, field, value } => write!(
+                f,
+                "Cannot read {:?} in feature {} of line {} as a number.",
+                value, field, line
+            ),
+        }
+    }
+}
+
+impl std::error::Error for BatchError {}
+
+impl From<std::io::Error> for BatchError {
+    fn from(error: std::io::Error) -> Self {
+        BatchError::Io(error)
+    }
+}
+
+impl From<SchemaError> for BatchError {
+    fn from(error: SchemaError) -> Self {
+        BatchError::Schema(error)
+    }
+}
+
+/// An iterator over the predictions for each row of a CSV file, which reads and predicts a chunk
+/// of rows at a time
+pub struct BatchPredictions<'a> {
+    /// The remaining lines of the file
+    lines: Split<Box<dyn BufRead + 'a>>,
+    /// The number of the last line read, counting from 1
+    line_number: usize,
+    /// The options used to parse each line
+    csv_options: CsvOptions,
+    /// Makes predictions for a chunk of rows
+    predict: Box<dyn Fn(&DenseMatrix<f32>) -> Vec<f32> + 'a>,
+    /// Predicts class probabilities for a chunk of rows, if they were requested
+    predict_proba: Option<ProbabilityPredictor<'a>>,
+    /// The number of features the model expects
+    width: usize,
+    /// The number of fields on each line
+    columns: usize,
+    /// The position of each feature among the fields
+    features: Vec<usize>,
+    /// The position of the ID column, if one was requested
+    id_position: Option<usize>,
+    /// The name of the ID column, if one was requested
+    id_name: Option<String>,
+    /// The number of rows read and predicted at a time
+    chunk_size: usize,
+    /// Predictions for the current chunk that have not been returned yet
+    buffer: std::vec::IntoIter<Prediction>,
+    /// Whether an error has been returned, which ends the iteration
+    failed: bool,
+}
+
+impl<'a> BatchPredictions<'a> {
+    /// Open a file and check its header against the schema. The file is parsed with the model's
+    /// saved CSV options unless the batch options replace them.
+    pub(crate) fn new(
+        file_name: &str,
+        schema: &Schema,
+        csv_options: &CsvOptions,
+        predict: impl Fn(&DenseMatrix<f32>) -> Vec<f32> + 'a,
+        predict_proba: Option<impl Fn(&DenseMatrix<f32>) -> Option<ClassProbabilities> + 'a>,
+        options: BatchOptions,
+    ) -> Result<Self, BatchError> {
+        let reader = Box::new(BufReader::new(File::open(file_name)?));
+        Self::from_reader(reader, schema, csv_options, predict, predict_proba, options)
+    }
+
+    /// Read CSV text from any source, such as the body of a request, like a file
+    pub(crate) fn from_reader(
+        reader: Box<dyn BufRead + 'a>,
+        schema: &Schema,
+        csv_options: &CsvOptions,
+        predict: impl Fn(&DenseMatrix<f32>) -> Vec<f32> + 'a,
+        predict_proba: Option<impl Fn(&DenseMatrix<f32>) -> Option<ClassProbabilities> + 'a>,
+        options: BatchOptions,
+    ) -> Result<Self, BatchError> {
+        let csv_options = options.csv_options(csv_options);
+        let predict_proba: Option<ProbabilityPredictor<'a>> = match predict_proba {
+            Some(predict_proba) if options.probabilities => Some(Box::new(predict_proba)),
+            None if options.probabilities => return Err(BatchError::NoProbabilities),
+            _ => None,
+        };
+        let mut lines = reader.split(b'\n');
+        let mut line_number = 0;
+        let mut id_position = None;
+        let (columns, features) = if options.header {
+            let header = next_line(&mut lines, &mut line_number, &csv_options)?.unwrap_or_default();
+            let fields = csv_options.fields(header.trim_start_matches('\u{feff}'));
+            if let Some(id) = &options.id_column {
+                id_position = Some(
+                    fields
+                        .iter()
+                        .position(|name| name == id)
+                        .ok_or_else(|| BatchError::MissingIdColumn(id.clone()))?,
+                );
+            }
+            let features: Vec<usize> = (0..fields.len())
+                .filter(|position| {
+                    Some(*position) != id_position && !csv_options.is_ignored(&fields[*position])
+                })
+                .collect();
+            let names: Vec<String> = features
+                .iter()
+                .map(|position| fields[*position].clone())
+                .collect();
+            schema.check_names(&names)?;
+            schema.check_width(names.len())?;
+            (fields.len(), features)
+        } else if let Some(id) = &options.id_column {
+            return Err(BatchError::MissingIdColumn(id.clone()));
+        } else {
+            (schema.len(), (0..schema.len()).collect())
+        };
+
+        Ok(Self {
+            lines,
+            line_number,
+            csv_options,
+            predict: Box::new(predict),
+            predict_proba,
+            width: schema.len(),
+            columns,
+            features,
+            id_position,
+            id_name: options.id_column,
+            chunk_size: options.chunk_size,
+            buffer: Vec::new().into_iter(),
+            failed: false,
+        })
+    }
+
+    /// Read and predict the next chunk of rows, returning false at the end of the file
+    fn next_chunk(&mut self) -> Result<bool, BatchError> {
+        let mut ids = Vec::new();
+        let mut values = Vec::new();
+        while ids.len() < self.chunk_size {
+            let line = match next_line(&mut self.lines, &mut self.line_number, &self.csv_options)? {
+                Some(line) => line,
+                None => break,
+            };
+            let fields = self.csv_options.fields(&line);
+            if fields.len() != self.columns {
+                return Err(BatchError::RowLength {
+                    line: self.line_number,
+                    expected: self.width,
+                    found: fields.len().saturating_sub(self.columns - self.width),
+                });
+            }
+            for (field, position) in self.features.iter().enumerate() {
+                let value = &fields[*position];
+                let number = self
+                    .csv_options
+                    .value(value)
+                    .ok_or_else(|| BatchError::Parse {
+                        line: self.line_number,
+                        field,
+                        value: value.clone(),
+                    })?;
+                values.push(number);
+            }
+            ids.push(self.id_position.map(|position| fields[position].clone()));
+        }
+        if ids.is_empty() {
+            return Ok(false);
+        }
+
+        let x = DenseMatrix::from_array(ids.len(), self.width, &values);
+        let predictions = (*self.predict)(&x);
+        let probabilities: Vec<Option<Vec<(f32, f32)>>> = match &self.predict_proba {
+            Some(predict_proba) => {
+                let (classes, rows) = predict_proba(&x).ok_or(BatchError::NoProbabilities)?;
+                rows.into_iter()
+                    .map(|row| Some(classes.iter().copied().zip(row).collect()))
+                    .collect()
+            }
+            None => vec![None; ids.len()],
+        };
+        self.buffer = ids
+            .into_iter()
+            .zip(predictions)
+            .zip(probabilities)
+            .map(|((id, value), probabilities)| Prediction {
+                id,
+                value,
+                probabilities,
+            })
+            .collect::<Vec<_>>()
+            .into_iter();
+        Ok(true)
+    }
+
+    /// Write every remaining prediction to a CSV file, returning the number of rows written. Each
+    /// class probability gets a column named after its label.
+    pub(crate) fn write(mut self, file_name: &str) -> Result<usize, BatchError> {
+        let mut writer = BufWriter::new(File::create(file_name)?);
+        let first = self.next().transpose()?;
+        let mut header: Vec<String> = self.id_name.iter().map(|name| csv_field(name)).collect();
+        header.push("prediction".to_string());
+        if let Some(probabilities) = first.as_ref().and_then(|first| first.probabilities()) {
+            header.extend(
+                probabilities
+                    .iter()
+                    .map(|(label, _)| csv_field(&format!("probability_{}", label))),
+            );
+        }
+        writeln!(writer, "{}", header.join(","))?;
+
+        let mut rows = 0;
+        for prediction in first.map(Ok).into_iter().chain(self) {
+            let prediction = prediction?;
+            let mut fields: Vec<String> = prediction.id.iter().map(|id| csv_field(id)).collect();
+            fields.push(prediction.value.to_string());
+            fields.extend(
+                prediction
+                    .probabilities
+                    .iter()
+                    .flatten()
+                    .map(|(_, probability)| probability.to_string()),
+            );
+            writeln!(writer, "{}", fields.join(","))?;
+            rows += 1;
+        }
+        writer.flush()?;
+        Ok(rows)
+    }
+}
+
+impl Iterator for BatchPredictions<'_> {
+    type Item = Result<Prediction, BatchError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(prediction) = self.buffer.next() {
+            return Some(Ok(prediction));
+        }
+        if self.failed {
+            return None;
+        }
+        match self.next_chunk() {
+            Ok(true) => self.buffer.next().map(Ok),
+            Ok(false) => None,
+            Err(error) => {
+                self.failed = true;
+                Some(Err(error))
+            }
+        }
+    }
+}
+
+/// Read the next line that is not empty or a comment, or return `None` at the end of the file
+fn next_line(
+    lines: &mut Split<Box<dyn BufRead + '_>>,
+    line_number: &mut usize,
+    csv_options: &CsvOptions,
+) -> Result<Option<String>, BatchError> {
+    for line in lines {
+        let line = line?;
+        *line_number += 1;
+        let decoded = csv_options
+            .decode(&line)
+            .map_err(|_| BatchError::Encoding { line: *line_number })?;
+        if let Some(decoded) = decoded {
+            return Ok(Some(decoded.into_owned()));
+        }
+    }
+    Ok(None)
+}
@@ -0,0 +1,53 @@
+//! # Prediction server
+//! The `automl-serve` binary serves a saved model over HTTP, reloading it whenever the file
+//! changes. Install it with `cargo install automl --features serve`.
+//! ```text
+//! automl-serve --model model.aml --address 127.0.0.1:8080
+//! curl -X POST 127.0.0.1:8080/predict -d '[[0.1, 0.2, 0.3]]'
+//! ```
+
+use automl::server::{PredictionServer, ServeError, ServerObserver};
+use clap::Parser;
+
+/// Serve predictions from a saved model over HTTP
+#[derive(Parser)]
+#[clap(name = "automl-serve", version, about)]
+struct Cli {
+    /// The saved model or predictor
+    #[clap(long)]
+    model: String,
+    /// The address to listen on
+    #[clap(long, default_value = "127.0.0.1:8080")]
+    address: String,
+}
+
+/// Prints problems the server carries on after to standard error
+struct Console;
+
+impl ServerObserver for Console {
+    fn on_reload_error(&self, model_file: &str, error: &ServeError) {
+        eprintln!(
+            "Keeping the previous model because {} cannot be loaded: {}",
+            model_file, error
+        );
+    }
+
+    fn on_response_error(&self, error: &std::io::Error) {
+        eprintln!("Cannot send response: {}", error);
+    }
+}
+
+fn main() {
+    let cli = Cli::parse();
+    match PredictionServer::new(&cli.model, &cli.address) {
+        Ok(server) => {
+            let server = server.with_observer(Console);
+            eprintln!("Serving {} on http://{}", cli.model, server.address());
+            server.run();
+        }
+        Err(error) => {
+            eprintln!("Error: {}", error);
+            std::process::exit(1);
+        }
+    }
+}
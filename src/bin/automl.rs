@@ -0,0 +1,221 @@
+//! # Command-line interface
+//! The `automl` binary trains, applies and reports on models without writing any Rust. Install it
+//! with `cargo install automl --features cli`.
+//! ```text
+//! automl settings init --task regression --out settings.yaml
+//! automl train --data data.csv --target price --settings settings.yaml --out model.aml
+//! automl predict --model model.aml --data new.csv --out predictions.csv
+//! automl report --model model.aml --format md
+//! ```
+
+use automl::{
+    batch::BatchOptions,
+    file_format::{read_header, Contents},
+    predictor::Predictor,
+    Settings, SupervisedModel,
+};
+use clap::{ArgEnum, Parser, Subcommand};
+use std::error::Error;
+use std::io::{BufRead, BufReader};
+
+/// Automated machine learning for classification and regression
+#[derive(Parser)]
+#[clap(name = "automl", version, about)]
+struct Cli {
+    /// The command to run
+    #[clap(subcommand)]
+    command: Command,
+}
+
+/// The commands the binary understands
+#[derive(Subcommand)]
+enum Command {
+    /// Compare models on a CSV file and save the result
+    Train {
+        /// The CSV file to train on, with a header
+        #[clap(long)]
+        data: String,
+        /// The name of the column to predict
+        #[clap(long)]
+        target: String,
+        /// A YAML settings file, as written by `automl settings init`
+        #[clap(long)]
+        settings: Option<String>,
+        /// The kind of problem, used when no settings file is given
+        #[clap(long, arg_enum, default_value = "regression")]
+        task: Task,
+        /// Where to save the trained model
+        #[clap(long)]
+        out: String,
+        /// Save a slim predictor without the training data instead of the full model
+        #[clap(long)]
+        predictor: bool,
+    },
+    /// Predict a CSV file with a saved model or predictor
+    Predict {
+        /// The saved model or predictor
+        #[clap(long)]
+        model: String,
+        /// The CSV file to predict, with a header
+        #[clap(long)]
+        data: String,
+        /// Where to write the predictions
+        #[clap(long)]
+        out: String,
+        /// A column to copy next to each prediction instead of using it as a feature
+        #[clap(long)]
+        id_column: Option<String>,
+        /// Add the probability of each class, for classifiers that can predict them
+        #[clap(long)]
+        probabilities: bool,
+    },
+    /// Print the comparison report of a saved model
+    Report {
+        /// The saved model
+        #[clap(long)]
+        model: String,
+        /// The format of the report
+        #[clap(long, arg_enum, default_value = "md")]
+        format: Format,
+        /// Where to write the report instead of standard output
+        #[clap(long)]
+        out: Option<String>,
+    },
+    /// Work with settings files
+    Settings {
+        /// The settings command to run
+        #[clap(subcommand)]
+        command: SettingsCommand,
+    },
+}
+
+/// The commands for settings files
+#[derive(Subcommand)]
+enum SettingsCommand {
+    /// Write default settings to a YAML file
+    Init {
+        /// The kind of problem
+        #[clap(long, arg_enum, default_value = "regression")]
+        task: Task,
+        /// Where to save the settings
+        #[clap(long)]
+        out: String,
+    },
+}
+
+/// The kinds of problem
+#[derive(Clone, Copy, ArgEnum)]
+enum Task {
+    /// Predict a continuous value
+    Regression,
+    /// Predict a class label
+    Classification,
+}
+
+impl Task {
+    /// Get the default settings for the task
+    fn settings(self) -> Settings {
+        match self {
+            Task::Regression => Settings::default_regression(),
+            Task::Classification => Settings::default_classification(),
+        }
+    }
+}
+
+/// The report formats
+#[derive(Clone, Copy, ArgEnum)]
+enum Format {
+    /// Markdown
+    Md,
+    /// HTML
+    Html,
+    /// JSON
+    Json,
+    /// CSV
+    Csv,
+}
+
+fn main() {
+    if let Err(error) = run(Cli::parse()) {
+        eprintln!("Error: {}", error);
+        std::process::exit(1);
+    }
+}
+
+/// Run a command
+fn run(cli: Cli) -> Result<(), Box<dyn Error>> {
+    match cli.command {
+        Command::Train {
+            data,
+            target,
+            settings,
+            task,
+            out,
+            predictor,
+        } => {
+            let settings = match settings {
+                Some(file_name) => Settings::new_from_file(&file_name),
+                None => task.settings(),
+            };
+            let target_index = column_index(&data, &target)?;
+            let mut model = SupervisedModel::new_from_csv(&data, target_index, true, settings);
+            model.train();
+            println!("{}", model);
+            if predictor {
+                model.export_predictor().save(&out);
+            } else {
+                model.save(&out);
+            }
+        }
+        Command::Predict {
+            model,
+            data,
+            out,
+            id_column,
+            probabilities,
+        } => {
+            let mut options = BatchOptions::default().with_probabilities(probabilities);
+            if let Some(id_column) = id_column {
+                options = options.with_id_column(&id_column);
+            }
+            let rows = match read_header(&model)?.contents() {
+                Contents::SupervisedModel => SupervisedModel::try_new_from_file(&model)?
+                    .predict_csv_to_file(&data, &out, options)?,
+                Contents::Predictor => {
+                    Predictor::try_new_from_file(&model)?.predict_csv_to_file(&data, &out, options)?
+                }
+            };
+            eprintln!("Wrote {} predictions to {}", rows, out);
+        }
+        Command::Report { model, format, out } => {
+            let report = SupervisedModel::try_new_from_file(&model)?.report();
+            let text = match format {
+                Format::Md => report.to_markdown(),
+                Format::Html => report.to_html(),
+                Format::Json => report.to_json(),
+                Format::Csv => report.to_csv(),
+            };
+            match out {
+                Some(file_name) => std::fs::write(file_name, text)?,
+                None => print!("{}", text),
+            }
+        }
+        Command::Settings {
+            command: SettingsCommand::Init { task, out },
+        } => task.settings().save(&out),
+    }
+    Ok(())
+}
+
+/// Find a column by name in the header of a CSV file
+fn column_index(file_name: &str, column: &str) -> Result<usize, Box<dyn Error>> {
+    let header = BufReader::new(std::fs::File::open(file_name)?)
+        .lines()
+        .next()
+        .ok_or_else(|| format!("{} is empty", file_name))??;
+    header
+        .trim_start_matches('\u{feff}')
+        .split(',')
+        .position(|name| name.trim().trim_matches('"') == column)
+        .ok_or_else(|| format!("{} has no column named {}", file_name, column).into())
+}
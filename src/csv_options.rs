@@ -0,0 +1,358 @@
+//! # CSV parsing options
+//! This module contains [`CsvOptions`], which controls how CSV files are parsed by
+//! `new_from_csv_with_options` and the `predict_from_csv*` methods. The options a model was built
+//! with are saved alongside it and reused by `predict_from_csv`, so training and prediction data
+//! are parsed the same way.
+//!
+//! Before a file is handed to polars it is checked for invalid UTF-8, a byte order mark and comment
+//! lines are removed, and fields matching one of the null value strings are blanked out, or
+//! replaced with the missing value if one is set. Models cannot be trained on or predict from
+//! missing values, so a file that still has them after parsing is rejected with the name of the
+//! column.
+//! ```
+//! # use automl::{SupervisedModel, Settings};
+//! use automl::csv_options::CsvOptions;
+//! # #[cfg(feature = "csv")]
+//! # {
+//! let model = SupervisedModel::new_from_csv_with_options(
+//!     "data/diabetes.csv",
+//!     10,
+//!     true,
+//!     Settings::default_regression(),
+//!     CsvOptions::default().with_null_values(&["NA", "?"]).with_missing_value(0.0),
+//! );
+//! # }
+//! ```
+
+use crate::schema::FeatureType;
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+
+#[cfg(any(feature = "csv"))]
+use polars::prelude::{CsvReader, DataFrame, DataType, Field, Schema, SerReader};
+
+/// Options for parsing CSV files
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct CsvOptions {
+    /// The field separator
+    delimiter: char,
+    /// The character used to quote fields, if any
+    quote_char: Option<char>,
+    /// Field values that mean a value is missing
+    null_values: Vec<String>,
+    /// The number used in place of missing values
+    missing_value: Option<f32>,
+    /// Lines starting with this prefix are skipped
+    comment_prefix: Option<String>,
+    /// Columns that are read but not used
+    ignore_columns: Vec<String>,
+    /// The type to read each listed column as, instead of inferring it
+    dtypes: BTreeMap<String, FeatureType>,
+    /// The number of rows used to infer column types, or every row if not set
+    infer_schema_length: Option<usize>,
+    /// Whether to reject files that are not valid UTF-8 rather than replace invalid bytes
+    check_encoding: bool,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        Self {
+            delimiter: ',',
+            quote_char: Some('"'),
+            null_values: vec![],
+            missing_value: None,
+            comment_prefix: None,
+            ignore_columns: vec![],
+            dtypes: BTreeMap::new(),
+            infer_schema_length: None,
+            check_encoding: true,
+        }
+    }
+}
+
+impl CsvOptions {
+    /// Set the field separator, which must be an ASCII character
+    /// ```
+    /// # use automl::csv_options::CsvOptions;
+    /// let options = CsvOptions::default().with_delimiter(';');
+    /// ```
+    pub fn with_delimiter(mut self, delimiter: char) -> Self {
+        assert!(delimiter.is_ascii(), "The delimiter must be ASCII.");
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// Set the character used to quote fields, which must be ASCII, or `None` to read quotes as
+    /// part of the field
+    /// ```
+    /// # use automl::csv_options::CsvOptions;
+    /// let options = CsvOptions::default().with_quote_char(Some('\''));
+    /// ```
+    pub fn with_quote_char(mut self, quote_char: Option<char>) -> Self {
+        assert!(
+            quote_char.is_none_or(|c| c.is_ascii()),
+            "The quote character must be ASCII."
+        );
+        self.quote_char = quote_char;
+        self
+    }
+
+    /// Set the field values that mean a value is missing
+    /// ```
+    /// # use automl::csv_options::CsvOptions;
+    /// let options = CsvOptions::default().with_null_values(&["NA", "N/A", ""]);
+    /// ```
+    pub fn with_null_values(mut self, null_values: &[&str]) -> Self {
+        self.null_values = null_values.iter().map(|value| value.to_string()).collect();
+        self
+    }
+
+    /// Replace missing values, including empty fields, with a number
+    /// ```
+    /// # use automl::csv_options::CsvOptions;
+    /// let options = CsvOptions::default().with_missing_value(0.0);
+    /// ```
+    pub fn with_missing_value(mut self, missing_value: f32) -> Self {
+        self.missing_value = Some(missing_value);
+        self
+    }
+
+    /// Skip lines that start with a prefix
+    /// ```
+    /// # use automl::csv_options::CsvOptions;
+    /// let options = CsvOptions::default().with_comment_prefix("#");
+    /// ```
+    pub fn with_comment_prefix(mut self, comment_prefix: &str) -> Self {
+        assert!(
+            !comment_prefix.is_empty(),
+            "The comment prefix cannot be empty."
+        );
+        self.comment_prefix = Some(comment_prefix.to_string());
+        self
+    }
+
+    /// Read these columns, if present, but leave them out of the features
+    /// ```
+    /// # use automl::csv_options::CsvOptions;
+    /// let options = CsvOptions::default().with_ignored_columns(&["customer_id"]);
+    /// ```
+    pub fn with_ignored_columns(mut self, columns: &[&str]) -> Self {
+        self.ignore_columns = columns.iter().map(|name| name.to_string()).collect();
+        self
+    }
+
+    /// Read a column as the given type instead of inferring it. Columns can be numeric, boolean or
+    /// text.
+    /// ```
+    /// # use automl::csv_options::CsvOptions;
+    /// use automl::schema::FeatureType;
+    /// let options = CsvOptions::default().with_dtype("zip_code", FeatureType::Numeric);
+    /// ```
+    pub fn with_dtype(mut self, column: &str, dtype: FeatureType) -> Self {
+        assert_ne!(
+            dtype,
+            FeatureType::Other,
+            "Columns can only be read as numeric, boolean or text."
+        );
+        self.dtypes.insert(column.to_string(), dtype);
+        self
+    }
+
+    /// Set the number of rows used to infer column types. By default every row is used.
+    /// ```
+    /// # use automl::csv_options::CsvOptions;
+    /// let options = CsvOptions::default().with_infer_schema_length(1000);
+    /// ```
+    pub fn with_infer_schema_length(mut self, rows: usize) -> Self {
+        self.infer_schema_length = Some(rows);
+        self
+    }
+
+    /// Set whether to reject files that are not valid UTF-8, which is the default, or to replace
+    /// invalid bytes instead
+    /// ```
+    /// # use automl::csv_options::CsvOptions;
+    /// let options = CsvOptions::default().with_encoding_check(false);
+    /// ```
+    pub fn with_encoding_check(mut self, check_encoding: bool) -> Self {
+        self.check_encoding = check_encoding;
+        self
+    }
+
+    /// Read a CSV file into a data frame, returning an error if the file cannot be opened or
+    /// parsed
+    #[cfg(any(feature = "csv"))]
+    pub(crate) fn read(
+        &self,
+        filepath: &str,
+        header: bool,
+    ) -> Result<DataFrame, crate::schema::SchemaError> {
+        let bytes =
+            std::fs::read(filepath).map_err(crate::SupervisedModel::unreadable(filepath))?;
+        let bytes = self
+            .clean(&bytes, header)
+            .map_err(crate::SupervisedModel::unreadable(filepath))?;
+        let schema = Schema::new(
+            self.dtypes
+                .iter()
+                .map(|(name, dtype)| {
+                    Field::new(
+                        name,
+                        match dtype {
+                            FeatureType::Numeric => DataType::Float64,
+                            FeatureType::Boolean => DataType::Boolean,
+                            _ => DataType::Utf8,
+                        },
+                    )
+                })
+                .collect(),
+        );
+        let mut df = CsvReader::new(std::io::Cursor::new(bytes))
+            .infer_schema(self.infer_schema_length)
+            .has_header(header)
+            .with_delimiter(self.delimiter as u8)
+            .with_quote_char(self.quote_char.map(|c| c as u8))
+            .with_dtypes(if self.dtypes.is_empty() {
+                None
+            } else {
+                Some(&schema)
+            })
+            .finish()
+            .map_err(crate::SupervisedModel::unreadable(filepath))?;
+        for name in &self.ignore_columns {
+            if df.column(name).is_ok() {
+                df = df.drop(name).unwrap();
+            }
+        }
+        Ok(df)
+    }
+
+    /// Check the encoding, remove a byte order mark and comment lines and replace null values,
+    /// explaining which line is not valid UTF-8 if the encoding is checked
+    #[cfg(any(feature = "csv"))]
+    fn clean(&self, bytes: &[u8], header: bool) -> Result<Vec<u8>, String> {
+        let bytes = bytes.strip_prefix("\u{feff}".as_bytes()).unwrap_or(bytes);
+        let missing = self.missing_value.map(|value| value.to_string());
+        let mut cleaned = Vec::with_capacity(bytes.len());
+        let mut first = true;
+        for (number, line) in bytes.split(|byte| *byte == b'\n').enumerate() {
+            let line = match self
+                .decode(line)
+                .map_err(|_| format!("Line {} is not valid UTF-8.", number + 1))?
+            {
+                Some(line) => line,
+                None => continue,
+            };
+            if (header && first) || (self.null_values.is_empty() && missing.is_none()) {
+                cleaned.extend_from_slice(line.as_bytes());
+            } else {
+                let fields: Vec<&str> = self
+                    .split_raw(&line)
+                    .into_iter()
+                    .map(|field| match (self.is_null(field), &missing) {
+                        (true, Some(missing)) => missing.as_str(),
+                        (true, None) => "",
+                        (false, _) => field,
+                    })
+                    .collect();
+                cleaned.extend_from_slice(fields.join(&self.delimiter.to_string()).as_bytes());
+            }
+            cleaned.push(b'\n');
+            first = false;
+        }
+        Ok(cleaned)
+    }
+
+    /// Decode a line without its line ending, or return `None` for an empty or comment line.
+    /// Invalid UTF-8 is an error if the encoding is checked and is replaced otherwise.
+    pub(crate) fn decode<'a>(
+        &self,
+        line: &'a [u8],
+    ) -> Result<Option<Cow<'a, str>>, std::str::Utf8Error> {
+        let line = if self.check_encoding {
+            Cow::Borrowed(std::str::from_utf8(line)?)
+        } else {
+            String::from_utf8_lossy(line)
+        };
+        let line = match line {
+            Cow::Borrowed(line) => Cow::Borrowed(line.strip_suffix('\r').unwrap_or(line)),
+            Cow::Owned(line) => Cow::Owned(line.strip_suffix('\r').unwrap_or(&line).to_string()),
+        };
+        let comment = self
+            .comment_prefix
+            .as_ref()
+            .map_or(false, |prefix| line.starts_with(prefix.as_str()));
+        Ok(if line.is_empty() || comment {
+            None
+        } else {
+            Some(line)
+        })
+    }
+
+    /// Split a line into fields, removing the quotes around quoted fields and undoubling the
+    /// quotes inside them. Line breaks inside quoted fields are not supported.
+    pub(crate) fn fields(&self, line: &str) -> Vec<String> {
+        let mut fields = Vec::new();
+        let mut field = String::new();
+        let mut quoted = false;
+        let mut characters = line.chars().peekable();
+        while let Some(character) = characters.next() {
+            if Some(character) == self.quote_char {
+                if quoted && characters.peek() == Some(&character) {
+                    field.push(character);
+                    characters.next();
+                } else {
+                    quoted = !quoted;
+                }
+            } else if character == self.delimiter && !quoted {
+                fields.push(std::mem::take(&mut field));
+            } else {
+                field.push(character);
+            }
+        }
+        fields.push(field);
+        fields
+    }
+
+    /// Read a field as a number, using the missing value for null fields. Returns `None` if the
+    /// field is not a number, or is null and no missing value is set.
+    pub(crate) fn value(&self, field: &str) -> Option<f32> {
+        if self.is_null(field) {
+            self.missing_value
+        } else {
+            field.trim().parse().ok()
+        }
+    }
+
+    /// Check whether a column is left out of the features
+    pub(crate) fn is_ignored(&self, name: &str) -> bool {
+        self.ignore_columns.iter().any(|column| column == name)
+    }
+
+    /// Check whether a field is empty or one of the null values
+    fn is_null(&self, field: &str) -> bool {
+        let unquoted = match self.quote_char {
+            Some(quote) => field.trim().trim_matches(quote),
+            None => field.trim(),
+        };
+        unquoted.is_empty() || self.null_values.iter().any(|value| value == unquoted)
+    }
+
+    /// Split a line into fields, keeping any quotes so that the line can be joined back together
+    #[cfg(any(feature = "csv"))]
+    fn split_raw<'a>(&self, line: &'a str) -> Vec<&'a str> {
+        let mut fields = Vec::new();
+        let mut start = 0;
+        let mut quoted = false;
+        for (index, character) in line.char_indices() {
+            if Some(character) == self.quote_char {
+                quoted = !quoted;
+            } else if character == self.delimiter && !quoted {
+                fields.push(&line[start..index]);
+                start = index + character.len_utf8();
+            }
+        }
+        fields.push(&line[start..]);
+        fields
+    }
+}
@@ -0,0 +1,152 @@
+//! # Polars data frames
+//! This module contains [`DataFrameOptions`], which controls how a [`SupervisedModel`] is built
+//! from a polars `DataFrame` that is already in memory. The target is chosen by column name, and
+//! the features can be narrowed down with an explicit column list or a list of columns to drop.
+//! [`DtypeHandling`] decides what happens to columns that are not numeric.
+//!
+//! At prediction time the features are picked out of the frame by name, so the frame can hold
+//! other columns, such as IDs or the target, as well. The predictions can be returned on their own
+//! or appended to the frame as a new column.
+//! ```no_run
+//! # use automl::{SupervisedModel, Settings};
+//! use automl::dataframe::{DataFrameOptions, DtypeHandling};
+//! use polars::prelude::*;
+//! let df = df!(
+//!     "id" => &["a", "b", "c", "d"],
+//!     "x" => &[1.0, 2.0, 3.0, 4.0],
+//!     "flag" => &[true, false, true, false],
+//!     "y" => &[2.0, 4.0, 6.0, 8.0]
+//! )
+//! .unwrap();
+//! let mut model = SupervisedModel::new_from_dataframe_with_options(
+//!     df.clone(),
+//!     "y",
+//!     Settings::default_regression(),
+//!     DataFrameOptions::default().with_dtype_handling(DtypeHandling::SkipNonNumeric),
+//! );
+//! model.train();
+//! let with_predictions = model.predict_into_dataframe(&df, "prediction");
+//! ```
+//!
+//! [`SupervisedModel`]: crate::SupervisedModel
+
+use crate::schema::FeatureType;
+use polars::prelude::{DataFrame, DataType};
+
+/// What to do with columns that are not numeric
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DtypeHandling {
+    /// Cast every column to `f32`, so booleans become 0 or 1. Panics on columns that cannot be
+    /// cast, such as text.
+    Cast,
+    /// Leave out columns that are neither numeric nor boolean
+    SkipNonNumeric,
+    /// Panic on any column that is not numeric, including booleans
+    Strict,
+}
+
+/// Options for building a model from a polars `DataFrame`
+#[derive(Clone, Debug)]
+pub struct DataFrameOptions {
+    /// The columns to use as features, or every column but the target if not set
+    columns: Option<Vec<String>>,
+    /// Columns to leave out of the features
+    drop: Vec<String>,
+    /// What to do with columns that are not numeric
+    dtype_handling: DtypeHandling,
+}
+
+impl Default for DataFrameOptions {
+    fn default() -> Self {
+        Self {
+            columns: None,
+            drop: vec![],
+            dtype_handling: DtypeHandling::Cast,
+        }
+    }
+}
+
+impl DataFrameOptions {
+    /// Use only these columns as features, in this order
+    /// ```
+    /// # use automl::dataframe::DataFrameOptions;
+    /// let options = DataFrameOptions::default().with_columns(&["age", "income"]);
+    /// ```
+    pub fn with_columns(mut self, columns: &[&str]) -> Self {
+        self.columns = Some(columns.iter().map(|name| name.to_string()).collect());
+        self
+    }
+
+    /// Leave these columns out of the features
+    /// ```
+    /// # use automl::dataframe::DataFrameOptions;
+    /// let options = DataFrameOptions::default().with_dropped_columns(&["customer_id"]);
+    /// ```
+    pub fn with_dropped_columns(mut self, columns: &[&str]) -> Self {
+        self.drop = columns.iter().map(|name| name.to_string()).collect();
+        self
+    }
+
+    /// Set what to do with columns that are not numeric
+    /// ```
+    /// # use automl::dataframe::{DataFrameOptions, DtypeHandling};
+    /// let options = DataFrameOptions::default().with_dtype_handling(DtypeHandling::Strict);
+    /// ```
+    pub fn with_dtype_handling(mut self, dtype_handling: DtypeHandling) -> Self {
+        self.dtype_handling = dtype_handling;
+        self
+    }
+
+    /// Pick the feature columns out of a frame
+    pub(crate) fn features(&self, df: &DataFrame, target: &str) -> DataFrame {
+        let names: Vec<String> = match &self.columns {
+            Some(columns) => columns.clone(),
+            None => df
+                .get_column_names()
+                .iter()
+                .map(|name| name.to_string())
+                .collect(),
+        };
+        let columns = names
+            .iter()
+            .filter(|name| name.as_str() != target && !self.drop.contains(name))
+            .map(|name| {
+                df.column(name)
+                    .unwrap_or_else(|_| panic!("Cannot find the column {}.", name))
+                    .clone()
+            })
+            .filter(|series| match self.dtype_handling {
+                DtypeHandling::Cast => true,
+                DtypeHandling::SkipNonNumeric => matches!(
+                    feature_type(series.dtype()),
+                    FeatureType::Numeric | FeatureType::Boolean
+                ),
+                DtypeHandling::Strict => {
+                    assert_eq!(
+                        feature_type(series.dtype()),
+                        FeatureType::Numeric,
+                        "Column {} is not numeric.",
+                        series.name()
+                    );
+                    true
+                }
+            })
+            .collect();
+        DataFrame::new(columns).expect("Cannot build a frame from the feature columns")
+    }
+}
+
+/// Get the kind of values a polars column holds
+pub(crate) fn feature_type(dtype: &DataType) -> FeatureType {
+    match dtype {
+        DataType::Float32
+        | DataType::Float64
+        | DataType::Int32
+        | DataType::Int64
+        | DataType::UInt32
+        | DataType::UInt64 => FeatureType::Numeric,
+        DataType::Boolean => FeatureType::Boolean,
+        DataType::Utf8 => FeatureType::Text,
+        _ => FeatureType::Other,
+    }
+}
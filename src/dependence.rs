@@ -0,0 +1,460 @@
+//! # Partial dependence
+//! This module contains the results of
+//! [`SupervisedModel::partial_dependence`](crate::SupervisedModel::partial_dependence) and
+//! [`SupervisedModel::partial_dependence_2d`](crate::SupervisedModel::partial_dependence_2d).
+//! A partial dependence curve shows how the prediction of the final model changes, on average over
+//! the training data, as one feature is moved along a grid of values. Individual conditional
+//! expectation (ICE) curves show the same thing for each training sample separately, which reveals
+//! interactions that the average hides. Results can be serialized, written as CSV, or drawn as an
+//! SVG plot.
+//! ```
+//! # use automl::{SupervisedModel, Settings};
+//! let mut model = SupervisedModel::new_from_dataset(
+//!     smartcore::dataset::diabetes::load_dataset(),
+//!     Settings::default_regression(),
+//! );
+//! model.train();
+//! let grid = model.feature_grid(2, 20);
+//! let dependence = model.partial_dependence(2, grid);
+//! std::fs::write("tests/dependence.svg", dependence.to_svg()).unwrap();
+//! # std::fs::remove_file("tests/dependence.svg");
+//! ```
+
+use std::fmt::Write;
+
+/// Width of SVG plots in pixels
+const WIDTH: f32 = 640.0;
+/// Height of SVG plots in pixels
+const HEIGHT: f32 = 420.0;
+/// Space left around the plot area for the axes, as (left, right, top, bottom)
+const MARGIN: (f32, f32, f32, f32) = (70.0, 30.0, 40.0, 55.0);
+
+/// Partial dependence and ICE curves for a single feature
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct PartialDependence {
+    /// Name of the feature
+    pub(crate) feature: String,
+    /// Values the feature was set to
+    pub(crate) grid: Vec<f32>,
+    /// Mean prediction at each grid value
+    pub(crate) average: Vec<f32>,
+    /// Prediction for each training sample at each grid value
+    pub(crate) individual: Vec<Vec<f32>>,
+}
+
+impl PartialDependence {
+    /// Name of the feature
+    /// ```
+    /// # use automl::{SupervisedModel, Settings, settings::Algorithm};
+    /// # let mut model = SupervisedModel::new_from_dataset(
+    /// #     smartcore::dataset::diabetes::load_dataset(),
+    /// #     Settings::default_regression().only(Algorithm::Linear),
+    /// # );
+    /// # model.train();
+    /// let dependence = model.partial_dependence(2, vec![-0.1, 0.0, 0.1]);
+    /// assert_eq!(dependence.feature(), "x_2");
+    /// ```
+    pub fn feature(&self) -> &str {
+        &self.feature
+    }
+
+    /// Values the feature was set to
+    /// ```
+    /// # use automl::{SupervisedModel, Settings, settings::Algorithm};
+    /// # let mut model = SupervisedModel::new_from_dataset(
+    /// #     smartcore::dataset::diabetes::load_dataset(),
+    /// #     Settings::default_regression().only(Algorithm::Linear),
+    /// # );
+    /// # model.train();
+    /// let dependence = model.partial_dependence(2, vec![-0.1, 0.0, 0.1]);
+    /// assert_eq!(dependence.grid(), &[-0.1, 0.0, 0.1]);
+    /// ```
+    pub fn grid(&self) -> &[f32] {
+        &self.grid
+    }
+
+    /// Mean prediction over the training data at each grid value
+    /// ```
+    /// # use automl::{SupervisedModel, Settings, settings::Algorithm};
+    /// # let mut model = SupervisedModel::new_from_dataset(
+    /// #     smartcore::dataset::diabetes::load_dataset(),
+    /// #     Settings::default_regression().only(Algorithm::Linear),
+    /// # );
+    /// # model.train();
+    /// let dependence = model.partial_dependence(2, vec![-0.1, 0.0, 0.1]);
+    /// assert_eq!(dependence.average().len(), 3);
+    /// ```
+    pub fn average(&self) -> &[f32] {
+        &self.average
+    }
+
+    /// ICE curves, with one row per training sample and one prediction per grid value
+    /// ```
+    /// # use automl::{SupervisedModel, Settings, settings::Algorithm};
+    /// # let mut model = SupervisedModel::new_from_dataset(
+    /// #     smartcore::dataset::diabetes::load_dataset(),
+    /// #     Settings::default_regression().only(Algorithm::Linear),
+    /// # );
+    /// # model.train();
+    /// let dependence = model.partial_dependence(2, vec![-0.1, 0.0, 0.1]);
+    /// assert_eq!(dependence.individual().len(), 442);
+    /// ```
+    pub fn individual(&self) -> &[Vec<f32>] {
+        &self.individual
+    }
+
+    /// Write the curves as CSV, with a column for the grid, one for the average, and one for each
+    /// ICE curve
+    /// ```
+    /// # use automl::{SupervisedModel, Settings, settings::Algorithm};
+    /// # let mut model = SupervisedModel::new_from_dataset(
+    /// #     smartcore::dataset::diabetes::load_dataset(),
+    /// #     Settings::default_regression().only(Algorithm::Linear),
+    /// # );
+    /// # model.train();
+    /// let csv = model.partial_dependence(2, vec![-0.1, 0.0, 0.1]).to_csv();
+    /// ```
+    pub fn to_csv(&self) -> String {
+        let mut csv = format!("{},average", self.feature);
+        for sample in 0..self.individual.len() {
+            write!(csv, ",ice_{}", sample).unwrap();
+        }
+        csv.push('\n');
+        for (point, value) in self.grid.iter().enumerate() {
+            write!(csv, "{},{}", value, self.average[point]).unwrap();
+            for curve in &self.individual {
+                write!(csv, ",{}", curve[point]).unwrap();
+            }
+            csv.push('\n');
+        }
+        csv
+    }
+
+    /// Draw the ICE curves in grey with the partial dependence curve on top as an SVG image
+    /// ```
+    /// # use automl::{SupervisedModel, Settings, settings::Algorithm};
+    /// # let mut model = SupervisedModel::new_from_dataset(
+    /// #     smartcore::dataset::diabetes::load_dataset(),
+    /// #     Settings::default_regression().only(Algorithm::Linear),
+    /// # );
+    /// # model.train();
+    /// let svg = model.partial_dependence(2, vec![-0.1, 0.0, 0.1]).to_svg();
+    /// ```
+    pub fn to_svg(&self) -> String {
+        let x_range = range(self.grid.iter());
+        let y_range = range(self.individual.iter().flatten().chain(self.average.iter()));
+
+        let mut svg = svg_header(&format!("Partial dependence on {}", self.feature));
+        svg.push_str(&axes(x_range, y_range, &self.feature, "Prediction"));
+        for curve in &self.individual {
+            svg.push_str(&polyline(
+                &self.grid, curve, x_range, y_range, "#bbbbbb", 0.5,
+            ));
+        }
+        svg.push_str(&polyline(
+            &self.grid,
+            &self.average,
+            x_range,
+            y_range,
+            "#d62728",
+            2.5,
+        ));
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
+
+/// Partial dependence on a pair of features
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct PartialDependence2D {
+    /// Names of the two features
+    pub(crate) features: (String, String),
+    /// Values each feature was set to
+    pub(crate) grids: (Vec<f32>, Vec<f32>),
+    /// Mean prediction, indexed by position in the first grid and then in the second
+    pub(crate) average: Vec<Vec<f32>>,
+}
+
+impl PartialDependence2D {
+    /// Names of the two features
+    /// ```
+    /// # use automl::{SupervisedModel, Settings, settings::Algorithm};
+    /// # let mut model = SupervisedModel::new_from_dataset(
+    /// #     smartcore::dataset::diabetes::load_dataset(),
+    /// #     Settings::default_regression().only(Algorithm::Linear),
+    /// # );
+    /// # model.train();
+    /// let dependence = model.partial_dependence_2d((2, 3), (vec![-0.1, 0.1], vec![0.0, 0.1]));
+    /// assert_eq!(dependence.features(), ("x_2", "x_3"));
+    /// ```
+    pub fn features(&self) -> (&str, &str) {
+        (&self.features.0, &self.features.1)
+    }
+
+    /// Values each feature was set to
+    /// ```
+    /// # use automl::{SupervisedModel, Settings, settings::Algorithm};
+    /// # let mut model = SupervisedModel::new_from_dataset(
+    /// #     smartcore::dataset::diabetes::load_dataset(),
+    /// #     Settings::default_regression().only(Algorithm::Linear),
+    /// # );
+    /// # model.train();
+    /// let dependence = model.partial_dependence_2d((2, 3), (vec![-0.1, 0.1], vec![0.0, 0.1]));
+    /// assert_eq!(dependence.grids().1, &[0.0, 0.1]);
+    /// ```
+    pub fn grids(&self) -> (&[f32], &[f32]) {
+        (&self.grids.0, &self.grids.1)
+    }
+
+    /// Mean prediction over the training data, where `average()[i][j]` has the first feature at
+    /// its `i`th grid value and the second at its `j`th
+    /// ```
+    /// # use automl::{SupervisedModel, Settings, settings::Algorithm};
+    /// # let mut model = SupervisedModel::new_from_dataset(
+    /// #     smartcore::dataset::diabetes::load_dataset(),
+    /// #     Settings::default_regression().only(Algorithm::Linear),
+    /// # );
+    /// # model.train();
+    /// let dependence = model.partial_dependence_2d((2, 3), (vec![-0.1, 0.1], vec![0.0, 0.1]));
+    /// let corner = dependence.average()[1][0];
+    /// ```
+    pub fn average(&self) -> &[Vec<f32>] {
+        &self.average
+    }
+
+    /// Write the surface as CSV in long format, with one row per pair of grid values
+    /// ```
+    /// # use automl::{SupervisedModel, Settings, settings::Algorithm};
+    /// # let mut model = SupervisedModel::new_from_dataset(
+    /// #     smartcore::dataset::diabetes::load_dataset(),
+    /// #     Settings::default_regression().only(Algorithm::Linear),
+    /// # );
+    /// # model.train();
+    /// let dependence = model.partial_dependence_2d((2, 3), (vec![-0.1, 0.1], vec![0.0, 0.1]));
+    /// assert_eq!(dependence.to_csv().lines().count(), 5);
+    /// ```
+    pub fn to_csv(&self) -> String {
+        let mut csv = format!("{},{},average\n", self.features.0, self.features.1);
+        for (i, first) in self.grids.0.iter().enumerate() {
+            for (j, second) in self.grids.1.iter().enumerate() {
+                writeln!(csv, "{},{},{}", first, second, self.average[i][j]).unwrap();
+            }
+        }
+        csv
+    }
+
+    /// Draw the surface as an SVG heat map, from blue for low predictions to red for high ones
+    /// ```
+    /// # use automl::{SupervisedModel, Settings, settings::Algorithm};
+    /// # let mut model = SupervisedModel::new_from_dataset(
+    /// #     smartcore::dataset::diabetes::load_dataset(),
+    /// #     Settings::default_regression().only(Algorithm::Linear),
+    /// # );
+    /// # model.train();
+    /// let dependence = model.partial_dependence_2d((2, 3), (vec![-0.1, 0.1], vec![0.0, 0.1]));
+    /// let svg = dependence.to_svg();
+    /// ```
+    pub fn to_svg(&self) -> String {
+        let x_edges = cell_edges(&self.grids.0);
+        let y_edges = cell_edges(&self.grids.1);
+        let x_range = (x_edges[0], x_edges[x_edges.len() - 1]);
+        let y_range = (y_edges[0], y_edges[y_edges.len() - 1]);
+        let z_range = range(self.average.iter().flatten());
+
+        let mut svg = svg_header(&format!(
+            "Partial dependence on {} and {} (prediction from {} to {})",
+            self.features.0,
+            self.features.1,
+            label(z_range.0),
+            label(z_range.1)
+        ));
+        for (i, x) in x_edges.windows(2).enumerate() {
+            for (j, y) in y_edges.windows(2).enumerate() {
+                let left = x_position(x[0], x_range);
+                let top = y_position(y[1], y_range);
+                writeln!(
+                    svg,
+                    "<rect x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{:.1}\" fill=\"{}\"/>",
+                    left,
+                    top,
+                    x_position(x[1], x_range) - left + 0.5,
+                    y_position(y[0], y_range) - top + 0.5,
+                    color(self.average[i][j], z_range)
+                )
+                .unwrap();
+            }
+        }
+        svg.push_str(&axes(x_range, y_range, &self.features.0, &self.features.1));
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
+
+/// Boundaries of the heat map cells, halfway between neighbouring grid values
+fn cell_edges(grid: &[f32]) -> Vec<f32> {
+    match grid.len() {
+        0 => vec![0.0, 1.0],
+        1 => vec![grid[0] - 0.5, grid[0] + 0.5],
+        n => {
+            let mut edges = vec![grid[0] - (grid[1] - grid[0]) / 2.0];
+            edges.extend(grid.windows(2).map(|pair| (pair[0] + pair[1]) / 2.0));
+            edges.push(grid[n - 1] + (grid[n - 1] - grid[n - 2]) / 2.0);
+            edges
+        }
+    }
+}
+
+/// Smallest and largest of a set of values, widened when they are equal so they can be scaled
+fn range<'a>(values: impl Iterator<Item = &'a f32>) -> (f32, f32) {
+    let (min, max) = values.fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), value| {
+        (min.min(*value), max.max(*value))
+    });
+    if !min.is_finite() || !max.is_finite() {
+        (0.0, 1.0)
+    } else if min == max {
+        (min - 0.5, max + 0.5)
+    } else {
+        (min, max)
+    }
+}
+
+/// Width and height of the area inside the axes
+fn plot_size() -> (f32, f32) {
+    (WIDTH - MARGIN.0 - MARGIN.1, HEIGHT - MARGIN.2 - MARGIN.3)
+}
+
+/// Horizontal pixel position of a value
+fn x_position(value: f32, range: (f32, f32)) -> f32 {
+    MARGIN.0 + (value - range.0) / (range.1 - range.0) * plot_size().0
+}
+
+/// Vertical pixel position of a value, with larger values higher up
+fn y_position(value: f32, range: (f32, f32)) -> f32 {
+    MARGIN.2 + (range.1 - value) / (range.1 - range.0) * plot_size().1
+}
+
+/// Format an axis label compactly
+fn label(value: f32) -> String {
+    if value != 0.0 && (value.abs() < 0.01 || value.abs() >= 10000.0) {
+        format!("{:.2e}", value)
+    } else {
+        format!("{:.2}", value)
+    }
+}
+
+/// Escape the characters that have a meaning in SVG text
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// Open an SVG document with a white background and a title
+fn svg_header(title: &str) -> String {
+    format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" \
+         viewBox=\"0 0 {w} {h}\" font-family=\"sans-serif\" font-size=\"12\">\n\
+         <rect width=\"{w}\" height=\"{h}\" fill=\"white\"/>\n\
+         <text x=\"{x}\" y=\"24\" text-anchor=\"middle\" font-size=\"14\">{title}</text>\n",
+        w = WIDTH,
+        h = HEIGHT,
+        x = WIDTH / 2.0,
+        title = escape(title)
+    )
+}
+
+/// Draw both axes with five labelled ticks each
+fn axes(x_range: (f32, f32), y_range: (f32, f32), x_label: &str, y_label: &str) -> String {
+    let (plot_width, plot_height) = plot_size();
+    let bottom = MARGIN.2 + plot_height;
+    let mut svg = format!(
+        "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"none\" stroke=\"black\"/>\n",
+        MARGIN.0, MARGIN.2, plot_width, plot_height
+    );
+    for tick in 0..5 {
+        let fraction = tick as f32 / 4.0;
+        let x_value = x_range.0 + fraction * (x_range.1 - x_range.0);
+        let x = x_position(x_value, x_range);
+        writeln!(
+            svg,
+            "<line x1=\"{x:.1}\" y1=\"{b:.1}\" x2=\"{x:.1}\" y2=\"{t:.1}\" stroke=\"black\"/>\
+             <text x=\"{x:.1}\" y=\"{l:.1}\" text-anchor=\"middle\">{v}</text>",
+            x = x,
+            b = bottom,
+            t = bottom + 5.0,
+            l = bottom + 18.0,
+            v = label(x_value)
+        )
+        .unwrap();
+
+        let y_value = y_range.0 + fraction * (y_range.1 - y_range.0);
+        let y = y_position(y_value, y_range);
+        writeln!(
+            svg,
+            "<line x1=\"{r:.1}\" y1=\"{y:.1}\" x2=\"{l:.1}\" y2=\"{y:.1}\" stroke=\"black\"/>\
+             <text x=\"{t:.1}\" y=\"{y:.1}\" text-anchor=\"end\" dy=\"4\">{v}</text>",
+            r = MARGIN.0,
+            l = MARGIN.0 - 5.0,
+            t = MARGIN.0 - 8.0,
+            y = y,
+            v = label(y_value)
+        )
+        .unwrap();
+    }
+    writeln!(
+        svg,
+        "<text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"middle\">{}</text>",
+        MARGIN.0 + plot_width / 2.0,
+        HEIGHT - 12.0,
+        escape(x_label)
+    )
+    .unwrap();
+    writeln!(
+        svg,
+        "<text transform=\"translate(16 {:.1}) rotate(-90)\" text-anchor=\"middle\">{}</text>",
+        MARGIN.2 + plot_height / 2.0,
+        escape(y_label)
+    )
+    .unwrap();
+    svg
+}
+
+/// Draw a line through a set of points
+fn polyline(
+    x: &[f32],
+    y: &[f32],
+    x_range: (f32, f32),
+    y_range: (f32, f32),
+    stroke: &str,
+    width: f32,
+) -> String {
+    let points: Vec<String> = x
+        .iter()
+        .zip(y)
+        .map(|(x, y)| {
+            format!(
+                "{:.1},{:.1}",
+                x_position(*x, x_range),
+                y_position(*y, y_range)
+            )
+        })
+        .collect();
+    format!(
+        "<polyline points=\"{}\" fill=\"none\" stroke=\"{}\" stroke-width=\"{}\"/>\n",
+        points.join(" "),
+        stroke,
+        width
+    )
+}
+
+/// Blend from blue at the bottom of the range to red at the top
+fn color(value: f32, range: (f32, f32)) -> String {
+    let fraction = ((value - range.0) / (range.1 - range.0)).clamp(0.0, 1.0);
+    let blend = |low: f32, high: f32| (low + fraction * (high - low)).round() as u8;
+    format!(
+        "rgb({},{},{})",
+        blend(49.0, 215.0),
+        blend(54.0, 48.0),
+        blend(149.0, 39.0)
+    )
+}
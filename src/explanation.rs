@@ -0,0 +1,347 @@
+//! # Model explanations
+//! This module contains the types used to look inside a trained model. For linear models,
+//! [`SupervisedModel::coefficients`](crate::SupervisedModel::coefficients) returns the fitted
+//! [`Coefficients`], labelled with the names of the features the model actually saw. When the
+//! settings add interaction or polynomial terms these are named after the features they combine
+//! (e.g. `x_1*x_3`), and PCA or SVD components are named `pca_0`, `svd_0` and so on.
+//! ```
+//! # use automl::{SupervisedModel, Settings};
+//! use automl::settings::{Algorithm, PreProcessing};
+//! let mut model = SupervisedModel::new_from_dataset(
+//!     smartcore::dataset::diabetes::load_dataset(),
+//!     Settings::default_regression()
+//!         .only(Algorithm::Linear)
+//!         .with_preprocessing(PreProcessing::AddInteractions),
+//! );
+//! model.train();
+//! let coefficients = model.coefficients(Algorithm::Linear).unwrap();
+//! println!("{:?}", coefficients.coefficient("x_1*x_3"));
+//! ```
+//!
+//! Individual predictions are explained with SHAP values through
+//! [`SupervisedModel::explain`](crate::SupervisedModel::explain), which returns an
+//! [`Explanation`] for each row giving the contribution of every input feature.
+
+use rand::{
+    distributions::{Distribution, WeightedIndex},
+    rngs::StdRng,
+    seq::SliceRandom,
+    SeedableRng,
+};
+
+/// The fitted coefficients of a linear model
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct Coefficients {
+    /// Names of the features after preprocessing
+    features: Vec<String>,
+    /// One row of coefficients per class, or a single row for regression and binary models
+    coefficients: Vec<Vec<f32>>,
+    /// One intercept per row of coefficients
+    intercepts: Vec<f32>,
+}
+
+impl Coefficients {
+    /// Label fitted coefficients with their feature names
+    pub(crate) fn new(
+        features: Vec<String>,
+        coefficients: Vec<Vec<f32>>,
+        intercepts: Vec<f32>,
+    ) -> Self {
+        Self {
+            features,
+            coefficients,
+            intercepts,
+        }
+    }
+
+    /// Names of the features the model was fitted on, in the same order as the coefficients
+    /// ```
+    /// # use automl::{SupervisedModel, Settings, settings::Algorithm};
+    /// # let mut model = SupervisedModel::new_from_dataset(
+    /// #     smartcore::dataset::diabetes::load_dataset(),
+    /// #     Settings::default_regression().only(Algorithm::Linear),
+    /// # );
+    /// # model.train();
+    /// let coefficients = model.coefficients(Algorithm::Linear).unwrap();
+    /// assert_eq!(coefficients.features()[0], "x_0");
+    /// ```
+    pub fn features(&self) -> &[String] {
+        &self.features
+    }
+
+    /// The fitted coefficients, with one row per class for multiclass logistic regression and a
+    /// single row otherwise
+    /// ```
+    /// # use automl::{SupervisedModel, Settings, settings::Algorithm};
+    /// # let mut model = SupervisedModel::new_from_dataset(
+    /// #     smartcore::dataset::diabetes::load_dataset(),
+    /// #     Settings::default_regression().only(Algorithm::Linear),
+    /// # );
+    /// # model.train();
+    /// let coefficients = model.coefficients(Algorithm::Linear).unwrap();
+    /// assert_eq!(coefficients.coefficients()[0].len(), 10);
+    /// ```
+    pub fn coefficients(&self) -> &[Vec<f32>] {
+        &self.coefficients
+    }
+
+    /// The fitted intercepts, one for each row of coefficients
+    /// ```
+    /// # use automl::{SupervisedModel, Settings, settings::Algorithm};
+    /// # let mut model = SupervisedModel::new_from_dataset(
+    /// #     smartcore::dataset::diabetes::load_dataset(),
+    /// #     Settings::default_regression().only(Algorithm::Linear),
+    /// # );
+    /// # model.train();
+    /// let intercept = model.coefficients(Algorithm::Linear).unwrap().intercepts()[0];
+    /// ```
+    pub fn intercepts(&self) -> &[f32] {
+        &self.intercepts
+    }
+
+    /// Look up the coefficient of a feature by name, from the first row of coefficients
+    /// ```
+    /// # use automl::{SupervisedModel, Settings, settings::Algorithm};
+    /// # let mut model = SupervisedModel::new_from_dataset(
+    /// #     smartcore::dataset::diabetes::load_dataset(),
+    /// #     Settings::default_regression().only(Algorithm::Linear),
+    /// # );
+    /// # model.train();
+    /// let coefficients = model.coefficients(Algorithm::Linear).unwrap();
+    /// assert!(coefficients.coefficient("x_2").is_some());
+    /// assert!(coefficients.coefficient("x_20").is_none());
+    /// ```
+    pub fn coefficient(&self, feature: &str) -> Option<f32> {
+        let column = self.features.iter().position(|name| name == feature)?;
+        self.coefficients.first().map(|row| row[column])
+    }
+}
+
+/// The contribution of each feature to a single prediction, as SHAP values. The base value is the
+/// expected model output over the background data, and the contributions add up to the
+/// difference between the prediction and the base value.
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct Explanation {
+    /// Names of the input features
+    features: Vec<String>,
+    /// Expected model output over the background data
+    base_value: f32,
+    /// Contribution of each feature
+    contributions: Vec<f32>,
+}
+
+impl Explanation {
+    /// Label SHAP values with their feature names
+    pub(crate) fn new(features: Vec<String>, base_value: f32, contributions: Vec<f32>) -> Self {
+        Self {
+            features,
+            base_value,
+            contributions,
+        }
+    }
+
+    /// Names of the input features, in the same order as the contributions
+    /// ```
+    /// # use automl::{SupervisedModel, Settings, settings::Algorithm};
+    /// # let mut model = SupervisedModel::new_from_dataset(
+    /// #     smartcore::dataset::diabetes::load_dataset(),
+    /// #     Settings::default_regression().only(Algorithm::DecisionTreeRegressor),
+    /// # );
+    /// # model.train();
+    /// let explanation = &model.explain(vec![vec![0.01; 10]])[0];
+    /// assert_eq!(explanation.features()[0], "x_0");
+    /// ```
+    pub fn features(&self) -> &[String] {
+        &self.features
+    }
+
+    /// The expected model output over the background data
+    /// ```
+    /// # use automl::{SupervisedModel, Settings, settings::Algorithm};
+    /// # let mut model = SupervisedModel::new_from_dataset(
+    /// #     smartcore::dataset::diabetes::load_dataset(),
+    /// #     Settings::default_regression().only(Algorithm::DecisionTreeRegressor),
+    /// # );
+    /// # model.train();
+    /// let base_value = model.explain(vec![vec![0.01; 10]])[0].base_value();
+    /// ```
+    pub fn base_value(&self) -> f32 {
+        self.base_value
+    }
+
+    /// The SHAP value of each feature
+    /// ```
+    /// # use automl::{SupervisedModel, Settings, settings::Algorithm};
+    /// # let mut model = SupervisedModel::new_from_dataset(
+    /// #     smartcore::dataset::diabetes::load_dataset(),
+    /// #     Settings::default_regression().only(Algorithm::DecisionTreeRegressor),
+    /// # );
+    /// # model.train();
+    /// let explanation = &model.explain(vec![vec![0.01; 10]])[0];
+    /// assert_eq!(explanation.contributions().len(), 10);
+    /// ```
+    pub fn contributions(&self) -> &[f32] {
+        &self.contributions
+    }
+
+    /// Look up the SHAP value of a feature by name
+    /// ```
+    /// # use automl::{SupervisedModel, Settings, settings::Algorithm};
+    /// # let mut model = SupervisedModel::new_from_dataset(
+    /// #     smartcore::dataset::diabetes::load_dataset(),
+    /// #     Settings::default_regression().only(Algorithm::DecisionTreeRegressor),
+    /// # );
+    /// # model.train();
+    /// let explanation = &model.explain(vec![vec![0.01; 10]])[0];
+    /// assert!(explanation.contribution("x_2").is_some());
+    /// ```
+    pub fn contribution(&self, feature: &str) -> Option<f32> {
+        let column = self.features.iter().position(|name| name == feature)?;
+        Some(self.contributions[column])
+    }
+
+    /// The explained model output, which is the base value plus every contribution. This is the
+    /// prediction, except for random forest classifiers, where it is the mean vote of the trees.
+    /// ```
+    /// # use automl::{SupervisedModel, Settings, settings::Algorithm};
+    /// # let mut model = SupervisedModel::new_from_dataset(
+    /// #     smartcore::dataset::diabetes::load_dataset(),
+    /// #     Settings::default_regression().only(Algorithm::DecisionTreeRegressor),
+    /// # );
+    /// # model.train();
+    /// let x = vec![vec![0.01; 10]];
+    /// let output = model.explain(x.clone())[0].output();
+    /// let prediction = model.predict_from_vec(x)[0];
+    /// assert!((output - prediction).abs() < 1e-3 * prediction.abs());
+    /// ```
+    pub fn output(&self) -> f32 {
+        self.base_value + self.contributions.iter().sum::<f32>()
+    }
+}
+
+/// Number of background rows Kernel SHAP averages over
+pub(crate) const BACKGROUND_SIZE: usize = 50;
+
+/// Largest number of coalitions Kernel SHAP evaluates. Every coalition is used when there are
+/// few enough features, and the rest are sampled.
+const MAX_COALITIONS: usize = 2048;
+
+/// A subset of features whose values are taken from the explained row, with its weight
+pub(crate) type Coalition = (Vec<bool>, f64);
+
+/// Choose the coalitions for Kernel SHAP, with their Shapley kernel weights
+pub(crate) fn kernel_coalitions(width: usize) -> Vec<Coalition> {
+    let kernel =
+        |size: usize| (width - 1) as f64 / (binomial(width, size) * (size * (width - size)) as f64);
+
+    if width < 2 {
+        vec![]
+    } else if (1_usize << width.min(63)) - 2 <= MAX_COALITIONS {
+        // Enumerate every coalition except the empty and full ones
+        (1..(1_usize << width) - 1)
+            .map(|mask| {
+                let coalition: Vec<bool> = (0..width).map(|j| mask & (1 << j) != 0).collect();
+                let size = coalition.iter().filter(|&&known| known).count();
+                (coalition, kernel(size))
+            })
+            .collect()
+    } else {
+        // Sample coalition sizes in proportion to their total kernel weight, pairing each sample
+        // with its complement, so every sampled coalition carries the same weight
+        let mut rng = StdRng::seed_from_u64(0);
+        let size_weights: Vec<f64> = (1..width)
+            .map(|size| kernel(size) * binomial(width, size))
+            .collect();
+        let sizes = WeightedIndex::new(&size_weights).unwrap();
+        let mut features: Vec<usize> = (0..width).collect();
+        let mut coalitions = Vec::with_capacity(MAX_COALITIONS);
+        while coalitions.len() < MAX_COALITIONS {
+            let size = sizes.sample(&mut rng) + 1;
+            features.shuffle(&mut rng);
+            let mut coalition = vec![false; width];
+            for feature in &features[..size] {
+                coalition[*feature] = true;
+            }
+            let complement = coalition.iter().map(|known| !known).collect();
+            coalitions.push((coalition, 1.0));
+            coalitions.push((complement, 1.0));
+        }
+        coalitions
+    }
+}
+
+/// Solve the Kernel SHAP weighted least squares problem, constrained so that the contributions
+/// add up to the difference between the prediction and the base value
+pub(crate) fn kernel_shap(
+    coalitions: &[Coalition],
+    values: &[f64],
+    base_value: f64,
+    prediction: f64,
+) -> Vec<f32> {
+    let difference = prediction - base_value;
+    let width = match coalitions.first() {
+        Some((coalition, _)) => coalition.len(),
+        None => return vec![difference as f32],
+    };
+
+    // Eliminate the last contribution using the constraint, then build the normal equations
+    let last = width - 1;
+    let mut lhs = vec![vec![0.0; last]; last];
+    let mut rhs = vec![0.0; last];
+    for ((coalition, weight), value) in coalitions.iter().zip(values) {
+        let known = |j: usize| if coalition[j] { 1.0 } else { 0.0 };
+        let target = value - base_value - known(last) * difference;
+        let row: Vec<f64> = (0..last).map(|j| known(j) - known(last)).collect();
+        for i in 0..last {
+            rhs[i] += weight * row[i] * target;
+            for j in 0..last {
+                lhs[i][j] += weight * row[i] * row[j];
+            }
+        }
+    }
+
+    let mut contributions = solve(lhs, rhs);
+    contributions.push(difference - contributions.iter().sum::<f64>());
+    contributions
+        .into_iter()
+        .map(|value| value as f32)
+        .collect()
+}
+
+/// Solve a small linear system by Gaussian elimination with partial pivoting
+fn solve(mut lhs: Vec<Vec<f64>>, mut rhs: Vec<f64>) -> Vec<f64> {
+    let n = rhs.len();
+    for column in 0..n {
+        let pivot = (column..n)
+            .max_by(|&a, &b| lhs[a][column].abs().total_cmp(&lhs[b][column].abs()))
+            .unwrap();
+        lhs.swap(column, pivot);
+        rhs.swap(column, pivot);
+        if lhs[column][column].abs() < 1e-12 {
+            continue;
+        }
+        let pivot_row = lhs[column].clone();
+        for row in (column + 1)..n {
+            let factor = lhs[row][column] / pivot_row[column];
+            for (value, pivot_value) in lhs[row][column..].iter_mut().zip(&pivot_row[column..]) {
+                *value -= factor * pivot_value;
+            }
+            rhs[row] -= factor * rhs[column];
+        }
+    }
+
+    let mut solution = vec![0.0; n];
+    for row in (0..n).rev() {
+        if lhs[row][row].abs() < 1e-12 {
+            continue;
+        }
+        let known: f64 = ((row + 1)..n).map(|k| lhs[row][k] * solution[k]).sum();
+        solution[row] = (rhs[row] - known) / lhs[row][row];
+    }
+    solution
+}
+
+/// The number of ways to choose `k` of `n` items
+fn binomial(n: usize, k: usize) -> f64 {
+    (0..k).fold(1.0, |total, i| total * (n - i) as f64 / (i + 1) as f64)
+}
@@ -0,0 +1,470 @@
+//! # Model file format
+//! This module describes the `.aml` files written by
+//! [`SupervisedModel::save`](crate::SupervisedModel::save) and
+//! [`Predictor::save`](crate::predictor::Predictor::save). Each file starts with a header that
+//! records
+//! * the magic bytes `AUML`, so other files are rejected straight away,
+//! * the version of the file format,
+//! * whether the file holds a full model or just a predictor,
+//! * the versions of automl and smartcore that wrote the file,
+//! * when the file was written, and
+//! * a checksum of the serialized model.
+//!
+//! When a file is loaded, the header is checked before the model itself is touched, so that a
+//! file from an incompatible version gives a [`LoadError`] explaining why, rather than failing
+//! halfway through or being misread. Files written with an older format version are upgraded by
+//! the migrations in this module before they are deserialized. The first version 1 files predate
+//! predictors and have no contents in their header, so they are read as full models.
+//! ```
+//! # use automl::{SupervisedModel, Settings};
+//! use automl::file_format::{read_header, FORMAT_VERSION};
+//! let model = SupervisedModel::new_from_dataset(
+//!     smartcore::dataset::diabetes::load_dataset(),
+//!     Settings::default_regression(),
+//! );
+//! model.save("tests/header_model.aml");
+//! let header = read_header("tests/header_model.aml").unwrap();
+//! assert_eq!(header.format_version(), FORMAT_VERSION);
+//! # std::fs::remove_file("tests/header_model.aml");
+//! ```
+
+use serde::{de::DeserializeOwned, Serialize};
+use std::fmt::{Display, Formatter};
+use std::io::Write;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// The bytes every model file starts with
+const MAGIC: [u8; 4] = *b"AUML";
+
+/// The version of the file format written by this version of automl
+pub const FORMAT_VERSION: u32 = 9;
+
+/// The series of smartcore releases whose models can be read by this version of automl. Update it
+/// along with the smartcore dependency in `Cargo.toml`.
+const SMARTCORE_VERSION: &str = "0.2";
+
+/// Upgrades a serialized model from one format version to the next
+type Migration = fn(Contents, Vec<u8>) -> Result<Vec<u8>, LoadError>;
+
+/// Upgrades from each older format version to the next, so that `MIGRATIONS[0]` turns a version 1
+/// payload into a version 2 payload. Add a step here whenever [`FORMAT_VERSION`] is bumped.
+const MIGRATIONS: [Migration; FORMAT_VERSION as usize - 1] = [
+    add_csv_options,
+    add_svc_strategy,
+    add_gradient_boosting,
+    add_extra_trees,
+    add_mlp,
+    tag_svc_models,
+    add_glm,
+    add_quantile,
+];
+
+/// Version 2 saves the CSV parsing options as the last field of a full model
+fn add_csv_options(contents: Contents, mut payload: Vec<u8>) -> Result<Vec<u8>, LoadError> {
+    if contents == Contents::SupervisedModel {
+        payload.extend(
+            bincode::serialize(&crate::csv_options::CsvOptions::default())
+                .map_err(|error| LoadError::Corrupted(error.to_string()))?,
+        );
+    }
+    Ok(payload)
+}
+
+/// Version 3 adds a multiclass strategy to the SVC parameters in the settings
+fn add_svc_strategy(_: Contents, payload: Vec<u8>) -> Result<Vec<u8>, LoadError> {
+    crate::settings::add_svc_strategy(payload)
+        .map_err(|error| LoadError::Corrupted(error.to_string()))
+}
+
+/// Gradient boosting arrived in version 4, so older settings get its parameters, left unset
+fn add_gradient_boosting(_: Contents, payload: Vec<u8>) -> Result<Vec<u8>, LoadError> {
+    crate::settings::add_gradient_boosting(payload)
+        .map_err(|error| LoadError::Corrupted(error.to_string()))
+}
+
+/// Version 5 files can also hold extra trees parameters
+fn add_extra_trees(_: Contents, payload: Vec<u8>) -> Result<Vec<u8>, LoadError> {
+    crate::settings::add_extra_trees(payload)
+        .map_err(|error| LoadError::Corrupted(error.to_string()))
+}
+
+/// Files from before version 6 have no multilayer perceptron parameters
+fn add_mlp(_: Contents, payload: Vec<u8>) -> Result<Vec<u8>, LoadError> {
+    crate::settings::add_mlp(payload).map_err(|error| LoadError::Corrupted(error.to_string()))
+}
+
+/// Version 7 saves every SVC with a tag saying whether it is binary or multiclass
+fn tag_svc_models(contents: Contents, payload: Vec<u8>) -> Result<Vec<u8>, LoadError> {
+    match contents {
+        Contents::SupervisedModel => crate::SupervisedModel::tag_svc_models(payload),
+        Contents::Predictor => crate::predictor::Predictor::tag_svc_models(payload),
+    }
+    .map_err(|error| LoadError::Corrupted(error.to_string()))
+}
+
+/// Generalized linear models were added in version 8, after the perceptron parameters
+fn add_glm(_: Contents, payload: Vec<u8>) -> Result<Vec<u8>, LoadError> {
+    crate::settings::add_glm(payload).map_err(|error| LoadError::Corrupted(error.to_string()))
+}
+
+/// Version 9 added quantile regression, whose parameters follow those of the generalized linear
+/// models. Full models keep the models behind prediction intervals with each compared model, and
+/// predictors save the CSV options as their last field.
+fn add_quantile(contents: Contents, payload: Vec<u8>) -> Result<Vec<u8>, LoadError> {
+    match contents {
+        Contents::SupervisedModel => crate::SupervisedModel::add_interval_models(payload),
+        Contents::Predictor => crate::predictor::Predictor::add_csv_options(payload),
+    }
+    .and_then(crate::settings::add_quantile)
+    .map_err(|error| LoadError::Corrupted(error.to_string()))
+}
+
+/// The header at the start of a model file
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Header {
+    /// The version of the file format
+    format_version: u32,
+    /// What the file holds
+    contents: Contents,
+    /// The version of automl that wrote the file
+    crate_version: String,
+    /// The series of smartcore releases that wrote the file
+    smartcore_version: String,
+    /// When the file was written, in seconds since the Unix epoch
+    created: u64,
+    /// The FNV-1a hash of the serialized model
+    checksum: u64,
+}
+
+impl Header {
+    /// Get the version of the file format
+    /// ```
+    /// # use automl::{SupervisedModel, Settings};
+    /// # let model = SupervisedModel::new_from_dataset(
+    /// #     smartcore::dataset::diabetes::load_dataset(),
+    /// #     Settings::default_regression(),
+    /// # );
+    /// # model.save("tests/header_format.aml");
+    /// let header = automl::file_format::read_header("tests/header_format.aml").unwrap();
+    /// println!("{}", header.format_version());
+    /// # std::fs::remove_file("tests/header_format.aml");
+    /// ```
+    pub fn format_version(&self) -> u32 {
+        self.format_version
+    }
+
+    /// Get what the file holds
+    /// ```
+    /// # use automl::{SupervisedModel, Settings};
+    /// use automl::file_format::Contents;
+    /// # let model = SupervisedModel::new_from_dataset(
+    /// #     smartcore::dataset::diabetes::load_dataset(),
+    /// #     Settings::default_regression(),
+    /// # );
+    /// # model.save("tests/header_contents.aml");
+    /// let header = automl::file_format::read_header("tests/header_contents.aml").unwrap();
+    /// assert_eq!(header.contents(), Contents::SupervisedModel);
+    /// # std::fs::remove_file("tests/header_contents.aml");
+    /// ```
+    pub fn contents(&self) -> Contents {
+        self.contents
+    }
+
+    /// Get the version of automl that wrote the file
+    /// ```
+    /// # use automl::{SupervisedModel, Settings};
+    /// # let model = SupervisedModel::new_from_dataset(
+    /// #     smartcore::dataset::diabetes::load_dataset(),
+    /// #     Settings::default_regression(),
+    /// # );
+    /// # model.save("tests/header_crate.aml");
+    /// let header = automl::file_format::read_header("tests/header_crate.aml").unwrap();
+    /// println!("{}", header.crate_version());
+    /// # std::fs::remove_file("tests/header_crate.aml");
+    /// ```
+    pub fn crate_version(&self) -> &str {
+        &self.crate_version
+    }
+
+    /// Get the series of smartcore releases that wrote the file
+    /// ```
+    /// # use automl::{SupervisedModel, Settings};
+    /// # let model = SupervisedModel::new_from_dataset(
+    /// #     smartcore::dataset::diabetes::load_dataset(),
+    /// #     Settings::default_regression(),
+    /// # );
+    /// # model.save("tests/header_smartcore.aml");
+    /// let header = automl::file_format::read_header("tests/header_smartcore.aml").unwrap();
+    /// println!("{}", header.smartcore_version());
+    /// # std::fs::remove_file("tests/header_smartcore.aml");
+    /// ```
+    pub fn smartcore_version(&self) -> &str {
+        &self.smartcore_version
+    }
+
+    /// Get when the file was written
+    /// ```
+    /// # use automl::{SupervisedModel, Settings};
+    /// # let model = SupervisedModel::new_from_dataset(
+    /// #     smartcore::dataset::diabetes::load_dataset(),
+    /// #     Settings::default_regression(),
+    /// # );
+    /// # model.save("tests/header_created.aml");
+    /// let header = automl::file_format::read_header("tests/header_created.aml").unwrap();
+    /// assert!(header.created() <= std::time::SystemTime::now());
+    /// # std::fs::remove_file("tests/header_created.aml");
+    /// ```
+    pub fn created(&self) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(self.created)
+    }
+
+    /// Get the checksum of the serialized model
+    /// ```
+    /// # use automl::{SupervisedModel, Settings};
+    /// # let model = SupervisedModel::new_from_dataset(
+    /// #     smartcore::dataset::diabetes::load_dataset(),
+    /// #     Settings::default_regression(),
+    /// # );
+    /// # model.save("tests/header_checksum.aml");
+    /// let header = automl::file_format::read_header("tests/header_checksum.aml").unwrap();
+    /// println!("{:x}", header.checksum());
+    /// # std::fs::remove_file("tests/header_checksum.aml");
+    /// ```
+    pub fn checksum(&self) -> u64 {
+        self.checksum
+    }
+}
+
+/// The header of version 1 files written before predictors existed, which did not record what
+/// the file holds
+#[derive(serde::Deserialize)]
+struct LegacyHeader {
+    /// The version of the file format
+    format_version: u32,
+    /// The version of automl that wrote the file
+    crate_version: String,
+    /// The series of smartcore releases that wrote the file
+    smartcore_version: String,
+    /// When the file was written, in seconds since the Unix epoch
+    created: u64,
+    /// The FNV-1a hash of the serialized model
+    checksum: u64,
+}
+
+impl From<LegacyHeader> for Header {
+    fn from(header: LegacyHeader) -> Self {
+        Self {
+            format_version: header.format_version,
+            contents: Contents::SupervisedModel,
+            crate_version: header.crate_version,
+            smartcore_version: header.smartcore_version,
+            created: header.created,
+            checksum: header.checksum,
+        }
+    }
+}
+
+/// What a model file holds
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum Contents {
+    /// A [`SupervisedModel`](crate::SupervisedModel), including its training data and comparison
+    SupervisedModel,
+    /// A [`Predictor`](crate::predictor::Predictor) with only what is needed for prediction
+    Predictor,
+}
+
+impl Display for Contents {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Contents::SupervisedModel => write!(f, "a supervised model"),
+            Contents::Predictor => write!(f, "a predictor"),
+        }
+    }
+}
+
+/// Describes why a model file could not be loaded
+#[derive(Debug)]
+pub enum LoadError {
+    /// The file could not be read
+    Io(std::io::Error),
+    /// The file does not start with a model file header
+    MissingHeader,
+    /// The file was written with a newer format version than this version of automl understands
+    UnsupportedVersion {
+        /// The format version of the file
+        found: u32,
+        /// The newest format version this version of automl can read
+        supported: u32,
+    },
+    /// The file was written with an incompatible version of smartcore
+    IncompatibleSmartcore {
+        /// The smartcore version that wrote the file
+        found: String,
+        /// The smartcore version used by this version of automl
+        expected: String,
+    },
+    /// The file holds something other than what was asked for
+    WrongContents {
+        /// What the file holds
+        found: Contents,
+        /// What was asked for
+        expected: Contents,
+    },
+    /// The model does not match the checksum in the header
+    ChecksumMismatch,
+    /// The model could not be deserialized
+    Corrupted(String),
+}
+
+impl Display for LoadError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LoadError::Io(error) => write!(f, "Cannot read the model file: {}", error),
+            LoadError::MissingHeader => write!(
+                f,
+                "The file has no model header. It is either not a model file or was saved by \
+                 automl 0.2.4 or earlier, which cannot be loaded; train and save the model again."
+            ),
+            LoadError::UnsupportedVersion { found, supported } => write!(
+                f,
+                "The model file uses format version {}, but this version of automl only reads up \
+                 to version {}. Upgrade automl to load it.",
+                found, supported
+            ),
+            LoadError::IncompatibleSmartcore { found, expected } => write!(
+                f,
+                "The model was trained with smartcore {}, but this version of automl uses \
+                 smartcore {}.",
+                found, expected
+            ),
+            LoadError::WrongContents { found, expected } => write!(
+                f,
+                "The file holds {}, but {} was expected.",
+                found, expected
+            ),
+            LoadError::ChecksumMismatch => write!(
+                f,
+                "The model does not match its checksum, so the file has been truncated or changed."
+            ),
+            LoadError::Corrupted(error) => write!(f, "Cannot deserialize the model: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for LoadError {}
+
+impl From<std::io::Error> for LoadError {
+    fn from(error: std::io::Error) -> Self {
+        LoadError::Io(error)
+    }
+}
+
+/// Read the header of a model file without loading the model
+/// ```
+/// # use automl::{SupervisedModel, Settings};
+/// # let model = SupervisedModel::new_from_dataset(
+/// #     smartcore::dataset::diabetes::load_dataset(),
+/// #     Settings::default_regression(),
+/// # );
+/// # model.save("tests/read_header.aml");
+/// let header = automl::file_format::read_header("tests/read_header.aml").unwrap();
+/// println!("Saved by automl {}", header.crate_version());
+/// # std::fs::remove_file("tests/read_header.aml");
+/// ```
+pub fn read_header(file_name: &str) -> Result<Header, LoadError> {
+    let bytes = std::fs::read(file_name)?;
+    split_header(&bytes).map(|(header, _)| header)
+}
+
+/// Serialize a value and write it to a file with a header
+pub(crate) fn save<T: Serialize>(value: &T, contents: Contents, file_name: &str) {
+    let payload = bincode::serialize(value).expect("Cannot serialize model.");
+    let header = Header {
+        format_version: FORMAT_VERSION,
+        contents,
+        crate_version: env!("CARGO_PKG_VERSION").to_string(),
+        smartcore_version: SMARTCORE_VERSION.to_string(),
+        created: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_secs()),
+        checksum: checksum(&payload),
+    };
+    let header = bincode::serialize(&header).expect("Cannot serialize model header.");
+    std::fs::File::create(file_name)
+        .and_then(|mut f| {
+            f.write_all(&MAGIC)?;
+            f.write_all(&header)?;
+            f.write_all(&payload)
+        })
+        .expect("Cannot write model to file.");
+}
+
+/// Read a file written by [`save`], checking and migrating it before deserializing the value
+pub(crate) fn load<T: DeserializeOwned>(
+    file_name: &str,
+    contents: Contents,
+) -> Result<T, LoadError> {
+    let bytes = std::fs::read(file_name)?;
+    let (header, payload) = split_header(&bytes)?;
+    if header.contents != contents {
+        return Err(LoadError::WrongContents {
+            found: header.contents,
+            expected: contents,
+        });
+    }
+    if checksum(payload) != header.checksum {
+        return Err(LoadError::ChecksumMismatch);
+    }
+    let payload = MIGRATIONS[(header.format_version - 1) as usize..]
+        .iter()
+        .try_fold(payload.to_vec(), |payload, migration| {
+            migration(contents, payload)
+        })?;
+    bincode::deserialize(&payload).map_err(|error| LoadError::Corrupted(error.to_string()))
+}
+
+/// Check the header of a model file and split it from the serialized model
+fn split_header(bytes: &[u8]) -> Result<(Header, &[u8]), LoadError> {
+    if bytes.len() < MAGIC.len() + 4 || bytes[..MAGIC.len()] != MAGIC {
+        return Err(LoadError::MissingHeader);
+    }
+
+    // The format version comes first so that it can be checked before the rest of the header
+    let mut version = [0; 4];
+    version.copy_from_slice(&bytes[MAGIC.len()..MAGIC.len() + 4]);
+    let found = u32::from_le_bytes(version);
+    if found == 0 || found > FORMAT_VERSION {
+        return Err(LoadError::UnsupportedVersion {
+            found,
+            supported: FORMAT_VERSION,
+        });
+    }
+
+    let mut rest = &bytes[MAGIC.len()..];
+    let header = match bincode::deserialize_from::<_, Header>(&mut rest) {
+        Ok(header) => header,
+        // Where the contents would be, a legacy header has the length of the crate version, which
+        // is never a valid contents variant, so it can only be read as a legacy header
+        Err(_) if found == 1 => {
+            rest = &bytes[MAGIC.len()..];
+            bincode::deserialize_from::<_, LegacyHeader>(&mut rest)
+                .map(Header::from)
+                .map_err(|error| LoadError::Corrupted(error.to_string()))?
+        }
+        Err(error) => return Err(LoadError::Corrupted(error.to_string())),
+    };
+    if header.smartcore_version != SMARTCORE_VERSION {
+        return Err(LoadError::IncompatibleSmartcore {
+            found: header.smartcore_version,
+            expected: SMARTCORE_VERSION.to_string(),
+        });
+    }
+    Ok((header, rest))
+}
+
+/// The 64-bit FNV-1a hash of some bytes, used to detect truncated or damaged files
+fn checksum(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x100000001b3)
+    })
+}
@@ -0,0 +1,93 @@
+//! # Feature importance
+//! This module contains [`FeatureImportance`], the result of
+//! [`SupervisedModel::feature_importance`](crate::SupervisedModel::feature_importance). The
+//! importance of a feature is how much the score of the final model drops when the values of that
+//! feature are shuffled, breaking its link with the target. Scores use the sorting metric from the
+//! settings and are oriented so that a larger importance always means a more useful feature, for
+//! error metrics as well as for R² and accuracy.
+//!
+//! Tree models also report impurity-based importances through
+//! [`SupervisedModel::impurity_importance`](crate::SupervisedModel::impurity_importance), which
+//! uses the same type.
+//! ```
+//! # use automl::{SupervisedModel, Settings};
+//! # use smartcore::dataset::diabetes::load_dataset;
+//! let mut model = SupervisedModel::new_from_dataset(load_dataset(), Settings::default_regression());
+//! model.train();
+//!
+//! // Score on data that was not used for training
+//! let x = vec![vec![0.0; 10], vec![0.1; 10], vec![-0.1; 10]];
+//! let y = vec![150.0, 250.0, 80.0];
+//! for importance in model.feature_importance(x, y, 5) {
+//!     println!("{}: {} ± {}", importance.feature(), importance.mean(), importance.std());
+//! }
+//! ```
+
+use crate::utils::mean_and_std;
+
+/// The importance of a single feature
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct FeatureImportance {
+    /// The name of the feature
+    feature: String,
+    /// Mean importance over the repeats or trees
+    mean: f32,
+    /// Population standard deviation of the importance over the repeats or trees
+    std: f32,
+}
+
+impl FeatureImportance {
+    /// Summarize the importances measured for a feature over several repeats or trees
+    pub(crate) fn new(feature: String, values: &[f32]) -> Self {
+        let (mean, std) = mean_and_std(values);
+        Self { feature, mean, std }
+    }
+
+    /// The name of the feature, taken from the CSV header when there is one
+    /// ```
+    /// # use automl::{SupervisedModel, Settings, settings::Algorithm};
+    /// # let mut model = SupervisedModel::new_from_dataset(
+    /// #     smartcore::dataset::diabetes::load_dataset(),
+    /// #     Settings::default_regression().only(Algorithm::Linear),
+    /// # );
+    /// # model.train();
+    /// let x = vec![vec![0.0; 10], vec![0.1; 10]];
+    /// let importance = model.feature_importance(x, vec![150.0, 250.0], 1);
+    /// assert_eq!(importance[0].feature(), "x_0");
+    /// ```
+    pub fn feature(&self) -> &str {
+        &self.feature
+    }
+
+    /// Mean drop in score when the feature is shuffled
+    /// ```
+    /// # use automl::{SupervisedModel, Settings, settings::Algorithm};
+    /// # let mut model = SupervisedModel::new_from_dataset(
+    /// #     smartcore::dataset::diabetes::load_dataset(),
+    /// #     Settings::default_regression().only(Algorithm::Linear),
+    /// # );
+    /// # model.train();
+    /// let x = vec![vec![0.0; 10], vec![0.1; 10]];
+    /// let importance = model.feature_importance(x, vec![150.0, 250.0], 3);
+    /// let mean = importance[0].mean();
+    /// ```
+    pub fn mean(&self) -> f32 {
+        self.mean
+    }
+
+    /// Standard deviation of the drop in score over the repeats
+    /// ```
+    /// # use automl::{SupervisedModel, Settings, settings::Algorithm};
+    /// # let mut model = SupervisedModel::new_from_dataset(
+    /// #     smartcore::dataset::diabetes::load_dataset(),
+    /// #     Settings::default_regression().only(Algorithm::Linear),
+    /// # );
+    /// # model.train();
+    /// let x = vec![vec![0.0; 10], vec![0.1; 10]];
+    /// let importance = model.feature_importance(x, vec![150.0, 250.0], 3);
+    /// assert!(importance[0].std() >= 0.0);
+    /// ```
+    pub fn std(&self) -> f32 {
+        self.std
+    }
+}
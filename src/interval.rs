@@ -0,0 +1,150 @@
+//! # Prediction intervals
+//! This module contains [`PredictionInterval`], the lower bound, median and upper bound predicted
+//! for one row by [`SupervisedModel::predict_interval`](crate::SupervisedModel::predict_interval).
+//! The bounds are the quantiles of the target that leave half of the missing coverage on each
+//! side, so an interval with a coverage of 0.8 runs from the 10th to the 90th percentile.
+//!
+//! The models behind the intervals are trained once at the end of
+//! [`SupervisedModel::train`](crate::SupervisedModel::train) and saved with the model, so
+//! predicting an interval does not need the training data and always gives the same answer.
+//! Quantile loss models are fit at the 2.5th, 5th, 10th, 25th, 50th, 75th, 90th, 95th and 97.5th
+//! percentiles, and other quantiles are interpolated between them, which limits their intervals
+//! to a coverage of at most 0.95. Random forests keep the training targets in each leaf instead,
+//! and read any quantile from them.
+//! ```
+//! # use automl::{SupervisedModel, Settings};
+//! use automl::settings::{Algorithm, QuantileRegressorParameters};
+//! let mut model = SupervisedModel::new_from_dataset(
+//!     smartcore::dataset::diabetes::load_dataset(),
+//!     Settings::default_regression()
+//!         .only(Algorithm::QuantileRegressor)
+//!         .with_number_of_folds(3),
+//! );
+//! model.train();
+//! for interval in model.predict_interval(vec![vec![0.0; 10]], 0.8).unwrap() {
+//!     println!("{} [{}, {}]", interval.median(), interval.lower(), interval.upper());
+//! }
+//! ```
+
+use crate::schema::SchemaError;
+use std::fmt::{Display, Formatter};
+
+/// The predicted median of the target for one row, with the bounds it falls between at the
+/// requested coverage
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct PredictionInterval {
+    /// The lower quantile
+    lower: f32,
+    /// The median
+    median: f32,
+    /// The upper quantile
+    upper: f32,
+}
+
+impl PredictionInterval {
+    /// Collect the interval for one row. Separately fit quantiles can cross, so the three values
+    /// are sorted to keep the bounds around the median.
+    pub(crate) fn new(lower: f32, median: f32, upper: f32) -> Self {
+        let mut values = [lower, median, upper];
+        values.sort_by(|a, b| a.total_cmp(b));
+        Self {
+            lower: values[0],
+            median: values[1],
+            upper: values[2],
+        }
+    }
+
+    /// The lower bound of the interval
+    /// ```
+    /// # use automl::{SupervisedModel, Settings, settings::Algorithm};
+    /// # let mut model = SupervisedModel::new_from_dataset(
+    /// #     smartcore::dataset::diabetes::load_dataset(),
+    /// #     Settings::default_regression().only(Algorithm::QuantileRegressor),
+    /// # );
+    /// # model.train();
+    /// let interval = &model.predict_interval(vec![vec![0.0; 10]], 0.8).unwrap()[0];
+    /// assert!(interval.lower() <= interval.median());
+    /// ```
+    pub fn lower(&self) -> f32 {
+        self.lower
+    }
+
+    /// The median, which splits the predicted distribution of the target in half
+    /// ```
+    /// # use automl::{SupervisedModel, Settings, settings::Algorithm};
+    /// # let mut model = SupervisedModel::new_from_dataset(
+    /// #     smartcore::dataset::diabetes::load_dataset(),
+    /// #     Settings::default_regression().only(Algorithm::QuantileRegressor),
+    /// # );
+    /// # model.train();
+    /// let interval = &model.predict_interval(vec![vec![0.0; 10]], 0.8).unwrap()[0];
+    /// println!("{}", interval.median());
+    /// ```
+    pub fn median(&self) -> f32 {
+        self.median
+    }
+
+    /// The upper bound of the interval
+    /// ```
+    /// # use automl::{SupervisedModel, Settings, settings::Algorithm};
+    /// # let mut model = SupervisedModel::new_from_dataset(
+    /// #     smartcore::dataset::diabetes::load_dataset(),
+    /// #     Settings::default_regression().only(Algorithm::QuantileRegressor),
+    /// # );
+    /// # model.train();
+    /// let interval = &model.predict_interval(vec![vec![0.0; 10]], 0.8).unwrap()[0];
+    /// assert!(interval.upper() >= interval.median());
+    /// ```
+    pub fn upper(&self) -> f32 {
+        self.upper
+    }
+}
+
+/// Describes why prediction intervals could not be predicted
+#[derive(Debug)]
+pub enum IntervalError {
+    /// The rows do not match the schema of the model
+    Schema(SchemaError),
+    /// The coverage is not between zero and one
+    Coverage(f32),
+    /// A bound of the interval is outside the quantiles the models were fit at
+    OutOfRange {
+        /// The quantile the bound needs
+        quantile: f32,
+        /// The lowest quantile that can be predicted
+        lowest: f32,
+        /// The highest quantile that can be predicted
+        highest: f32,
+    },
+}
+
+impl Display for IntervalError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IntervalError::Schema(error) => write!(f, "{}", error),
+            IntervalError::Coverage(coverage) => write!(
+                f,
+                "The coverage must be between zero and one, not {}.",
+                coverage
+            ),
+            IntervalError::OutOfRange {
+                quantile,
+                lowest,
+                highest,
+            } => write!(
+                f,
+                "The interval needs the {} quantile, but the models were fit from the {} to the {} \
+                quantile.",
+                quantile, lowest, highest
+            ),
+        }
+    }
+}
+
+impl std::error::Error for IntervalError {}
+
+impl From<SchemaError> for IntervalError {
+    fn from(error: SchemaError) -> Self {
+        IntervalError::Schema(error)
+    }
+}
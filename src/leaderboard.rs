@@ -0,0 +1,210 @@
+//! # Model comparison results
+//! This module contains [`LeaderboardEntry`], a read-only summary of how each algorithm did during
+//! the model comparison. Entries are returned by
+//! [`SupervisedModel::leaderboard`](crate::SupervisedModel::leaderboard) in the same order as the
+//! comparison table, best first, and can be serialized to feed dashboards or regression tests.
+//! ```
+//! # use automl::{SupervisedModel, Settings};
+//! let mut model = SupervisedModel::new_from_dataset(
+//!     smartcore::dataset::diabetes::load_dataset(),
+//!     Settings::default_regression(),
+//! );
+//! model.train();
+//! for entry in model.leaderboard() {
+//!     println!("{}: {}", entry.algorithm(), entry.mean_test_score());
+//! }
+//! let yaml = serde_yaml::to_string(&model.leaderboard()).unwrap();
+//! ```
+
+use crate::progress::Status;
+use crate::settings::{Algorithm, Settings};
+use crate::utils::mean_and_std;
+use crate::Model;
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+/// The results of cross-validating a single algorithm
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct LeaderboardEntry {
+    /// The algorithm that was cross-validated
+    algorithm: Algorithm,
+    /// Training score of each fold
+    train_scores: Vec<f32>,
+    /// Testing score of each fold
+    test_scores: Vec<f32>,
+    /// Mean training score over the folds
+    mean_train_score: f32,
+    /// Standard deviation of the training scores
+    std_train_score: f32,
+    /// Mean testing score over the folds
+    mean_test_score: f32,
+    /// Standard deviation of the testing scores
+    std_test_score: f32,
+    /// Time spent on cross-validation
+    duration: Duration,
+    /// How far the algorithm got
+    status: Status,
+    /// The parameters used, as printed in the settings
+    parameters: BTreeMap<String, String>,
+}
+
+impl LeaderboardEntry {
+    /// Summarize a model from the comparison
+    pub(crate) fn new(model: &Model, settings: &Settings) -> Self {
+        let (mean_train_score, std_train_score) = mean_and_std(&model.score.train_score);
+        let (mean_test_score, std_test_score) = mean_and_std(&model.score.test_score);
+        Self {
+            algorithm: model.name,
+            train_scores: model.score.train_score.clone(),
+            test_scores: model.score.test_score.clone(),
+            mean_train_score,
+            std_train_score,
+            mean_test_score,
+            std_test_score,
+            duration: model.duration,
+            status: model.status.clone(),
+            parameters: settings.get_parameters(model.name),
+        }
+    }
+
+    /// The algorithm that was cross-validated
+    /// ```
+    /// # use automl::{SupervisedModel, Settings, settings::Algorithm};
+    /// # let mut model = SupervisedModel::new_from_dataset(
+    /// #     smartcore::dataset::diabetes::load_dataset(),
+    /// #     Settings::default_regression().only(Algorithm::Linear),
+    /// # );
+    /// # model.train();
+    /// assert_eq!(model.leaderboard()[0].algorithm(), Algorithm::Linear);
+    /// ```
+    pub fn algorithm(&self) -> Algorithm {
+        self.algorithm
+    }
+
+    /// Training score of each cross-validation fold
+    /// ```
+    /// # use automl::{SupervisedModel, Settings, settings::Algorithm};
+    /// # let mut model = SupervisedModel::new_from_dataset(
+    /// #     smartcore::dataset::diabetes::load_dataset(),
+    /// #     Settings::default_regression().only(Algorithm::Linear).with_number_of_folds(3),
+    /// # );
+    /// # model.train();
+    /// assert_eq!(model.leaderboard()[0].train_scores().len(), 3);
+    /// ```
+    pub fn train_scores(&self) -> &[f32] {
+        &self.train_scores
+    }
+
+    /// Testing score of each cross-validation fold
+    /// ```
+    /// # use automl::{SupervisedModel, Settings, settings::Algorithm};
+    /// # let mut model = SupervisedModel::new_from_dataset(
+    /// #     smartcore::dataset::diabetes::load_dataset(),
+    /// #     Settings::default_regression().only(Algorithm::Linear).with_number_of_folds(3),
+    /// # );
+    /// # model.train();
+    /// assert_eq!(model.leaderboard()[0].test_scores().len(), 3);
+    /// ```
+    pub fn test_scores(&self) -> &[f32] {
+        &self.test_scores
+    }
+
+    /// Mean training score over the folds
+    /// ```
+    /// # use automl::{SupervisedModel, Settings, settings::Algorithm};
+    /// # let mut model = SupervisedModel::new_from_dataset(
+    /// #     smartcore::dataset::diabetes::load_dataset(),
+    /// #     Settings::default_regression().only(Algorithm::Linear),
+    /// # );
+    /// # model.train();
+    /// let r_squared = model.leaderboard()[0].mean_train_score();
+    /// ```
+    pub fn mean_train_score(&self) -> f32 {
+        self.mean_train_score
+    }
+
+    /// Population standard deviation of the training scores
+    /// ```
+    /// # use automl::{SupervisedModel, Settings, settings::Algorithm};
+    /// # let mut model = SupervisedModel::new_from_dataset(
+    /// #     smartcore::dataset::diabetes::load_dataset(),
+    /// #     Settings::default_regression().only(Algorithm::Linear),
+    /// # );
+    /// # model.train();
+    /// assert!(model.leaderboard()[0].std_train_score() >= 0.0);
+    /// ```
+    pub fn std_train_score(&self) -> f32 {
+        self.std_train_score
+    }
+
+    /// Mean testing score over the folds, used to rank the algorithms
+    /// ```
+    /// # use automl::{SupervisedModel, Settings, settings::Algorithm};
+    /// # let mut model = SupervisedModel::new_from_dataset(
+    /// #     smartcore::dataset::diabetes::load_dataset(),
+    /// #     Settings::default_regression().only(Algorithm::Linear),
+    /// # );
+    /// # model.train();
+    /// let r_squared = model.leaderboard()[0].mean_test_score();
+    /// ```
+    pub fn mean_test_score(&self) -> f32 {
+        self.mean_test_score
+    }
+
+    /// Population standard deviation of the testing scores
+    /// ```
+    /// # use automl::{SupervisedModel, Settings, settings::Algorithm};
+    /// # let mut model = SupervisedModel::new_from_dataset(
+    /// #     smartcore::dataset::diabetes::load_dataset(),
+    /// #     Settings::default_regression().only(Algorithm::Linear),
+    /// # );
+    /// # model.train();
+    /// assert!(model.leaderboard()[0].std_test_score() >= 0.0);
+    /// ```
+    pub fn std_test_score(&self) -> f32 {
+        self.std_test_score
+    }
+
+    /// Time spent cross-validating the algorithm
+    /// ```
+    /// # use automl::{SupervisedModel, Settings, settings::Algorithm};
+    /// # let mut model = SupervisedModel::new_from_dataset(
+    /// #     smartcore::dataset::diabetes::load_dataset(),
+    /// #     Settings::default_regression().only(Algorithm::Linear),
+    /// # );
+    /// # model.train();
+    /// println!("{:?}", model.leaderboard()[0].duration());
+    /// ```
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+
+    /// Whether the algorithm finished cross-validation
+    /// ```
+    /// # use automl::{SupervisedModel, Settings, settings::Algorithm};
+    /// # let mut model = SupervisedModel::new_from_dataset(
+    /// #     smartcore::dataset::diabetes::load_dataset(),
+    /// #     Settings::default_regression().only(Algorithm::Linear),
+    /// # );
+    /// # model.train();
+    /// use automl::progress::Status;
+    /// assert_eq!(model.leaderboard()[0].status(), Status::Completed);
+    /// ```
+    pub fn status(&self) -> Status {
+        self.status.clone()
+    }
+
+    /// The parameters the algorithm was trained with, keyed by field name
+    /// ```
+    /// # use automl::{SupervisedModel, Settings, settings::Algorithm};
+    /// # let mut model = SupervisedModel::new_from_dataset(
+    /// #     smartcore::dataset::diabetes::load_dataset(),
+    /// #     Settings::default_regression().only(Algorithm::Ridge),
+    /// # );
+    /// # model.train();
+    /// let alpha = &model.leaderboard()[0].parameters()["alpha"];
+    /// ```
+    pub fn parameters(&self) -> &BTreeMap<String, String> {
+        &self.parameters
+    }
+}
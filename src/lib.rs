@@ -9,20 +9,71 @@ pub mod settings;
 pub use settings::Settings;
 use settings::{Algorithm, Distance, Kernel, Metric, PreProcessing};
 
+pub mod progress;
+use progress::{CancellationToken, Interruption, Monitor, ProgressObserver, Status};
+
+pub mod leaderboard;
+use leaderboard::LeaderboardEntry;
+
+pub mod report;
+use report::Report;
+
+pub mod importance;
+use importance::FeatureImportance;
+
+pub mod explanation;
+use explanation::{Coefficients, Explanation};
+
+pub mod dependence;
+use dependence::{PartialDependence, PartialDependence2D};
+
+pub mod schema;
+use schema::{Schema, SchemaError};
+
+pub mod file_format;
+use file_format::{Contents, LoadError};
+
+pub mod predictor;
+use predictor::Predictor;
+
+pub mod batch;
+use batch::{BatchError, BatchOptions, BatchPredictions};
+
+pub mod csv_options;
+use csv_options::CsvOptions;
+
+pub mod interval;
+use interval::{IntervalError, PredictionInterval};
+
+#[cfg_attr(docsrs, doc(cfg(feature = "serve")))]
+#[cfg(any(feature = "serve"))]
+pub mod server;
+
+#[cfg_attr(docsrs, doc(cfg(feature = "dataframe")))]
+#[cfg(any(feature = "dataframe"))]
+pub mod dataframe;
+#[cfg(any(feature = "dataframe"))]
+use dataframe::DataFrameOptions;
+
 mod algorithms;
 use algorithms::{
-    CategoricalNaiveBayesClassifierWrapper, DecisionTreeClassifierWrapper,
-    DecisionTreeRegressorWrapper, ElasticNetRegressorWrapper, GaussianNaiveBayesClassifierWrapper,
+    check_family, CategoricalNaiveBayesClassifierWrapper, ClassProbabilities,
+    DecisionTreeClassifierWrapper, DecisionTreeRegressorWrapper, ElasticNetRegressorWrapper,
+    ExtraTreesClassifierWrapper, ExtraTreesRegressorWrapper, GammaRegressorWrapper,
+    GaussianNaiveBayesClassifierWrapper, GradientBoostingClassifierWrapper,
+    GradientBoostingQuantileRegressorWrapper, GradientBoostingRegressorWrapper,
     KNNClassifierWrapper, KNNRegressorWrapper, LassoRegressorWrapper, LinearRegressorWrapper,
-    LogisticRegressionWrapper, ModelWrapper, RandomForestClassifierWrapper,
-    RandomForestRegressorWrapper, RidgeRegressorWrapper, SupportVectorClassifierWrapper,
-    SupportVectorRegressorWrapper,
+    LogisticRegressionWrapper, MLPClassifierWrapper, MLPRegressorWrapper, ModelWrapper,
+    PoissonRegressorWrapper, QuantileRegressorWrapper, RandomForestClassifierWrapper,
+    RandomForestRegressorWrapper, RidgeRegressorWrapper, Shap, SupportVectorClassifierWrapper,
+    SupportVectorRegressorWrapper, TweedieRegressorWrapper,
 };
 
 mod utils;
-use utils::elementwise_multiply;
+use utils::{clone_by_serialization, elementwise_multiply};
 
 use itertools::Itertools;
+use rand::seq::SliceRandom;
 use smartcore::{
     dataset::Dataset,
     decomposition::{
@@ -34,20 +85,34 @@ use smartcore::{
 };
 use std::{
     cmp::Ordering::Equal,
+    collections::BTreeMap,
     fmt::{Display, Formatter},
     time::Duration,
 };
 
-use std::io::{Read, Write};
-
 #[cfg(any(feature = "nd"))]
 use ndarray::{Array1, Array2};
 
 #[cfg(any(feature = "gui"))]
 use eframe::{egui, epi};
 
-#[cfg(any(feature = "csv"))]
-use polars::prelude::{CsvReader, DataFrame, Float32Type, SerReader};
+#[cfg(any(feature = "dataframe"))]
+use polars::prelude::{DataFrame, DataType, Float32Type, Series};
+
+#[cfg(any(feature = "parquet", feature = "ipc", feature = "json"))]
+use polars::prelude::SerReader;
+
+#[cfg(any(feature = "parquet"))]
+use polars::prelude::ParquetReader;
+
+#[cfg(any(feature = "ipc"))]
+use polars::prelude::IpcReader;
+
+#[cfg(any(feature = "json"))]
+use polars::prelude::JsonReader;
+
+#[cfg(any(feature = "dataframe"))]
+use schema::FeatureType;
 
 #[cfg(any(feature = "display"))]
 use comfy_table::{
@@ -58,23 +123,48 @@ use crate::settings::FinalModel;
 #[cfg(any(feature = "display"))]
 use humantime::format_duration;
 
+/// The fitted PCA or SVD, if the settings use one
+type FittedPreprocessing = (
+    Option<PCA<f32, DenseMatrix<f32>>>,
+    Option<SVD<f32, DenseMatrix<f32>>>,
+);
+
 /// Trains and compares supervised models
 #[derive(serde::Serialize, serde::Deserialize)]
 pub struct SupervisedModel {
+    /// The settings of the comparison
     settings: Settings,
+    /// The preprocessed training features
     x_train: DenseMatrix<f32>,
+    /// The training features before preprocessing
+    x_raw: DenseMatrix<f32>,
+    /// The training target
     y_train: Vec<f32>,
+    /// The features held out for blending
     x_val: DenseMatrix<f32>,
+    /// The target held out for blending
     y_val: Vec<f32>,
+    /// The names and count of the raw features
+    schema: Schema,
+    /// The number of classes, for classification
     number_of_classes: usize,
+    /// The results of every algorithm, best first
     comparison: Vec<Model>,
+    /// The blending model, if any
     metamodel: Model,
-    preprocessing: (
-        Option<PCA<f32, DenseMatrix<f32>>>,
-        Option<SVD<f32, DenseMatrix<f32>>>,
-    ),
+    /// The fitted PCA or SVD, if any
+    preprocessing: FittedPreprocessing,
+    /// The features being edited in the GUI
     #[cfg(any(feature = "gui"))]
     current_x: Vec<f32>,
+    /// The options the training CSV was read with, reused when predicting from CSV
+    csv_options: CsvOptions,
+    /// Who is told about progress during training
+    #[serde(skip)]
+    observers: Vec<Box<dyn ProgressObserver>>,
+    /// The token that stops training early, if any
+    #[serde(skip)]
+    cancellation_token: Option<CancellationToken>,
 }
 
 impl SupervisedModel {
@@ -107,6 +197,27 @@ impl SupervisedModel {
         SupervisedModel::new(DenseMatrix::from_2d_vec(&x), y, settings)
     }
 
+    /// Create a new supervised model using vec data with a name for each feature
+    /// ```
+    /// # use automl::{SupervisedModel, Settings};
+    /// let model = automl::SupervisedModel::new_from_vec_with_names(
+    ///     vec![vec![1.0, 2.0], vec![2.0, 1.0], vec![3.0, 3.0]],
+    ///     vec![1.0, 2.0, 3.0],
+    ///     &["age", "income"],
+    ///     automl::Settings::default_regression(),
+    /// );
+    /// ```
+    pub fn new_from_vec_with_names(
+        x: Vec<Vec<f32>>,
+        y: Vec<f32>,
+        feature_names: &[&str],
+        settings: Settings,
+    ) -> Self {
+        let mut model = SupervisedModel::new_from_vec(x, y, settings);
+        model.set_feature_names(feature_names);
+        model
+    }
+
     /// Load the supervised model from a file saved previously
     /// ```
     /// # use automl::{SupervisedModel, Settings};
@@ -119,11 +230,26 @@ impl SupervisedModel {
     /// # std::fs::remove_file("tests/load_that_model.aml");
     /// ```
     pub fn new_from_file(file_name: &str) -> Self {
-        let mut buf: Vec<u8> = Vec::new();
-        std::fs::File::open(&file_name)
-            .and_then(|mut f| f.read_to_end(&mut buf))
-            .expect("Cannot load model from file.");
-        bincode::deserialize(&buf).expect("Can not deserialize the model")
+        Self::try_new_from_file(file_name).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Load the supervised model from a file saved previously, returning an error if the file
+    /// cannot be read, was written by an incompatible version, or is damaged
+    /// ```
+    /// # use automl::{SupervisedModel, Settings};
+    /// # let mut model = SupervisedModel::new_from_dataset(
+    /// #    smartcore::dataset::diabetes::load_dataset(),
+    /// #    Settings::default_regression()
+    /// # );
+    /// # model.save("tests/try_load_that_model.aml");
+    /// match SupervisedModel::try_new_from_file("tests/try_load_that_model.aml") {
+    ///     Ok(model) => println!("{}", model),
+    ///     Err(error) => println!("{}", error),
+    /// }
+    /// # std::fs::remove_file("tests/try_load_that_model.aml");
+    /// ```
+    pub fn try_new_from_file(file_name: &str) -> Result<Self, LoadError> {
+        file_format::load(file_name, Contents::SupervisedModel)
     }
 
     /// Predict values using the final model based on a vec.
@@ -136,8 +262,234 @@ impl SupervisedModel {
     /// model.train();
     /// model.predict_from_vec(vec![vec![5.0; 10]; 5]);
     /// ```
-    pub fn predict_from_vec(&mut self, x: Vec<Vec<f32>>) -> Vec<f32> {
-        self.predict(&DenseMatrix::from_2d_vec(&x))
+    pub fn predict_from_vec(&self, x: Vec<Vec<f32>>) -> Vec<f32> {
+        self.try_predict_from_vec(x)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Predict values using the final model based on a vec, returning an error if the rows do not
+    /// have one value per feature
+    /// ```
+    /// # use automl::{SupervisedModel, Settings, settings::Algorithm};
+    /// let mut model = SupervisedModel::new_from_dataset(
+    ///     smartcore::dataset::diabetes::load_dataset(),
+    ///     Settings::default_regression().only(Algorithm::Linear)
+    /// );
+    /// model.train();
+    /// assert!(model.try_predict_from_vec(vec![vec![5.0; 10]; 5]).is_ok());
+    /// assert!(model.try_predict_from_vec(vec![vec![5.0; 11]; 5]).is_err());
+    /// ```
+    pub fn try_predict_from_vec(&self, x: Vec<Vec<f32>>) -> Result<Vec<f32>, SchemaError> {
+        self.schema.check_rows(&x)?;
+        Ok(self.predict(&DenseMatrix::from_2d_vec(&x)))
+    }
+
+    /// Predict the median of the target for each row, with an interval that should hold the
+    /// target for the given fraction of rows. Intervals come from the best model when it can
+    /// predict quantiles, which quantile regressors and random forests can, and otherwise from the
+    /// best quantile regressor in the comparison. There is no interval, and `None` is returned,
+    /// when no compared model can predict one, when the final model is a blend of the compared
+    /// models, since a blend of their quantiles is not a quantile of the target, or when the
+    /// settings train no final model.
+    ///
+    /// Panics if the coverage is not between zero and one, if the rows do not match the schema,
+    /// or if the coverage is wider than the interval models allow; use
+    /// [`try_predict_interval`](Self::try_predict_interval) to handle these as errors instead.
+    /// ```
+    /// # use automl::{SupervisedModel, Settings};
+    /// use automl::settings::Algorithm;
+    /// let mut model = SupervisedModel::new_from_dataset(
+    ///     smartcore::dataset::diabetes::load_dataset(),
+    ///     Settings::default_regression().only(Algorithm::RandomForestRegressor),
+    /// );
+    /// model.train();
+    /// let intervals = model.predict_interval(vec![vec![0.0; 10]; 5], 0.8).unwrap();
+    /// for interval in intervals {
+    ///     println!("{} to {}", interval.lower(), interval.upper());
+    /// }
+    /// ```
+    pub fn predict_interval(
+        &self,
+        x: Vec<Vec<f32>>,
+        coverage: f32,
+    ) -> Option<Vec<PredictionInterval>> {
+        self.try_predict_interval(x, coverage)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Predict the median of the target for each row with an interval, as
+    /// [`predict_interval`](Self::predict_interval) does, returning an error if the coverage is
+    /// not between zero and one, if the rows do not match the schema, or if the interval needs
+    /// quantiles that the quantile regressor was not fit at
+    /// ```
+    /// # use automl::{SupervisedModel, Settings};
+    /// use automl::settings::Algorithm;
+    /// let mut model = SupervisedModel::new_from_dataset(
+    ///     smartcore::dataset::diabetes::load_dataset(),
+    ///     Settings::default_regression().only(Algorithm::QuantileRegressor),
+    /// );
+    /// model.train();
+    /// assert!(model.try_predict_interval(vec![vec![0.0; 10]], 0.9).is_ok());
+    /// assert!(model.try_predict_interval(vec![vec![0.0; 10]], 1.5).is_err());
+    /// assert!(model.try_predict_interval(vec![vec![0.0; 11]], 0.9).is_err());
+    /// assert!(model.try_predict_interval(vec![vec![0.0; 10]], 0.99).is_err());
+    /// ```
+    pub fn try_predict_interval(
+        &self,
+        x: Vec<Vec<f32>>,
+        coverage: f32,
+    ) -> Result<Option<Vec<PredictionInterval>>, IntervalError> {
+        if !(coverage > 0.0 && coverage < 1.0) {
+            return Err(IntervalError::Coverage(coverage));
+        }
+        self.schema.check_rows(&x)?;
+
+        // Only the model chosen during training has interval models
+        let model = match self.settings.final_model_approach {
+            FinalModel::Best => self
+                .completed_models()
+                .find(|model| !model.interval_models.is_empty()),
+            FinalModel::None | FinalModel::Blending { .. } => None,
+        };
+        let model = match model {
+            Some(model) => model,
+            None => return Ok(None),
+        };
+
+        let x = &self.preprocess(DenseMatrix::from_2d_vec(&x));
+        let quantiles = [(1.0 - coverage) / 2.0, 0.5, (1.0 + coverage) / 2.0];
+        let (final_model, interval_models) = (&model.model, &model.interval_models);
+        let predictions = match model.name {
+            Algorithm::QuantileRegressor => QuantileRegressorWrapper::predict_quantiles(
+                x,
+                final_model,
+                interval_models,
+                &quantiles,
+            ),
+            Algorithm::GradientBoostingQuantileRegressor => {
+                GradientBoostingQuantileRegressorWrapper::predict_quantiles(
+                    x,
+                    final_model,
+                    interval_models,
+                    &quantiles,
+                )
+            }
+            Algorithm::RandomForestRegressor => RandomForestRegressorWrapper::predict_quantiles(
+                x,
+                final_model,
+                interval_models,
+                &quantiles,
+            ),
+            _ => Ok(None),
+        }?;
+        Ok(predictions.map(|predictions| {
+            (0..predictions[0].len())
+                .map(|row| {
+                    PredictionInterval::new(
+                        predictions[0][row],
+                        predictions[1][row],
+                        predictions[2][row],
+                    )
+                })
+                .collect()
+        }))
+    }
+
+    /// Predict over a CSV file a chunk of rows at a time, returning an iterator over the
+    /// predictions so that files larger than memory can be scored
+    /// ```
+    /// # use automl::{SupervisedModel, Settings};
+    /// use automl::batch::BatchOptions;
+    /// let mut model = SupervisedModel::new_from_dataset(
+    ///     smartcore::dataset::diabetes::load_dataset(),
+    ///     Settings::default_regression()
+    /// );
+    /// model.train();
+    /// # let csv = "a,b,c,d,e,f,g,h,i,j\n0,0,0,0,0,0,0,0,0,0\n";
+    /// # std::fs::write("tests/model_batches.csv", csv).unwrap();
+    /// let options = BatchOptions::default().with_chunk_size(1000);
+    /// let predictions = model.predict_from_csv_in_batches("tests/model_batches.csv", options);
+    /// for prediction in predictions.unwrap() {
+    ///     println!("{}", prediction.unwrap().value());
+    /// }
+    /// # std::fs::remove_file("tests/model_batches.csv");
+    /// ```
+    pub fn predict_from_csv_in_batches(
+        &self,
+        file_name: &str,
+        options: BatchOptions,
+    ) -> Result<BatchPredictions<'_>, BatchError> {
+        BatchPredictions::new(
+            file_name,
+            &self.schema,
+            &self.csv_options,
+            |x| self.predict(x),
+            self.probability_predictor(),
+            options,
+        )
+    }
+
+    /// Predict over a CSV file a chunk of rows at a time and write the predictions to another CSV
+    /// file, returning the number of rows written
+    /// ```
+    /// # use automl::{SupervisedModel, Settings};
+    /// use automl::batch::BatchOptions;
+    /// let mut model = SupervisedModel::new_from_dataset(
+    ///     smartcore::dataset::diabetes::load_dataset(),
+    ///     Settings::default_regression()
+    /// );
+    /// model.train();
+    /// # let csv = "id,a,b,c,d,e,f,g,h,i,j\n7,0,0,0,0,0,0,0,0,0,0\n";
+    /// # std::fs::write("tests/model_input.csv", csv).unwrap();
+    /// let options = BatchOptions::default().with_id_column("id");
+    /// let input = "tests/model_input.csv";
+    /// let rows = model.predict_csv_to_file(input, "tests/model_output.csv", options);
+    /// assert_eq!(rows.unwrap(), 1);
+    /// # std::fs::remove_file("tests/model_input.csv");
+    /// # std::fs::remove_file("tests/model_output.csv");
+    /// ```
+    pub fn predict_csv_to_file(
+        &self,
+        input_file_name: &str,
+        output_file_name: &str,
+        options: BatchOptions,
+    ) -> Result<usize, BatchError> {
+        self.predict_from_csv_in_batches(input_file_name, options)?
+            .write(output_file_name)
+    }
+
+    /// Attach an observer that is notified as the model comparison progresses
+    /// ```
+    /// # use automl::{SupervisedModel, Settings};
+    /// use automl::progress::ProgressObserver;
+    /// struct Quiet;
+    /// impl ProgressObserver for Quiet {}
+    ///
+    /// let model = SupervisedModel::new_from_dataset(
+    ///     smartcore::dataset::diabetes::load_dataset(),
+    ///     Settings::default_regression()
+    /// )
+    /// .with_progress_observer(Quiet);
+    /// ```
+    pub fn with_progress_observer(mut self, observer: impl ProgressObserver + 'static) -> Self {
+        self.observers.push(Box::new(observer));
+        self
+    }
+
+    /// Attach a token that can be used to stop the model comparison from another thread
+    /// ```
+    /// # use automl::{SupervisedModel, Settings};
+    /// use automl::progress::CancellationToken;
+    /// let token = CancellationToken::new();
+    /// let model = SupervisedModel::new_from_dataset(
+    ///     smartcore::dataset::diabetes::load_dataset(),
+    ///     Settings::default_regression()
+    /// )
+    /// .with_cancellation_token(token.clone());
+    /// ```
+    pub fn with_cancellation_token(mut self, token: CancellationToken) -> Self {
+        self.cancellation_token = Some(token);
+        self
     }
 
     /// Runs a model comparison and trains a final model.
@@ -151,6 +503,7 @@ impl SupervisedModel {
     /// ```
     pub fn train(&mut self) {
         // Preprocess the data
+        self.fit_preprocessing();
         self.x_train = self.preprocess(self.x_train.clone());
 
         // Split validatino out if blending
@@ -175,264 +528,1017 @@ impl SupervisedModel {
             }
         }
 
-        // Run logistic regression
-        if !self
-            .settings
-            .skiplist
-            .contains(&Algorithm::LogisticRegression)
-        {
-            self.record_model(LogisticRegressionWrapper::cv_model(
-                &self.x_train,
-                &self.y_train,
-                &self.settings,
-            ));
+        // Run the model comparison
+        let mut monitor = Monitor::new(
+            std::mem::take(&mut self.observers),
+            &self.settings,
+            self.cancellation_token.clone(),
+        );
+        let algorithms = self.candidate_algorithms();
+        for (i, algorithm) in algorithms.iter().enumerate() {
+            // Record algorithms that are never started once the comparison has been interrupted
+            if let Some(reason) = monitor.comparison_interruption() {
+                self.record_model(Model {
+                    name: *algorithm,
+                    status: Status::Skipped(reason),
+                    ..Default::default()
+                });
+                continue;
+            }
+
+            // Record algorithms that cannot be fit to these targets without running them
+            if let Err(reason) = self.check_targets(*algorithm) {
+                self.record_model(Model {
+                    name: *algorithm,
+                    status: Status::Skipped(Interruption::Unsuitable(reason)),
+                    ..Default::default()
+                });
+                continue;
+            }
+
+            monitor.algorithm_start(*algorithm, i + 1, algorithms.len());
+            let model = self.cv_by_algorithm(*algorithm, &mut monitor);
+            let mean_test_score = model.score.mean_test_score();
+            let duration = model.duration;
+            self.record_model(model);
+            let best = self
+                .completed_models()
+                .next()
+                .map(|best| (best.name, best.score.mean_test_score()));
+            monitor.algorithm_end(*algorithm, mean_test_score, duration, best);
         }
 
-        // Run random forest classification
-        if !self
-            .settings
-            .skiplist
-            .contains(&Algorithm::RandomForestClassifier)
-        {
-            self.record_model(RandomForestClassifierWrapper::cv_model(
-                &self.x_train,
-                &self.y_train,
-                &self.settings,
-            ));
+        // Only train a final model if at least one algorithm finished cross-validation
+        match self.settings.final_model_approach {
+            FinalModel::None => {}
+            _ if self.completed_models().next().is_none() => {}
+            FinalModel::Best => {
+                self.train_interval_models();
+                let best = &self.comparison[0];
+                monitor.final_model(best.name, best.score.mean_test_score());
+            }
+            FinalModel::Blending {
+                algorithm,
+                meta_training_fraction,
+                meta_testing_fraction,
+            } => {
+                self.train_blended_model(algorithm, meta_training_fraction, meta_testing_fraction);
+                monitor.final_model(algorithm, self.metamodel.score.mean_test_score());
+            }
         }
+        self.observers = monitor.into_observers();
+    }
 
-        // Run k-nearest neighbor classifier
-        if !self.settings.skiplist.contains(&Algorithm::KNNClassifier) {
-            self.record_model(KNNClassifierWrapper::cv_model(
-                &self.x_train,
-                &self.y_train,
-                &self.settings,
-            ));
+    /// Save the supervised model to a file for later use
+    /// ```
+    /// # use automl::{SupervisedModel, Settings};
+    /// let mut model = SupervisedModel::new_from_dataset(
+    ///     smartcore::dataset::diabetes::load_dataset(),
+    ///     Settings::default_regression()
+    /// );
+    /// model.save("tests/save_that_model.aml");
+    /// # std::fs::remove_file("tests/save_that_model.aml");
+    /// ```
+    pub fn save(&self, file_name: &str) {
+        file_format::save(self, Contents::SupervisedModel, file_name);
+    }
+
+    /// Export a lightweight predictor that holds only what is needed to make predictions with the
+    /// final model, leaving out the training data and the other compared models
+    /// ```
+    /// # use automl::{SupervisedModel, Settings};
+    /// let mut model = SupervisedModel::new_from_dataset(
+    ///     smartcore::dataset::diabetes::load_dataset(),
+    ///     Settings::default_regression()
+    /// );
+    /// model.train();
+    /// let predictor = model.export_predictor();
+    /// let x = vec![vec![5.0; 10]; 5];
+    /// assert_eq!(predictor.predict_from_vec(x.clone()), model.predict_from_vec(x));
+    /// ```
+    pub fn export_predictor(&self) -> Predictor {
+        assert!(
+            self.completed_models().next().is_some(),
+            "No algorithm finished cross-validation, so there is no final model."
+        );
+        let (models, metamodel): (Vec<&Model>, _) = match self.settings.final_model_approach {
+            FinalModel::None => panic!("The settings do not train a final model."),
+            FinalModel::Best => (vec![&self.comparison[0]], None),
+            FinalModel::Blending { .. } => (
+                self.completed_models().collect(),
+                Some((self.metamodel.name, self.metamodel.model.clone())),
+            ),
+        };
+        Predictor::new(
+            clone_by_serialization(&self.settings),
+            self.schema.clone(),
+            clone_by_serialization(&self.preprocessing),
+            models
+                .into_iter()
+                .map(|model| (model.name, model.model.clone()))
+                .collect(),
+            metamodel,
+            self.csv_options.clone(),
+        )
+    }
+
+    /// Get the names and order of the features the model was built with
+    /// ```
+    /// # use automl::{SupervisedModel, Settings};
+    /// let model = SupervisedModel::new_from_dataset(
+    ///     smartcore::dataset::diabetes::load_dataset(),
+    ///     Settings::default_regression()
+    /// );
+    /// assert_eq!(model.schema().names()[0], "x_0");
+    /// ```
+    pub fn schema(&self) -> &Schema {
+        &self.schema
+    }
+
+    /// Get the names of the features after preprocessing, such as `x_1*x_3` for an interaction or
+    /// `pca_2` for a principal component
+    /// ```
+    /// # use automl::{SupervisedModel, Settings, settings::PreProcessing};
+    /// let model = SupervisedModel::new_from_vec_with_names(
+    ///     vec![vec![1.0, 2.0], vec![2.0, 1.0], vec![3.0, 3.0]],
+    ///     vec![1.0, 2.0, 3.0],
+    ///     &["age", "income"],
+    ///     Settings::default_regression().with_preprocessing(PreProcessing::AddInteractions),
+    /// );
+    /// assert_eq!(
+    ///     model.preprocessed_feature_names(),
+    ///     vec!["age", "income", "age*income"]
+    /// );
+    /// ```
+    pub fn preprocessed_feature_names(&self) -> Vec<String> {
+        self.schema.preprocessed_names(&self.settings.preprocessing)
+    }
+
+    /// Get the results of the model comparison, best first, in the same order as the printed table
+    /// ```
+    /// # use automl::{SupervisedModel, Settings};
+    /// let mut model = SupervisedModel::new_from_dataset(
+    ///     smartcore::dataset::diabetes::load_dataset(),
+    ///     Settings::default_regression()
+    /// );
+    /// model.train();
+    /// for entry in model.leaderboard() {
+    ///     println!("{}: {}", entry.algorithm(), entry.mean_test_score());
+    /// }
+    /// ```
+    pub fn leaderboard(&self) -> Vec<LeaderboardEntry> {
+        self.comparison
+            .iter()
+            .map(|model| LeaderboardEntry::new(model, &self.settings))
+            .collect()
+    }
+
+    /// Get the best algorithm that finished cross-validation, if training has been run
+    /// ```
+    /// # use automl::{SupervisedModel, Settings};
+    /// use automl::settings::Algorithm;
+    /// let mut model = SupervisedModel::new_from_dataset(
+    ///     smartcore::dataset::diabetes::load_dataset(),
+    ///     Settings::default_regression().only(Algorithm::Linear)
+    /// );
+    /// assert_eq!(model.best_algorithm(), None);
+    /// model.train();
+    /// assert_eq!(model.best_algorithm(), Some(Algorithm::Linear));
+    /// ```
+    pub fn best_algorithm(&self) -> Option<Algorithm> {
+        self.completed_models().next().map(|model| model.name)
+    }
+
+    /// Measure how much each input feature matters to the final model with permutation importance.
+    ///
+    /// Each feature of the held-out data `x` is shuffled `repeats` times and the final model is
+    /// scored with the sorting metric after every shuffle. The importance is the drop from the
+    /// unshuffled score, oriented so that larger is always more important. Features are returned
+    /// in input order. Use data that was not part of training, otherwise the importance of features
+    /// the model has overfit to is overstated.
+    /// ```
+    /// # use automl::{SupervisedModel, Settings};
+    /// let mut model = SupervisedModel::new_from_dataset(
+    ///     smartcore::dataset::diabetes::load_dataset(),
+    ///     Settings::default_regression()
+    /// );
+    /// model.train();
+    /// let x = vec![vec![0.0; 10], vec![0.1; 10]];
+    /// let importance = model.feature_importance(x, vec![150.0, 250.0], 5);
+    /// ```
+    pub fn feature_importance(
+        &self,
+        x: Vec<Vec<f32>>,
+        y: Vec<f32>,
+        repeats: usize,
+    ) -> Vec<FeatureImportance> {
+        self.permutation_importance(&DenseMatrix::from_2d_vec(&x), &y, repeats)
+    }
+
+    /// Make an evenly spaced grid of values spanning the training data for a feature, for use with
+    /// [`partial_dependence`](Self::partial_dependence)
+    /// ```
+    /// # use automl::{SupervisedModel, Settings};
+    /// let model = SupervisedModel::new_from_dataset(
+    ///     smartcore::dataset::diabetes::load_dataset(),
+    ///     Settings::default_regression()
+    /// );
+    /// let grid = model.feature_grid(2, 10);
+    /// assert_eq!(grid.len(), 10);
+    /// ```
+    pub fn feature_grid(&self, feature: usize, number_of_points: usize) -> Vec<f32> {
+        let column = self.x_raw.get_col_as_vec(feature);
+        let min = column.iter().cloned().fold(f32::INFINITY, f32::min);
+        let max = column.iter().cloned().fold(f32::NEG_INFINITY, f32::max);
+        match number_of_points {
+            0 => vec![],
+            1 => vec![(min + max) / 2.0],
+            n => (0..n)
+                .map(|i| min + (max - min) * i as f32 / (n - 1) as f32)
+                .collect(),
         }
+    }
 
-        if !self
-            .settings
-            .skiplist
-            .contains(&Algorithm::DecisionTreeClassifier)
-        {
-            self.record_model(DecisionTreeClassifierWrapper::cv_model(
-                &self.x_train,
-                &self.y_train,
-                &self.settings,
-            ));
+    /// Compute the partial dependence of the final model on a feature, along with individual
+    /// conditional expectation (ICE) curves. Every training sample is predicted with the feature
+    /// set to each value in `grid`; the ICE curves are those predictions and the partial dependence
+    /// is their mean.
+    /// ```
+    /// # use automl::{SupervisedModel, Settings};
+    /// let mut model = SupervisedModel::new_from_dataset(
+    ///     smartcore::dataset::diabetes::load_dataset(),
+    ///     Settings::default_regression()
+    /// );
+    /// model.train();
+    /// let dependence = model.partial_dependence(2, vec![-0.1, 0.0, 0.1]);
+    /// println!("{:?}", dependence.average());
+    /// ```
+    pub fn partial_dependence(&self, feature: usize, grid: Vec<f32>) -> PartialDependence {
+        assert!(
+            !grid.is_empty(),
+            "The grid must contain at least one value."
+        );
+        let (height, _) = self.x_raw.shape();
+        let mut individual = vec![Vec::with_capacity(grid.len()); height];
+        let mut average = Vec::with_capacity(grid.len());
+        for value in &grid {
+            let predictions = self.predict_with_features_set(&[(feature, *value)]);
+            average.push(predictions.iter().sum::<f32>() / height as f32);
+            for (curve, prediction) in individual.iter_mut().zip(predictions) {
+                curve.push(prediction);
+            }
+        }
+        PartialDependence {
+            feature: self.schema.names()[feature].clone(),
+            grid,
+            average,
+            individual,
         }
+    }
 
-        if !self
-            .settings
-            .skiplist
-            .contains(&Algorithm::GaussianNaiveBayes)
-        {
-            self.record_model(GaussianNaiveBayesClassifierWrapper::cv_model(
-                &self.x_train,
-                &self.y_train,
-                &self.settings,
-            ));
+    /// Compute the partial dependence of the final model on a pair of features, to show how they
+    /// interact
+    /// ```
+    /// # use automl::{SupervisedModel, Settings};
+    /// let mut model = SupervisedModel::new_from_dataset(
+    ///     smartcore::dataset::diabetes::load_dataset(),
+    ///     Settings::default_regression()
+    /// );
+    /// model.train();
+    /// let grids = (model.feature_grid(2, 5), model.feature_grid(3, 5));
+    /// let dependence = model.partial_dependence_2d((2, 3), grids);
+    /// ```
+    pub fn partial_dependence_2d(
+        &self,
+        features: (usize, usize),
+        grids: (Vec<f32>, Vec<f32>),
+    ) -> PartialDependence2D {
+        assert!(
+            !grids.0.is_empty() && !grids.1.is_empty(),
+            "Each grid must contain at least one value."
+        );
+        let (height, _) = self.x_raw.shape();
+        let mut average = Vec::with_capacity(grids.0.len());
+        for first in &grids.0 {
+            let mut row = Vec::with_capacity(grids.1.len());
+            for second in &grids.1 {
+                let predictions =
+                    self.predict_with_features_set(&[(features.0, *first), (features.1, *second)]);
+                row.push(predictions.iter().sum::<f32>() / height as f32);
+            }
+            average.push(row);
+        }
+        PartialDependence2D {
+            features: (
+                self.schema.names()[features.0].clone(),
+                self.schema.names()[features.1].clone(),
+            ),
+            grids,
+            average,
         }
+    }
 
-        if !self
-            .settings
-            .skiplist
-            .contains(&Algorithm::CategoricalNaiveBayes)
-            && std::mem::discriminant(&self.settings.preprocessing)
-                != std::mem::discriminant(&PreProcessing::ReplaceWithPCA {
-                    number_of_components: 1,
-                })
-            && std::mem::discriminant(&self.settings.preprocessing)
-                != std::mem::discriminant(&PreProcessing::ReplaceWithSVD {
-                    number_of_components: 1,
+    /// Explain individual predictions of the final model with SHAP values, giving the contribution
+    /// of each input feature.
+    ///
+    /// When the final model is a decision tree, random forest or extra trees model trained without
+    /// preprocessing, exact TreeSHAP values are computed with the training data as background.
+    /// Every other final model, including blended ones, is explained with a Kernel SHAP
+    /// approximation against a background sample of up to 50 training rows, which needs many
+    /// predictions per explained row. In both cases the contributions add up to the difference
+    /// between the explained output and the base value. For random forest and extra trees
+    /// classifiers the explained output is the mean over the trees of the class label each tree
+    /// predicts, which can differ from the class the forest predicts.
+    /// ```
+    /// # use automl::{SupervisedModel, Settings};
+    /// let mut model = SupervisedModel::new_from_dataset(
+    ///     smartcore::dataset::diabetes::load_dataset(),
+    ///     Settings::default_regression()
+    /// );
+    /// model.train();
+    /// for explanation in model.explain(vec![vec![0.01; 10]]) {
+    ///     println!("{:?}", explanation.contributions());
+    /// }
+    /// ```
+    pub fn explain(&self, x: Vec<Vec<f32>>) -> Vec<Explanation> {
+        let x = DenseMatrix::from_2d_vec(&x);
+        let (base_value, contributions) = match self.tree_shap(&x) {
+            Some(shap) => shap,
+            None => self.kernel_shap(&x),
+        };
+        contributions
+            .into_iter()
+            .map(|row| Explanation::new(self.schema.names().to_vec(), base_value, row))
+            .collect()
+    }
+
+    /// Get the fitted coefficients of a linear model from the comparison. This is available for
+    /// linear, ridge, LASSO, elastic net and logistic regression once the model has been trained
+    /// with a final model, and returns `None` otherwise.
+    /// ```
+    /// # use automl::{SupervisedModel, Settings};
+    /// use automl::settings::Algorithm;
+    /// let mut model = SupervisedModel::new_from_dataset(
+    ///     smartcore::dataset::diabetes::load_dataset(),
+    ///     Settings::default_regression()
+    /// );
+    /// model.train();
+    /// let coefficients = model.coefficients(Algorithm::Ridge).unwrap();
+    /// assert!(model.coefficients(Algorithm::KNNRegressor).is_none());
+    /// ```
+    pub fn coefficients(&self, algorithm: Algorithm) -> Option<Coefficients> {
+        let saved_model = &self.trained_model(algorithm)?.model;
+        let (coefficients, intercepts) = match algorithm {
+            Algorithm::Linear => LinearRegressorWrapper::coefficients(saved_model),
+            Algorithm::Lasso => LassoRegressorWrapper::coefficients(saved_model),
+            Algorithm::Ridge => RidgeRegressorWrapper::coefficients(saved_model),
+            Algorithm::ElasticNet => ElasticNetRegressorWrapper::coefficients(saved_model),
+            Algorithm::LogisticRegression => LogisticRegressionWrapper::coefficients(saved_model),
+            _ => None,
+        }?;
+        Some(Coefficients::new(
+            self.preprocessed_feature_names(),
+            coefficients,
+            intercepts,
+        ))
+    }
+
+    /// Get the impurity-based feature importances of a tree model from the comparison, normalized
+    /// to sum to one. This is available for decision trees, random forests and extra trees once the
+    /// model has been trained with a final model, and returns `None` otherwise. For random forests
+    /// and extra trees the mean and standard deviation are taken over the trees.
+    /// ```
+    /// # use automl::{SupervisedModel, Settings};
+    /// use automl::settings::Algorithm;
+    /// let mut model = SupervisedModel::new_from_dataset(
+    ///     smartcore::dataset::diabetes::load_dataset(),
+    ///     Settings::default_regression()
+    /// );
+    /// model.train();
+    /// for importance in model.impurity_importance(Algorithm::RandomForestRegressor).unwrap() {
+    ///     println!("{}: {}", importance.feature(), importance.mean());
+    /// }
+    /// ```
+    pub fn impurity_importance(&self, algorithm: Algorithm) -> Option<Vec<FeatureImportance>> {
+        let saved_model = &self.trained_model(algorithm)?.model;
+        let x = &self.x_train;
+        let per_tree = match algorithm {
+            Algorithm::DecisionTreeRegressor => {
+                DecisionTreeRegressorWrapper::impurity_importances(x, saved_model)
+            }
+            Algorithm::RandomForestRegressor => {
+                RandomForestRegressorWrapper::impurity_importances(x, saved_model)
+            }
+            Algorithm::DecisionTreeClassifier => {
+                DecisionTreeClassifierWrapper::impurity_importances(x, saved_model)
+            }
+            Algorithm::RandomForestClassifier => {
+                RandomForestClassifierWrapper::impurity_importances(x, saved_model)
+            }
+            Algorithm::ExtraTreesRegressor => {
+                ExtraTreesRegressorWrapper::impurity_importances(x, saved_model)
+            }
+            Algorithm::ExtraTreesClassifier => {
+                ExtraTreesClassifierWrapper::impurity_importances(x, saved_model)
+            }
+            _ => None,
+        }?;
+        Some(
+            self.preprocessed_feature_names()
+                .into_iter()
+                .enumerate()
+                .map(|(column, feature)| {
+                    let values: Vec<f32> = per_tree.iter().map(|tree| tree[column]).collect();
+                    FeatureImportance::new(feature, &values)
                 })
-        {
-            self.record_model(CategoricalNaiveBayesClassifierWrapper::cv_model(
-                &self.x_train,
-                &self.y_train,
-                &self.settings,
-            ));
-        }
+                .collect(),
+        )
+    }
 
-        if self.number_of_classes == 2 && !self.settings.skiplist.contains(&Algorithm::SVC) {
-            self.record_model(SupportVectorClassifierWrapper::cv_model(
-                &self.x_train,
-                &self.y_train,
-                &self.settings,
-            ));
-        }
+    /// Summarize the model comparison in a report that can be written as JSON, CSV, Markdown or
+    /// HTML
+    /// ```
+    /// # use automl::{SupervisedModel, Settings};
+    /// let mut model = SupervisedModel::new_from_dataset(
+    ///     smartcore::dataset::diabetes::load_dataset(),
+    ///     Settings::default_regression()
+    /// );
+    /// model.train();
+    /// let report = model.report();
+    /// std::fs::write("tests/report.html", report.to_html()).unwrap();
+    /// # std::fs::remove_file("tests/report.html");
+    /// ```
+    pub fn report(&self) -> Report {
+        #[cfg(any(feature = "display"))]
+        let settings = format!("{}", self.settings);
+        #[cfg(not(any(feature = "display")))]
+        let settings = serde_yaml::to_string(&self.settings).expect("Cannot serialize settings.");
+
+        // Count the samples in each class, including those held out for blending
+        let class_counts = if self.settings.is_classification() {
+            let mut counts = BTreeMap::new();
+            for class in self.y_train.iter().chain(self.y_val.iter()) {
+                *counts.entry(format!("{}", class)).or_insert(0) += 1;
+            }
+            Some(counts)
+        } else {
+            None
+        };
 
-        if !self.settings.skiplist.contains(&Algorithm::Linear) {
-            self.record_model(LinearRegressorWrapper::cv_model(
-                &self.x_train,
-                &self.y_train,
-                &self.settings,
-            ));
-        }
+        let meta_model = match self.settings.final_model_approach {
+            FinalModel::Blending { .. } if !self.metamodel.score.test_score.is_empty() => {
+                Some(LeaderboardEntry::new(&self.metamodel, &self.settings))
+            }
+            _ => None,
+        };
 
-        if !self.settings.skiplist.contains(&Algorithm::SVR) {
-            self.record_model(SupportVectorRegressorWrapper::cv_model(
-                &self.x_train,
-                &self.y_train,
-                &self.settings,
-            ));
+        Report {
+            settings,
+            number_of_samples: self.y_train.len() + self.y_val.len(),
+            number_of_features: self.x_train.shape().1,
+            class_counts,
+            leaderboard: self.leaderboard(),
+            meta_model,
         }
+    }
+}
 
-        if !self.settings.skiplist.contains(&Algorithm::Lasso) {
-            self.record_model(RidgeRegressorWrapper::cv_model(
-                &self.x_train,
-                &self.y_train,
-                &self.settings,
-            ));
-        }
+#[cfg_attr(docsrs, doc(cfg(feature = "dataframe")))]
+#[cfg(any(feature = "dataframe"))]
+impl SupervisedModel {
+    /// Create a new supervised model from a polars `DataFrame`, using the column named `target` as
+    /// the target and every other column as a feature
+    /// ```no_run
+    /// # use automl::{SupervisedModel, Settings};
+    /// use polars::prelude::*;
+    /// let df = df!(
+    ///     "x" => &[1.0, 2.0, 3.0, 4.0, 5.0, 6.0],
+    ///     "y" => &[2.0, 4.0, 6.0, 8.0, 10.0, 12.0]
+    /// )
+    /// .unwrap();
+    /// let model = SupervisedModel::new_from_dataframe(df, "y", Settings::default_regression());
+    /// ```
+    pub fn new_from_dataframe(df: DataFrame, target: &str, settings: Settings) -> Self {
+        Self::new_from_dataframe_with_options(df, target, settings, DataFrameOptions::default())
+    }
 
-        if !self.settings.skiplist.contains(&Algorithm::Ridge) {
-            self.record_model(LassoRegressorWrapper::cv_model(
-                &self.x_train,
-                &self.y_train,
-                &self.settings,
-            ));
-        }
+    /// Create a new supervised model from a polars `DataFrame`, choosing the feature columns and
+    /// how to handle columns that are not numeric with [`DataFrameOptions`]
+    /// ```no_run
+    /// # use automl::{SupervisedModel, Settings};
+    /// use automl::dataframe::DataFrameOptions;
+    /// use polars::prelude::*;
+    /// let df = df!(
+    ///     "id" => &["a", "b", "c", "d"],
+    ///     "x" => &[1.0, 2.0, 3.0, 4.0],
+    ///     "y" => &[2.0, 4.0, 6.0, 8.0]
+    /// )
+    /// .unwrap();
+    /// let model = SupervisedModel::new_from_dataframe_with_options(
+    ///     df,
+    ///     "y",
+    ///     Settings::default_regression(),
+    ///     DataFrameOptions::default().with_dropped_columns(&["id"]),
+    /// );
+    /// ```
+    pub fn new_from_dataframe_with_options(
+        df: DataFrame,
+        target: &str,
+        settings: Settings,
+        options: DataFrameOptions,
+    ) -> Self {
+        // Get target variables
+        let series = df
+            .column(target)
+            .unwrap_or_else(|_| panic!("Cannot find the target column {}.", target))
+            .cast(&DataType::Float32)
+            .unwrap_or_else(|_| panic!("Cannot cast the target column {} to f32.", target));
+        let target_df = DataFrame::new(vec![series]).unwrap();
+        let y = target_df
+            .to_ndarray::<Float32Type>()
+            .unwrap()
+            .into_raw_vec();
 
-        if !self.settings.skiplist.contains(&Algorithm::ElasticNet) {
-            self.record_model(ElasticNetRegressorWrapper::cv_model(
-                &self.x_train,
-                &self.y_train,
-                &self.settings,
-            ));
-        }
+        // Get the rest of the data
+        let features = options.features(&df, target);
+        let x = Self::polars_to_matrix(&features).unwrap_or_else(|error| panic!("{}", error));
+        let mut model = SupervisedModel::new(x, y, settings);
+        model.schema = Schema::named(
+            features
+                .get_column_names()
+                .iter()
+                .map(|name| name.to_string())
+                .collect(),
+        )
+        .with_types(Self::feature_types(&features));
+        model
+    }
 
-        if !self
-            .settings
-            .skiplist
-            .contains(&Algorithm::DecisionTreeRegressor)
-        {
-            self.record_model(DecisionTreeRegressorWrapper::cv_model(
-                &self.x_train,
-                &self.y_train,
-                &self.settings,
-            ));
-        }
+    /// Predict values from a polars `DataFrame`. The features are picked out by name, so the frame
+    /// may hold other columns as well.
+    /// ```no_run
+    /// # use automl::{SupervisedModel, Settings};
+    /// use polars::prelude::*;
+    /// let df = df!(
+    ///     "x" => &[1.0, 2.0, 3.0, 4.0, 5.0, 6.0],
+    ///     "y" => &[2.0, 4.0, 6.0, 8.0, 10.0, 12.0]
+    /// )
+    /// .unwrap();
+    /// let mut model = SupervisedModel::new_from_dataframe(
+    ///     df.clone(),
+    ///     "y",
+    ///     Settings::default_regression()
+    /// );
+    /// model.train();
+    /// let predictions = model.predict_from_dataframe(&df);
+    /// ```
+    pub fn predict_from_dataframe(&self, df: &DataFrame) -> Vec<f32> {
+        self.try_predict_from_dataframe(df)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
 
-        if !self
-            .settings
-            .skiplist
-            .contains(&Algorithm::RandomForestRegressor)
-        {
-            self.record_model(RandomForestRegressorWrapper::cv_model(
-                &self.x_train,
-                &self.y_train,
-                &self.settings,
-            ));
+    /// Predict values from a polars `DataFrame`, returning an error listing the features that are
+    /// missing or have the wrong type
+    /// ```no_run
+    /// # use automl::{SupervisedModel, Settings};
+    /// use polars::prelude::*;
+    /// let df = df!(
+    ///     "x" => &[1.0, 2.0, 3.0, 4.0, 5.0, 6.0],
+    ///     "y" => &[2.0, 4.0, 6.0, 8.0, 10.0, 12.0]
+    /// )
+    /// .unwrap();
+    /// let mut model = SupervisedModel::new_from_dataframe(
+    ///     df.clone(),
+    ///     "y",
+    ///     Settings::default_regression()
+    /// );
+    /// model.train();
+    /// match model.try_predict_from_dataframe(&df) {
+    ///     Ok(predictions) => println!("{:?}", predictions),
+    ///     Err(error) => println!("{}", error),
+    /// }
+    /// ```
+    pub fn try_predict_from_dataframe(&self, df: &DataFrame) -> Result<Vec<f32>, SchemaError> {
+        let present: Vec<String> = df
+            .get_column_names()
+            .iter()
+            .map(|name| name.to_string())
+            .collect();
+        let missing: Vec<String> = self
+            .schema
+            .names()
+            .iter()
+            .filter(|name| !present.contains(name))
+            .cloned()
+            .collect();
+        if !missing.is_empty() {
+            return Err(SchemaError::Columns {
+                missing,
+                extra: vec![],
+                out_of_order: vec![],
+            });
         }
+        let features = DataFrame::new(
+            self.schema
+                .names()
+                .iter()
+                .map(|name| df.column(name).unwrap().clone())
+                .collect(),
+        )
+        .unwrap();
+        self.try_predict_from_polars(&features, true)
+    }
 
-        if !self.settings.skiplist.contains(&Algorithm::KNNRegressor) {
-            self.record_model(KNNRegressorWrapper::cv_model(
-                &self.x_train,
-                &self.y_train,
-                &self.settings,
-            ));
-        }
+    /// Predict values from a polars `DataFrame` and return a copy of the frame with the
+    /// predictions appended as a column called `name`
+    /// ```no_run
+    /// # use automl::{SupervisedModel, Settings};
+    /// use polars::prelude::*;
+    /// let df = df!(
+    ///     "x" => &[1.0, 2.0, 3.0, 4.0, 5.0, 6.0],
+    ///     "y" => &[2.0, 4.0, 6.0, 8.0, 10.0, 12.0]
+    /// )
+    /// .unwrap();
+    /// let mut model = SupervisedModel::new_from_dataframe(
+    ///     df.clone(),
+    ///     "y",
+    ///     Settings::default_regression()
+    /// );
+    /// model.train();
+    /// let with_predictions = model.predict_into_dataframe(&df, "prediction");
+    /// assert_eq!(with_predictions.width(), 3);
+    /// ```
+    pub fn predict_into_dataframe(&self, df: &DataFrame, name: &str) -> DataFrame {
+        self.try_predict_into_dataframe(df, name)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
 
-        match self.settings.final_model_approach {
-            FinalModel::Blending {
-                algorithm,
-                meta_training_fraction,
-                meta_testing_fraction,
-            } => self.train_blended_model(algorithm, meta_training_fraction, meta_testing_fraction),
-            _ => {}
-        }
+    /// Predict values from a polars `DataFrame` and return a copy of the frame with the
+    /// predictions appended as a column called `name`, or an error if the features do not match
+    /// the schema
+    /// ```no_run
+    /// # use automl::{SupervisedModel, Settings};
+    /// use polars::prelude::*;
+    /// let df = df!(
+    ///     "x" => &[1.0, 2.0, 3.0, 4.0, 5.0, 6.0],
+    ///     "y" => &[2.0, 4.0, 6.0, 8.0, 10.0, 12.0]
+    /// )
+    /// .unwrap();
+    /// let mut model = SupervisedModel::new_from_dataframe(
+    ///     df.clone(),
+    ///     "y",
+    ///     Settings::default_regression()
+    /// );
+    /// model.train();
+    /// let with_predictions = model.try_predict_into_dataframe(&df, "prediction").unwrap();
+    /// ```
+    pub fn try_predict_into_dataframe(
+        &self,
+        df: &DataFrame,
+        name: &str,
+    ) -> Result<DataFrame, SchemaError> {
+        let predictions = self.try_predict_from_dataframe(df)?;
+        let mut output = df.clone();
+        output
+            .with_column(Series::new(name, predictions))
+            .expect("Cannot append the predictions");
+        Ok(output)
+    }
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "csv")))]
+#[cfg(any(feature = "csv"))]
+impl SupervisedModel {
+    /// Create a new supervised model from a csv
+    /// ```
+    /// # use automl::{SupervisedModel, Settings};
+    /// let model = SupervisedModel::new_from_csv(
+    ///     "data/diabetes.csv",
+    ///     10,
+    ///     true,
+    ///     Settings::default_regression()
+    /// );
+    /// ```
+    pub fn new_from_csv(
+        filepath: &str,
+        target_index: usize,
+        header: bool,
+        settings: Settings,
+    ) -> Self {
+        Self::new_from_csv_with_options(
+            filepath,
+            target_index,
+            header,
+            settings,
+            CsvOptions::default(),
+        )
+    }
+
+    /// Create a new supervised model from a csv, parsed with [`CsvOptions`]. The options are saved
+    /// with the model and used again by [`predict_from_csv`](Self::predict_from_csv).
+    /// ```
+    /// # use automl::{SupervisedModel, Settings};
+    /// use automl::csv_options::CsvOptions;
+    /// let model = SupervisedModel::new_from_csv_with_options(
+    ///     "data/diabetes.csv",
+    ///     10,
+    ///     true,
+    ///     Settings::default_regression(),
+    ///     CsvOptions::default().with_null_values(&["NA"]).with_comment_prefix("#"),
+    /// );
+    /// ```
+    pub fn new_from_csv_with_options(
+        filepath: &str,
+        target_index: usize,
+        header: bool,
+        settings: Settings,
+        options: CsvOptions,
+    ) -> Self {
+        let df = options
+            .read(filepath, header)
+            .unwrap_or_else(|error| panic!("{}", error));
+        let target = df.get_column_names()[target_index].to_string();
+        let mut model = SupervisedModel::new_from_dataframe(df, &target, settings);
+        model.csv_options = options;
+        model
+    }
+
+    /// Predict values from a csv, parsed with the options the model was built with
+    /// ```no_run
+    /// # use automl::{SupervisedModel, Settings};
+    /// let mut model = SupervisedModel::new_from_csv(
+    ///     "data/diabetes.csv",
+    ///     10,
+    ///     true,
+    ///     Settings::default_regression()
+    /// );
+    /// model.predict_from_csv(
+    ///     "data/diabetes_without_target.csv",
+    ///     true
+    /// );
+    /// ```
+    pub fn predict_from_csv(&self, filepath: &str, header: bool) -> Vec<f32> {
+        self.try_predict_from_csv(filepath, header)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Predict values from a csv, returning an error if its columns do not match the schema. If the
+    /// file has a header and the model was built with named features, the error lists the columns
+    /// that are missing, extra or out of order.
+    /// ```no_run
+    /// # use automl::{SupervisedModel, Settings};
+    /// let mut model = SupervisedModel::new_from_csv(
+    ///     "data/diabetes.csv",
+    ///     10,
+    ///     true,
+    ///     Settings::default_regression()
+    /// );
+    /// model.train();
+    /// match model.try_predict_from_csv("data/diabetes_without_target.csv", true) {
+    ///     Ok(predictions) => println!("{:?}", predictions),
+    ///     Err(error) => println!("{}", error),
+    /// }
+    /// ```
+    pub fn try_predict_from_csv(
+        &self,
+        filepath: &str,
+        header: bool,
+    ) -> Result<Vec<f32>, SchemaError> {
+        self.try_predict_from_csv_with_options(filepath, header, &self.csv_options)
+    }
+
+    /// Predict values from a csv, parsed with different options than the model was built with
+    /// ```no_run
+    /// # use automl::{SupervisedModel, Settings};
+    /// use automl::csv_options::CsvOptions;
+    /// let mut model = SupervisedModel::new_from_csv(
+    ///     "data/diabetes.csv",
+    ///     10,
+    ///     true,
+    ///     Settings::default_regression()
+    /// );
+    /// model.train();
+    /// model.predict_from_csv_with_options(
+    ///     "data/diabetes_without_target.tsv",
+    ///     true,
+    ///     &CsvOptions::default().with_delimiter('\t'),
+    /// );
+    /// ```
+    pub fn predict_from_csv_with_options(
+        &self,
+        filepath: &str,
+        header: bool,
+        options: &CsvOptions,
+    ) -> Vec<f32> {
+        self.try_predict_from_csv_with_options(filepath, header, options)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Predict values from a csv, parsed with different options than the model was built with,
+    /// returning an error if its columns do not match the schema
+    /// ```no_run
+    /// # use automl::{SupervisedModel, Settings};
+    /// use automl::csv_options::CsvOptions;
+    /// let mut model = SupervisedModel::new_from_csv(
+    ///     "data/diabetes.csv",
+    ///     10,
+    ///     true,
+    ///     Settings::default_regression()
+    /// );
+    /// model.train();
+    /// let predictions = model.try_predict_from_csv_with_options(
+    ///     "data/diabetes_without_target.tsv",
+    ///     true,
+    ///     &CsvOptions::default().with_delimiter('\t'),
+    /// );
+    /// ```
+    pub fn try_predict_from_csv_with_options(
+        &self,
+        filepath: &str,
+        header: bool,
+        options: &CsvOptions,
+    ) -> Result<Vec<f32>, SchemaError> {
+        self.try_predict_from_polars(&options.read(filepath, header)?, header)
+    }
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "parquet")))]
+#[cfg(any(feature = "parquet"))]
+impl SupervisedModel {
+    /// Create a new supervised model from a Parquet file, using the column named `target` as the
+    /// target and every other column as a feature
+    /// ```no_run
+    /// # use automl::{SupervisedModel, Settings};
+    /// let model = SupervisedModel::new_from_parquet(
+    ///     "data/diabetes.parquet",
+    ///     "y",
+    ///     Settings::default_regression()
+    /// );
+    /// ```
+    pub fn new_from_parquet(filepath: &str, target: &str, settings: Settings) -> Self {
+        let df = ParquetReader::new(std::fs::File::open(filepath).expect("Cannot find file"))
+            .finish()
+            .expect("Cannot read file as Parquet");
+        SupervisedModel::new_from_dataframe(df, target, settings)
+    }
+
+    /// Predict values from a Parquet file
+    /// ```no_run
+    /// # use automl::{SupervisedModel, Settings};
+    /// let mut model = SupervisedModel::new_from_parquet(
+    ///     "data/diabetes.parquet",
+    ///     "y",
+    ///     Settings::default_regression()
+    /// );
+    /// model.train();
+    /// model.predict_from_parquet("data/diabetes_without_target.parquet");
+    /// ```
+    pub fn predict_from_parquet(&self, filepath: &str) -> Vec<f32> {
+        self.try_predict_from_parquet(filepath)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Predict values from a Parquet file, returning an error if it cannot be read or if its
+    /// columns do not match the schema
+    /// ```no_run
+    /// # use automl::{SupervisedModel, Settings};
+    /// let mut model = SupervisedModel::new_from_parquet(
+    ///     "data/diabetes.parquet",
+    ///     "y",
+    ///     Settings::default_regression()
+    /// );
+    /// model.train();
+    /// match model.try_predict_from_parquet("data/diabetes_without_target.parquet") {
+    ///     Ok(predictions) => println!("{:?}", predictions),
+    ///     Err(error) => println!("{}", error),
+    /// }
+    /// ```
+    pub fn try_predict_from_parquet(&self, filepath: &str) -> Result<Vec<f32>, SchemaError> {
+        let file = std::fs::File::open(filepath).map_err(Self::unreadable(filepath))?;
+        let df = ParquetReader::new(file)
+            .finish()
+            .map_err(Self::unreadable(filepath))?;
+        self.try_predict_from_polars(&df, true)
+    }
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "ipc")))]
+#[cfg(any(feature = "ipc"))]
+impl SupervisedModel {
+    /// Create a new supervised model from an Arrow IPC file, using the column named `target` as the
+    /// target and every other column as a feature
+    /// ```no_run
+    /// # use automl::{SupervisedModel, Settings};
+    /// let model = SupervisedModel::new_from_ipc(
+    ///     "data/diabetes.arrow",
+    ///     "y",
+    ///     Settings::default_regression()
+    /// );
+    /// ```
+    pub fn new_from_ipc(filepath: &str, target: &str, settings: Settings) -> Self {
+        let df = IpcReader::new(std::fs::File::open(filepath).expect("Cannot find file"))
+            .finish()
+            .expect("Cannot read file as Arrow IPC");
+        SupervisedModel::new_from_dataframe(df, target, settings)
+    }
+
+    /// Predict values from an Arrow IPC file
+    /// ```no_run
+    /// # use automl::{SupervisedModel, Settings};
+    /// let mut model = SupervisedModel::new_from_ipc(
+    ///     "data/diabetes.arrow",
+    ///     "y",
+    ///     Settings::default_regression()
+    /// );
+    /// model.train();
+    /// model.predict_from_ipc("data/diabetes_without_target.arrow");
+    /// ```
+    pub fn predict_from_ipc(&self, filepath: &str) -> Vec<f32> {
+        self.try_predict_from_ipc(filepath)
+            .unwrap_or_else(|error| panic!("{}", error))
     }
 
-    /// Save the supervised model to a file for later use
-    /// ```
+    /// Predict values from an Arrow IPC file, returning an error if it cannot be read or if its
+    /// columns do not match the schema
+    /// ```no_run
     /// # use automl::{SupervisedModel, Settings};
-    /// let mut model = SupervisedModel::new_from_dataset(
-    ///     smartcore::dataset::diabetes::load_dataset(),
+    /// let mut model = SupervisedModel::new_from_ipc(
+    ///     "data/diabetes.arrow",
+    ///     "y",
     ///     Settings::default_regression()
     /// );
-    /// model.save("tests/save_that_model.aml");
-    /// # std::fs::remove_file("tests/save_that_model.aml");
+    /// model.train();
+    /// match model.try_predict_from_ipc("data/diabetes_without_target.arrow") {
+    ///     Ok(predictions) => println!("{:?}", predictions),
+    ///     Err(error) => println!("{}", error),
+    /// }
     /// ```
-    pub fn save(&self, file_name: &str) {
-        let serial = bincode::serialize(&self).expect("Cannot serialize model.");
-        std::fs::File::create(file_name)
-            .and_then(|mut f| f.write_all(&serial))
-            .expect("Cannot write model to file.");
+    pub fn try_predict_from_ipc(&self, filepath: &str) -> Result<Vec<f32>, SchemaError> {
+        let file = std::fs::File::open(filepath).map_err(Self::unreadable(filepath))?;
+        let df = IpcReader::new(file)
+            .finish()
+            .map_err(Self::unreadable(filepath))?;
+        self.try_predict_from_polars(&df, true)
     }
 }
 
-#[cfg_attr(docsrs, doc(cfg(feature = "csv")))]
-#[cfg(any(feature = "csv"))]
+#[cfg_attr(docsrs, doc(cfg(feature = "json")))]
+#[cfg(any(feature = "json"))]
 impl SupervisedModel {
-    /// Create a new supervised model from a csv
-    /// ```
+    /// Create a new supervised model from a JSON Lines file, using the column named `target` as the
+    /// target and every other column as a feature
+    /// ```no_run
     /// # use automl::{SupervisedModel, Settings};
-    /// let model = SupervisedModel::new_from_csv(
-    ///     "data/diabetes.csv",
-    ///     10,
-    ///     true,
+    /// let model = SupervisedModel::new_from_json(
+    ///     "data/diabetes.jsonl",
+    ///     "y",
     ///     Settings::default_regression()
     /// );
     /// ```
-    pub fn new_from_csv(
-        filepath: &str,
-        target_index: usize,
-        header: bool,
-        settings: Settings,
-    ) -> Self {
-        let df = CsvReader::from_path(filepath)
-            .expect("Cannot find file")
-            .infer_schema(None)
-            .has_header(header)
+    pub fn new_from_json(filepath: &str, target: &str, settings: Settings) -> Self {
+        let df = JsonReader::new(std::fs::File::open(filepath).expect("Cannot find file"))
             .finish()
-            .expect("Cannot read file as CSV");
-
-        // Get target variables
-        let target_column_name = df.get_column_names()[target_index];
-        let series = df.column(target_column_name).unwrap().clone();
-        let target_df = DataFrame::new(vec![series]).unwrap();
-        let ndarray = target_df.to_ndarray::<Float32Type>().unwrap();
-        let y = ndarray.into_raw_vec();
-
-        // Get the rest of the data
-        let features = df.drop(target_column_name).unwrap();
-        let (height, width) = features.shape();
-        let ndarray = features.to_ndarray::<Float32Type>().unwrap();
-        let x = DenseMatrix::from_array(height, width, ndarray.as_slice().unwrap());
-
-        SupervisedModel::new(x, y, settings)
+            .expect("Cannot read file as JSON Lines");
+        SupervisedModel::new_from_dataframe(df, target, settings)
     }
 
-    /// Create a new supervised model from a csv
+    /// Predict values from a JSON Lines file
     /// ```no_run
     /// # use automl::{SupervisedModel, Settings};
-    /// let mut model = SupervisedModel::new_from_csv(
-    ///     "data/diabetes.csv",
-    ///     10,
-    ///     true,
+    /// let mut model = SupervisedModel::new_from_json(
+    ///     "data/diabetes.jsonl",
+    ///     "y",
     ///     Settings::default_regression()
     /// );
-    /// model.predict_from_csv(
-    ///     "data/diabetes_without_target.csv",
-    ///     true
+    /// model.train();
+    /// model.predict_from_json("data/diabetes_without_target.jsonl");
+    /// ```
+    pub fn predict_from_json(&self, filepath: &str) -> Vec<f32> {
+        self.try_predict_from_json(filepath)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Predict values from a JSON Lines file, returning an error if it cannot be read or if its
+    /// columns do not match the schema
+    /// ```no_run
+    /// # use automl::{SupervisedModel, Settings};
+    /// let mut model = SupervisedModel::new_from_json(
+    ///     "data/diabetes.jsonl",
+    ///     "y",
+    ///     Settings::default_regression()
     /// );
+    /// model.train();
+    /// match model.try_predict_from_json("data/diabetes_without_target.jsonl") {
+    ///     Ok(predictions) => println!("{:?}", predictions),
+    ///     Err(error) => println!("{}", error),
+    /// }
     /// ```
-    pub fn predict_from_csv(&mut self, filepath: &str, header: bool) -> Vec<f32> {
-        let df = CsvReader::from_path(filepath)
-            .unwrap()
-            .infer_schema(None)
-            .has_header(header)
+    pub fn try_predict_from_json(&self, filepath: &str) -> Result<Vec<f32>, SchemaError> {
+        let file = std::fs::File::open(filepath).map_err(Self::unreadable(filepath))?;
+        let df = JsonReader::new(file)
             .finish()
-            .unwrap();
-
-        // Get the rest of the data
-        let (height, width) = df.shape();
-        let ndarray = df.to_ndarray::<Float32Type>().unwrap();
-        let x = DenseMatrix::from_array(height, width, ndarray.as_slice().unwrap());
-
-        // Predict
-        self.predict(&x)
+            .map_err(Self::unreadable(filepath))?;
+        self.try_predict_from_polars(&df, true)
     }
 }
 
@@ -457,6 +1563,28 @@ impl SupervisedModel {
         )
     }
 
+    /// Create a new supervised model using ndarray data with a name for each feature
+    /// ```
+    /// # use automl::{SupervisedModel, Settings};
+    /// use ndarray::{arr1, arr2};
+    /// let model = SupervisedModel::new_from_ndarray_with_names(
+    ///     arr2(&[[1.0, 2.0], [3.0, 4.0]]),
+    ///     arr1(&[1.0, 2.0]),
+    ///     &["age", "income"],
+    ///     automl::Settings::default_regression(),
+    /// );
+    /// ```
+    pub fn new_from_ndarray_with_names(
+        x: Array2<f32>,
+        y: Array1<f32>,
+        feature_names: &[&str],
+        settings: Settings,
+    ) -> Self {
+        let mut model = SupervisedModel::new_from_ndarray(x, y, settings);
+        model.set_feature_names(feature_names);
+        model
+    }
+
     /// Predict values using the final model based on ndarray.
     /// ```no_run
     /// # use automl::{SupervisedModel, Settings};
@@ -473,12 +1601,30 @@ impl SupervisedModel {
     ///     ])
     /// );
     /// ```
-    pub fn predict_from_ndarray(&mut self, x: Array2<f32>) -> Vec<f32> {
-        self.predict(&DenseMatrix::from_array(
+    pub fn predict_from_ndarray(&self, x: Array2<f32>) -> Vec<f32> {
+        self.try_predict_from_ndarray(x)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Predict values using the final model based on ndarray, returning an error if the array
+    /// does not have one column per feature
+    /// ```
+    /// # use automl::{SupervisedModel, Settings, settings::Algorithm};
+    /// use ndarray::arr2;
+    /// let mut model = SupervisedModel::new_from_dataset(
+    ///     smartcore::dataset::diabetes::load_dataset(),
+    ///     Settings::default_regression().only(Algorithm::Linear)
+    /// );
+    /// model.train();
+    /// assert!(model.try_predict_from_ndarray(arr2(&[[1.0, 2.0]])).is_err());
+    /// ```
+    pub fn try_predict_from_ndarray(&self, x: Array2<f32>) -> Result<Vec<f32>, SchemaError> {
+        self.schema.check_width(x.shape()[1])?;
+        Ok(self.predict(&DenseMatrix::from_array(
             x.shape()[0],
             x.shape()[1],
             x.as_slice().unwrap(),
-        ))
+        )))
     }
 }
 
@@ -502,24 +1648,106 @@ impl SupervisedModel {
     }
 }
 
+/// Private functions shared by the readers built on polars
+#[cfg(any(feature = "dataframe"))]
+impl SupervisedModel {
+    fn try_predict_from_polars(
+        &self,
+        df: &DataFrame,
+        named: bool,
+    ) -> Result<Vec<f32>, SchemaError> {
+        let names: Vec<String> = df
+            .get_column_names()
+            .iter()
+            .map(|name| name.to_string())
+            .collect();
+        self.schema.check_columns(
+            if named { Some(names.as_slice()) } else { None },
+            &Self::feature_types(df),
+        )?;
+        Ok(self.predict(&Self::polars_to_matrix(df)?))
+    }
+
+    fn polars_to_matrix(df: &DataFrame) -> Result<DenseMatrix<f32>, SchemaError> {
+        // Cast explicitly so that booleans become 0 or 1
+        let columns = df
+            .get_columns()
+            .iter()
+            .map(|series| {
+                if series.null_count() > 0 {
+                    return Err(SchemaError::MissingValues {
+                        column: series.name().to_string(),
+                    });
+                }
+                Ok(series
+                    .cast(&DataType::Float32)
+                    .unwrap_or_else(|_| panic!("Cannot cast column {} to f32.", series.name())))
+            })
+            .collect::<Result<Vec<Series>, SchemaError>>()?;
+        let df = DataFrame::new(columns).unwrap();
+        let (height, width) = df.shape();
+        let ndarray = df.to_ndarray::<Float32Type>().unwrap();
+        Ok(DenseMatrix::from_array(
+            height,
+            width,
+            ndarray.as_slice().unwrap(),
+        ))
+    }
+
+    fn feature_types(df: &DataFrame) -> Vec<FeatureType> {
+        df.dtypes().iter().map(dataframe::feature_type).collect()
+    }
+
+    /// Turn a failure to open or parse a file into a [`SchemaError`]
+    #[cfg(any(
+        feature = "csv",
+        feature = "parquet",
+        feature = "ipc",
+        feature = "json"
+    ))]
+    fn unreadable<E: std::fmt::Display>(filepath: &str) -> impl Fn(E) -> SchemaError + '_ {
+        move |error| SchemaError::Unreadable {
+            file: filepath.to_string(),
+            reason: error.to_string(),
+        }
+    }
+}
+
 /// Private functions go here
 impl SupervisedModel {
     fn new(x: DenseMatrix<f32>, y: Vec<f32>, settings: Settings) -> Self {
         Self {
             settings,
             x_train: x.clone(),
+            x_raw: x.clone(),
             y_train: y.clone(),
             x_val: DenseMatrix::new(0, 0, vec![]),
             y_val: vec![],
+            schema: Schema::anonymous(x.shape().1),
             number_of_classes: Self::count_classes(&y),
             comparison: vec![],
             #[cfg(any(feature = "gui"))]
             current_x: vec![0.0; x.shape().1],
             preprocessing: (None, None),
             metamodel: Default::default(),
+            csv_options: CsvOptions::default(),
+            observers: vec![],
+            cancellation_token: None,
         }
     }
 
+    /// Replace the generated feature names, one for each column
+    fn set_feature_names(&mut self, feature_names: &[&str]) {
+        assert_eq!(
+            feature_names.len(),
+            self.schema.len(),
+            "Expected {} feature names but got {}.",
+            self.schema.len(),
+            feature_names.len()
+        );
+        self.schema = Schema::named(feature_names.iter().map(|name| name.to_string()).collect());
+    }
+
     fn train_blended_model(
         &mut self,
         algo: Algorithm,
@@ -528,7 +1756,7 @@ impl SupervisedModel {
     ) {
         // Make the data
         let mut meta_x: Vec<Vec<f32>> = Vec::new();
-        for model in &self.comparison {
+        for model in self.completed_models() {
             meta_x.push(self.predict_by_model(&self.x_val, model))
         }
         let xdm = DenseMatrix::from_2d_vec(&meta_x).transpose();
@@ -565,14 +1793,16 @@ impl SupervisedModel {
             name: algo,
             duration: Default::default(),
             model,
+            status: Status::Completed,
+            interval_models: vec![],
         };
     }
 
-    fn predict_blended_model(&mut self, x: &DenseMatrix<f32>, algo: Algorithm) -> Vec<f32> {
+    /// Predict with the metamodel from the predictions of every completed model
+    fn predict_blended_model(&self, x: &DenseMatrix<f32>, algo: Algorithm) -> Vec<f32> {
         // Make the data
         let mut meta_x: Vec<Vec<f32>> = Vec::new();
-        for i in 0..self.comparison.len() {
-            let model = &self.comparison[i];
+        for model in self.completed_models() {
             meta_x.push(self.predict_by_model(&x, model))
         }
 
@@ -584,6 +1814,179 @@ impl SupervisedModel {
         (*algo.get_predictor())(&xdm, metamodel, &self.settings)
     }
 
+    /// The algorithms to compare, leaving out skipped ones, ones without settings and ones that
+    /// cannot work on the preprocessed features
+    fn candidate_algorithms(&self) -> Vec<Algorithm> {
+        let preprocessing = std::mem::discriminant(&self.settings.preprocessing);
+        let replaces_features = preprocessing
+            == std::mem::discriminant(&PreProcessing::ReplaceWithPCA {
+                number_of_components: 1,
+            })
+            || preprocessing
+                == std::mem::discriminant(&PreProcessing::ReplaceWithSVD {
+                    number_of_components: 1,
+                });
+
+        vec![
+            Algorithm::LogisticRegression,
+            Algorithm::RandomForestClassifier,
+            Algorithm::KNNClassifier,
+            Algorithm::DecisionTreeClassifier,
+            Algorithm::GaussianNaiveBayes,
+            Algorithm::CategoricalNaiveBayes,
+            Algorithm::SVC,
+            Algorithm::Linear,
+            Algorithm::SVR,
+            Algorithm::Ridge,
+            Algorithm::Lasso,
+            Algorithm::ElasticNet,
+            Algorithm::DecisionTreeRegressor,
+            Algorithm::RandomForestRegressor,
+            Algorithm::KNNRegressor,
+            Algorithm::GradientBoostingRegressor,
+            Algorithm::GradientBoostingClassifier,
+            Algorithm::ExtraTreesRegressor,
+            Algorithm::ExtraTreesClassifier,
+            Algorithm::MLPRegressor,
+            Algorithm::MLPClassifier,
+            Algorithm::PoissonRegressor,
+            Algorithm::GammaRegressor,
+            Algorithm::TweedieRegressor,
+            Algorithm::QuantileRegressor,
+            Algorithm::GradientBoostingQuantileRegressor,
+        ]
+        .into_iter()
+        .filter(|algorithm| !self.settings.skiplist.contains(algorithm))
+        .filter(|algorithm| match algorithm {
+            Algorithm::CategoricalNaiveBayes => !replaces_features,
+            // Settings written before gradient boosting, extra trees and multilayer perceptrons
+            // existed do not skip them, but have no parameters for them either
+            Algorithm::GradientBoostingRegressor => {
+                self.settings.gradient_boosting_regressor_settings.is_some()
+            }
+            Algorithm::GradientBoostingClassifier => self
+                .settings
+                .gradient_boosting_classifier_settings
+                .is_some(),
+            Algorithm::ExtraTreesRegressor => {
+                self.settings.extra_trees_regressor_settings.is_some()
+            }
+            Algorithm::ExtraTreesClassifier => {
+                self.settings.extra_trees_classifier_settings.is_some()
+            }
+            Algorithm::MLPRegressor => self.settings.mlp_regressor_settings.is_some(),
+            Algorithm::MLPClassifier => self.settings.mlp_classifier_settings.is_some(),
+            Algorithm::PoissonRegressor => self.settings.poisson_regressor_settings.is_some(),
+            Algorithm::GammaRegressor => self.settings.gamma_regressor_settings.is_some(),
+            Algorithm::TweedieRegressor => self.settings.tweedie_regressor_settings.is_some(),
+            Algorithm::QuantileRegressor => self.settings.quantile_regressor_settings.is_some(),
+            Algorithm::GradientBoostingQuantileRegressor => self
+                .settings
+                .gradient_boosting_quantile_regressor_settings
+                .is_some(),
+            _ => true,
+        })
+        .collect()
+    }
+
+    /// Check that an algorithm can be fit to the training targets, explaining why not. Generalized
+    /// linear models are only compared on targets their family allows.
+    fn check_targets(&self, algorithm: Algorithm) -> Result<(), String> {
+        let y = &self.y_train;
+        match algorithm {
+            Algorithm::PoissonRegressor => check_family(1.0, y),
+            Algorithm::GammaRegressor => check_family(2.0, y),
+            Algorithm::TweedieRegressor => match &self.settings.tweedie_regressor_settings {
+                Some(parameters) => check_family(parameters.power, y),
+                None => Ok(()),
+            },
+            _ => Ok(()),
+        }
+    }
+
+    /// Cross-validate one algorithm on the training data
+    fn cv_by_algorithm(&self, algorithm: Algorithm, monitor: &mut Monitor) -> Model {
+        let (x, y, settings) = (&self.x_train, &self.y_train, &self.settings);
+        match algorithm {
+            Algorithm::Linear => {
+                LinearRegressorWrapper::cv_model(algorithm, x, y, settings, monitor)
+            }
+            Algorithm::Lasso => LassoRegressorWrapper::cv_model(algorithm, x, y, settings, monitor),
+            Algorithm::Ridge => RidgeRegressorWrapper::cv_model(algorithm, x, y, settings, monitor),
+            Algorithm::ElasticNet => {
+                ElasticNetRegressorWrapper::cv_model(algorithm, x, y, settings, monitor)
+            }
+            Algorithm::RandomForestRegressor => {
+                RandomForestRegressorWrapper::cv_model(algorithm, x, y, settings, monitor)
+            }
+            Algorithm::KNNRegressor => {
+                KNNRegressorWrapper::cv_model(algorithm, x, y, settings, monitor)
+            }
+            Algorithm::SVR => {
+                SupportVectorRegressorWrapper::cv_model(algorithm, x, y, settings, monitor)
+            }
+            Algorithm::DecisionTreeRegressor => {
+                DecisionTreeRegressorWrapper::cv_model(algorithm, x, y, settings, monitor)
+            }
+            Algorithm::LogisticRegression => {
+                LogisticRegressionWrapper::cv_model(algorithm, x, y, settings, monitor)
+            }
+            Algorithm::RandomForestClassifier => {
+                RandomForestClassifierWrapper::cv_model(algorithm, x, y, settings, monitor)
+            }
+            Algorithm::DecisionTreeClassifier => {
+                DecisionTreeClassifierWrapper::cv_model(algorithm, x, y, settings, monitor)
+            }
+            Algorithm::KNNClassifier => {
+                KNNClassifierWrapper::cv_model(algorithm, x, y, settings, monitor)
+            }
+            Algorithm::SVC => {
+                SupportVectorClassifierWrapper::cv_model(algorithm, x, y, settings, monitor)
+            }
+            Algorithm::GaussianNaiveBayes => {
+                GaussianNaiveBayesClassifierWrapper::cv_model(algorithm, x, y, settings, monitor)
+            }
+            Algorithm::CategoricalNaiveBayes => {
+                CategoricalNaiveBayesClassifierWrapper::cv_model(algorithm, x, y, settings, monitor)
+            }
+            Algorithm::GradientBoostingRegressor => {
+                GradientBoostingRegressorWrapper::cv_model(algorithm, x, y, settings, monitor)
+            }
+            Algorithm::GradientBoostingClassifier => {
+                GradientBoostingClassifierWrapper::cv_model(algorithm, x, y, settings, monitor)
+            }
+            Algorithm::ExtraTreesRegressor => {
+                ExtraTreesRegressorWrapper::cv_model(algorithm, x, y, settings, monitor)
+            }
+            Algorithm::ExtraTreesClassifier => {
+                ExtraTreesClassifierWrapper::cv_model(algorithm, x, y, settings, monitor)
+            }
+            Algorithm::MLPRegressor => {
+                MLPRegressorWrapper::cv_model(algorithm, x, y, settings, monitor)
+            }
+            Algorithm::MLPClassifier => {
+                MLPClassifierWrapper::cv_model(algorithm, x, y, settings, monitor)
+            }
+            Algorithm::PoissonRegressor => {
+                PoissonRegressorWrapper::cv_model(algorithm, x, y, settings, monitor)
+            }
+            Algorithm::GammaRegressor => {
+                GammaRegressorWrapper::cv_model(algorithm, x, y, settings, monitor)
+            }
+            Algorithm::TweedieRegressor => {
+                TweedieRegressorWrapper::cv_model(algorithm, x, y, settings, monitor)
+            }
+            Algorithm::QuantileRegressor => {
+                QuantileRegressorWrapper::cv_model(algorithm, x, y, settings, monitor)
+            }
+            Algorithm::GradientBoostingQuantileRegressor => {
+                GradientBoostingQuantileRegressorWrapper::cv_model(
+                    algorithm, x, y, settings, monitor,
+                )
+            }
+        }
+    }
+
     fn predict_by_model(&self, x: &DenseMatrix<f32>, model: &Model) -> Vec<f32> {
         let saved_model = &model.model;
         match model.name {
@@ -624,10 +2027,46 @@ impl SupervisedModel {
             Algorithm::CategoricalNaiveBayes => {
                 CategoricalNaiveBayesClassifierWrapper::predict(x, saved_model, &self.settings)
             }
+            Algorithm::GradientBoostingRegressor => {
+                GradientBoostingRegressorWrapper::predict(x, saved_model, &self.settings)
+            }
+            Algorithm::GradientBoostingClassifier => {
+                GradientBoostingClassifierWrapper::predict(x, saved_model, &self.settings)
+            }
+            Algorithm::ExtraTreesRegressor => {
+                ExtraTreesRegressorWrapper::predict(x, saved_model, &self.settings)
+            }
+            Algorithm::ExtraTreesClassifier => {
+                ExtraTreesClassifierWrapper::predict(x, saved_model, &self.settings)
+            }
+            Algorithm::MLPRegressor => MLPRegressorWrapper::predict(x, saved_model, &self.settings),
+            Algorithm::MLPClassifier => {
+                MLPClassifierWrapper::predict(x, saved_model, &self.settings)
+            }
+            Algorithm::PoissonRegressor => {
+                PoissonRegressorWrapper::predict(x, saved_model, &self.settings)
+            }
+            Algorithm::GammaRegressor => {
+                GammaRegressorWrapper::predict(x, saved_model, &self.settings)
+            }
+            Algorithm::TweedieRegressor => {
+                TweedieRegressorWrapper::predict(x, saved_model, &self.settings)
+            }
+            Algorithm::QuantileRegressor => {
+                QuantileRegressorWrapper::predict(x, saved_model, &self.settings)
+            }
+            Algorithm::GradientBoostingQuantileRegressor => {
+                GradientBoostingQuantileRegressorWrapper::predict(x, saved_model, &self.settings)
+            }
         }
     }
 
-    fn predict(&mut self, x: &DenseMatrix<f32>) -> Vec<f32> {
+    /// Preprocess raw features and predict with the final model
+    fn predict(&self, x: &DenseMatrix<f32>) -> Vec<f32> {
+        assert!(
+            self.completed_models().next().is_some(),
+            "No algorithm finished cross-validation, so there is no final model."
+        );
         let x = &self.preprocess(x.clone());
         match self.settings.final_model_approach {
             FinalModel::None => panic!(""),
@@ -636,6 +2075,168 @@ impl SupervisedModel {
         }
     }
 
+    /// Predicts class probabilities with the final model, if it is a single model that can
+    fn probability_predictor(
+        &self,
+    ) -> Option<impl Fn(&DenseMatrix<f32>) -> Option<ClassProbabilities> + '_> {
+        let model = match self.settings.final_model_approach {
+            FinalModel::Best => self.completed_models().next()?,
+            FinalModel::None | FinalModel::Blending { .. } => return None,
+        };
+        let predict_proba = model.name.get_probability_predictor()?;
+        Some(move |x: &DenseMatrix<f32>| predict_proba(&self.preprocess(x.clone()), &model.model))
+    }
+
+    /// How much shuffling each feature in turn worsens the score, over a number of repeats
+    fn permutation_importance(
+        &self,
+        x: &DenseMatrix<f32>,
+        y: &Vec<f32>,
+        repeats: usize,
+    ) -> Vec<FeatureImportance> {
+        let (_, width) = x.shape();
+        assert_eq!(
+            width,
+            self.schema.len(),
+            "Expected {} features but the data has {}.",
+            self.schema.len(),
+            width
+        );
+
+        // Error metrics improve as they fall, so flip them to make larger drops more important
+        let metric = self.settings.get_metric();
+        let orientation = match self.settings.sort_by {
+            Metric::RSquared | Metric::Accuracy => 1.0,
+            _ => -1.0,
+        };
+        let baseline = (*metric)(y, &self.predict(x));
+
+        let mut rng = rand::thread_rng();
+        let mut importances = Vec::with_capacity(width);
+        for column in 0..width {
+            let mut drops = Vec::with_capacity(repeats);
+            for _ in 0..repeats {
+                let mut values = x.get_col_as_vec(column);
+                values.shuffle(&mut rng);
+                let mut shuffled = x.clone();
+                for (row, value) in values.into_iter().enumerate() {
+                    shuffled.set(row, column, value);
+                }
+                let score = (*metric)(y, &self.predict(&shuffled));
+                drops.push(orientation * (baseline - score));
+            }
+            importances.push(FeatureImportance::new(
+                self.schema.names()[column].clone(),
+                &drops,
+            ));
+        }
+        importances
+    }
+
+    /// Predict for every training row with some features fixed to the given values
+    fn predict_with_features_set(&self, values: &[(usize, f32)]) -> Vec<f32> {
+        let mut x = self.x_raw.clone();
+        let (height, _) = x.shape();
+        for (feature, value) in values {
+            for row in 0..height {
+                x.set(row, *feature, *value);
+            }
+        }
+        self.predict(&x)
+    }
+
+    /// Exact SHAP values, if the final model is a tree model on the raw features
+    fn tree_shap(&self, x: &DenseMatrix<f32>) -> Option<Shap> {
+        // Trees see the raw features only when there is no preprocessing
+        if !matches!(self.settings.final_model_approach, FinalModel::Best)
+            || !matches!(self.settings.preprocessing, PreProcessing::None)
+        {
+            return None;
+        }
+        let model = self.completed_models().next()?;
+        let background = &self.x_train;
+        match model.name {
+            Algorithm::DecisionTreeRegressor => {
+                DecisionTreeRegressorWrapper::tree_shap(background, x, &model.model)
+            }
+            Algorithm::RandomForestRegressor => {
+                RandomForestRegressorWrapper::tree_shap(background, x, &model.model)
+            }
+            Algorithm::DecisionTreeClassifier => {
+                DecisionTreeClassifierWrapper::tree_shap(background, x, &model.model)
+            }
+            Algorithm::RandomForestClassifier => {
+                RandomForestClassifierWrapper::tree_shap(background, x, &model.model)
+            }
+            Algorithm::ExtraTreesRegressor => {
+                ExtraTreesRegressorWrapper::tree_shap(background, x, &model.model)
+            }
+            Algorithm::ExtraTreesClassifier => {
+                ExtraTreesClassifierWrapper::tree_shap(background, x, &model.model)
+            }
+            _ => None,
+        }
+    }
+
+    /// SHAP values estimated by KernelSHAP against a sample of the training rows
+    fn kernel_shap(&self, x: &DenseMatrix<f32>) -> Shap {
+        // Take an evenly spaced background sample from the training data
+        let (number_of_rows, width) = self.x_raw.shape();
+        let step = (number_of_rows / explanation::BACKGROUND_SIZE).max(1);
+        let background_rows: Vec<usize> = (0..number_of_rows)
+            .step_by(step)
+            .take(explanation::BACKGROUND_SIZE)
+            .collect();
+        let background = self.x_raw.take(&background_rows, 0);
+        let base_value =
+            self.predict(&background).iter().sum::<f32>() as f64 / background_rows.len() as f64;
+
+        let coalitions = explanation::kernel_coalitions(width);
+        let predictions = self.predict(x);
+        let (height, _) = x.shape();
+        let mut contributions = Vec::with_capacity(height);
+        for (row, prediction) in predictions.into_iter().enumerate() {
+            // Fill in the unknown features of each coalition from every background row
+            let mut samples = Vec::with_capacity(coalitions.len() * background_rows.len() * width);
+            for (coalition, _) in &coalitions {
+                for sample in 0..background_rows.len() {
+                    for (column, known) in coalition.iter().enumerate() {
+                        samples.push(if *known {
+                            x.get(row, column)
+                        } else {
+                            background.get(sample, column)
+                        });
+                    }
+                }
+            }
+            let values: Vec<f64> = if coalitions.is_empty() {
+                vec![]
+            } else {
+                self.predict(&DenseMatrix::from_array(
+                    coalitions.len() * background_rows.len(),
+                    width,
+                    &samples,
+                ))
+                .chunks(background_rows.len())
+                .map(|chunk| chunk.iter().sum::<f32>() as f64 / chunk.len() as f64)
+                .collect()
+            };
+            contributions.push(explanation::kernel_shap(
+                &coalitions,
+                &values,
+                base_value,
+                prediction as f64,
+            ));
+        }
+        (base_value as f32, contributions)
+    }
+
+    /// The completed model of an algorithm, if it was trained on all of the training data
+    fn trained_model(&self, algorithm: Algorithm) -> Option<&Model> {
+        self.completed_models()
+            .find(|model| model.name == algorithm && !model.model.is_empty())
+    }
+
     fn interaction_features(mut x: DenseMatrix<f32>) -> DenseMatrix<f32> {
         let (_, width) = x.shape();
         for i in 0..width {
@@ -664,51 +2265,65 @@ impl SupervisedModel {
         x
     }
 
-    fn pca_features(&mut self, x: DenseMatrix<f32>, n: usize) -> DenseMatrix<f32> {
-        if let None = self.preprocessing.0 {
-            let pca = PCA::fit(
-                &x,
-                PCAParameters::default()
-                    .with_n_components(n)
-                    .with_use_correlation_matrix(true),
-            )
-            .unwrap();
-            self.preprocessing.0 = Some(pca);
-        }
-        self.preprocessing
-            .0
-            .as_ref()
-            .unwrap()
-            .transform(&x)
-            .unwrap()
+    /// Fit the PCA or SVD the settings ask for to the training data
+    fn fit_preprocessing(&mut self) {
+        self.preprocessing = match self.settings.preprocessing {
+            PreProcessing::ReplaceWithPCA {
+                number_of_components,
+            } => {
+                let pca = PCA::fit(
+                    &self.x_train,
+                    PCAParameters::default()
+                        .with_n_components(number_of_components)
+                        .with_use_correlation_matrix(true),
+                )
+                .unwrap();
+                (Some(pca), None)
+            }
+            PreProcessing::ReplaceWithSVD {
+                number_of_components,
+            } => {
+                let svd = SVD::fit(
+                    &self.x_train,
+                    SVDParameters::default().with_n_components(number_of_components),
+                )
+                .unwrap();
+                (None, Some(svd))
+            }
+            _ => (None, None),
+        };
     }
 
-    fn svd_features(&mut self, x: DenseMatrix<f32>, n: usize) -> DenseMatrix<f32> {
-        if let None = self.preprocessing.1 {
-            let svd = SVD::fit(&x, SVDParameters::default().with_n_components(n)).unwrap();
-            self.preprocessing.1 = Some(svd);
-        }
-        self.preprocessing
-            .1
-            .as_ref()
-            .unwrap()
-            .transform(&x)
-            .unwrap()
+    /// Apply the fitted preprocessing of this model
+    fn preprocess(&self, x: DenseMatrix<f32>) -> DenseMatrix<f32> {
+        Self::transform(x, &self.settings.preprocessing, &self.preprocessing)
     }
 
-    fn preprocess(&mut self, x: DenseMatrix<f32>) -> DenseMatrix<f32> {
-        match self.settings.preprocessing {
+    /// Apply preprocessing that has already been fitted, so that the same code serves training,
+    /// prediction and exported predictors
+    fn transform(
+        x: DenseMatrix<f32>,
+        preprocessing: &PreProcessing,
+        fitted: &FittedPreprocessing,
+    ) -> DenseMatrix<f32> {
+        match *preprocessing {
             PreProcessing::None => x,
             PreProcessing::AddInteractions => SupervisedModel::interaction_features(x),
             PreProcessing::AddPolynomial { order } => {
                 SupervisedModel::polynomial_features(x, order)
             }
-            PreProcessing::ReplaceWithPCA {
-                number_of_components,
-            } => self.pca_features(x, number_of_components),
-            PreProcessing::ReplaceWithSVD {
-                number_of_components,
-            } => self.svd_features(x, number_of_components),
+            PreProcessing::ReplaceWithPCA { .. } => fitted
+                .0
+                .as_ref()
+                .expect("PCA must be fitted before it is applied.")
+                .transform(&x)
+                .unwrap(),
+            PreProcessing::ReplaceWithSVD { .. } => fitted
+                .1
+                .as_ref()
+                .expect("SVD must be fitted before it is applied.")
+                .transform(&x)
+                .unwrap(),
         }
     }
 
@@ -719,16 +2334,122 @@ impl SupervisedModel {
         sorted_targets.len()
     }
 
-    fn record_model(&mut self, model: (CrossValidationResult<f32>, Algorithm, Duration, Vec<u8>)) {
-        self.comparison.push(Model {
-            score: model.0,
-            name: model.1,
-            duration: model.2,
-            model: model.3,
-        });
+    /// Upgrade a serialized model from format version 6 by tagging its SVC models
+    pub(crate) fn tag_svc_models(payload: Vec<u8>) -> Result<Vec<u8>, bincode::Error> {
+        let settings_end = settings::end_of_settings_v6(&payload)?;
+        Self::rewrite_models(payload, settings_end, |mut comparison, mut metamodel| {
+            for model in comparison.iter_mut().chain(std::iter::once(&mut metamodel)) {
+                algorithms::tag_svc(model.name, &mut model.model)?;
+            }
+            bincode::serialize(&(comparison, metamodel))
+        })
+    }
+
+    /// Upgrade a serialized model from format version 8 by giving every model empty interval
+    /// models, since intervals used to be refit when they were predicted
+    pub(crate) fn add_interval_models(payload: Vec<u8>) -> Result<Vec<u8>, bincode::Error> {
+        let settings_end = settings::end_of_settings_v8(&payload)?;
+        Self::rewrite_models(payload, settings_end, |comparison, metamodel| {
+            let comparison: Vec<Model> = comparison.into_iter().map(Model::from).collect();
+            bincode::serialize(&(comparison, Model::from(metamodel)))
+        })
+    }
+
+    /// Replace the comparison and metamodel of a serialized model from before format version 9,
+    /// whose settings end at `settings_end`, with what `rewrite` serializes in their place
+    fn rewrite_models(
+        payload: Vec<u8>,
+        settings_end: usize,
+        rewrite: impl FnOnce(Vec<ModelV8>, ModelV8) -> Result<Vec<u8>, bincode::Error>,
+    ) -> Result<Vec<u8>, bincode::Error> {
+        // The data, schema and number of classes after the settings are kept as they are
+        let mut rest = &payload[settings_end..];
+        bincode::deserialize_from::<
+            _,
+            (
+                DenseMatrix<f32>,
+                DenseMatrix<f32>,
+                Vec<f32>,
+                DenseMatrix<f32>,
+                Vec<f32>,
+                Schema,
+                usize,
+            ),
+        >(&mut rest)?;
+        let start = payload.len() - rest.len();
+        let (comparison, metamodel): (Vec<ModelV8>, ModelV8) =
+            bincode::deserialize_from(&mut rest)?;
+        let end = payload.len() - rest.len();
+
+        let mut upgraded = payload[..start].to_vec();
+        upgraded.extend(rewrite(comparison, metamodel)?);
+        upgraded.extend(&payload[end..]);
+        Ok(upgraded)
+    }
+
+    /// Train the models behind prediction intervals for the model that predicts them, which is the
+    /// best model if it can predict quantiles and otherwise the best quantile regressor
+    fn train_interval_models(&mut self) {
+        let predicts_quantiles = |model: &Model, quantile_loss_only: bool| {
+            model.status == Status::Completed
+                && !model.model.is_empty()
+                && match model.name {
+                    Algorithm::QuantileRegressor | Algorithm::GradientBoostingQuantileRegressor => {
+                        true
+                    }
+                    Algorithm::RandomForestRegressor => !quantile_loss_only,
+                    _ => false,
+                }
+        };
+        let best = self
+            .comparison
+            .iter()
+            .position(|model| model.status == Status::Completed)
+            .filter(|best| predicts_quantiles(&self.comparison[*best], false));
+        let index = match best.or_else(|| {
+            self.comparison
+                .iter()
+                .position(|model| predicts_quantiles(model, true))
+        }) {
+            Some(index) => index,
+            None => return,
+        };
+
+        let (x, y, settings) = (&self.x_train, &self.y_train, &self.settings);
+        let model = &mut self.comparison[index];
+        let interval_models = match model.name {
+            Algorithm::QuantileRegressor => {
+                QuantileRegressorWrapper::train_quantiles(x, y, &model.model, settings)
+            }
+            Algorithm::GradientBoostingQuantileRegressor => {
+                GradientBoostingQuantileRegressorWrapper::train_quantiles(
+                    x,
+                    y,
+                    &model.model,
+                    settings,
+                )
+            }
+            Algorithm::RandomForestRegressor => {
+                RandomForestRegressorWrapper::train_quantiles(x, y, &model.model, settings)
+            }
+            _ => None,
+        };
+        model.interval_models = interval_models.unwrap_or_default();
+    }
+
+    /// Add a cross-validated model to the comparison, keeping it sorted
+    fn record_model(&mut self, model: Model) {
+        self.comparison.push(model);
         self.sort();
     }
 
+    /// The models that finished cross-validation, best first
+    fn completed_models(&self) -> impl Iterator<Item = &Model> {
+        self.comparison
+            .iter()
+            .filter(|model| model.status == Status::Completed)
+    }
+
     fn sort(&mut self) {
         self.comparison.sort_by(|a, b| {
             a.score
@@ -739,6 +2460,10 @@ impl SupervisedModel {
         if self.settings.sort_by == Metric::RSquared || self.settings.sort_by == Metric::Accuracy {
             self.comparison.reverse();
         }
+
+        // Keep algorithms that did not finish cross-validation at the bottom
+        self.comparison
+            .sort_by_key(|model| model.status != Status::Completed);
     }
 }
 
@@ -757,11 +2482,17 @@ impl Display for SupervisedModel {
         ]);
         for model in &self.comparison {
             let mut row_vec = vec![];
-            row_vec.push(format!("{}", &model.name));
+            match &model.status {
+                Status::Completed => row_vec.push(format!("{}", &model.name)),
+                status => row_vec.push(format!("{}\n{}", &model.name, status)),
+            }
             row_vec.push(format!("{}", format_duration(model.duration)));
             let decider =
                 ((model.score.mean_train_score() + model.score.mean_test_score()) / 2.0).abs();
-            if decider > 0.01 && decider < 1000.0 {
+            if let Status::Skipped(_) = model.status {
+                row_vec.push("-".to_string());
+                row_vec.push("-".to_string());
+            } else if decider > 0.01 && decider < 1000.0 {
                 row_vec.push(format!("{:.2}", &model.score.mean_train_score()));
                 row_vec.push(format!("{:.2}", &model.score.mean_test_score()));
             } else {
@@ -825,7 +2556,7 @@ impl epi::App for SupervisedModel {
             for i in 0..self.current_x.len() {
                 // Figure out the maximum in the training dataa
                 let maxx = self
-                    .x_train
+                    .x_raw
                     .get_col_as_vec(i)
                     .iter()
                     .cloned()
@@ -833,7 +2564,7 @@ impl epi::App for SupervisedModel {
 
                 // Figure out the minimum in the training data
                 let minn = self
-                    .x_train
+                    .x_raw
                     .get_col_as_vec(i)
                     .iter()
                     .cloned()
@@ -841,7 +2572,8 @@ impl epi::App for SupervisedModel {
 
                 // Add the slider
                 ui.add(
-                    egui::Slider::new(&mut self.current_x[i], minn..=maxx).text(format!("x_{}", i)),
+                    egui::Slider::new(&mut self.current_x[i], minn..=maxx)
+                        .text(&self.schema.names()[i]),
                 );
             }
         });
@@ -855,11 +2587,20 @@ impl epi::App for SupervisedModel {
 /// This contains the results of a single model
 #[derive(serde::Serialize, serde::Deserialize)]
 struct Model {
+    /// The train and test scores of each fold
     #[serde(with = "CrossValidationResultDef")]
     score: CrossValidationResult<f32>,
+    /// The algorithm
     name: Algorithm,
+    /// How long cross-validation took
     duration: Duration,
+    /// The serialized model trained on all of the training data, if one was kept
     model: Vec<u8>,
+    /// Whether cross-validation finished
+    status: Status,
+    /// The serialized models behind prediction intervals, trained on all of the training data,
+    /// for the model that predicts them
+    interval_models: Vec<u8>,
 }
 
 impl Default for Model {
@@ -872,6 +2613,37 @@ impl Default for Model {
             name: Algorithm::Linear,
             duration: Duration::default(),
             model: vec![],
+            status: Status::Completed,
+            interval_models: vec![],
+        }
+    }
+}
+
+/// The layout of [`Model`] up to format version 8, before it kept interval models
+#[derive(serde::Serialize, serde::Deserialize)]
+struct ModelV8 {
+    /// The train and test scores of each fold
+    #[serde(with = "CrossValidationResultDef")]
+    score: CrossValidationResult<f32>,
+    /// The algorithm
+    name: Algorithm,
+    /// How long cross-validation took
+    duration: Duration,
+    /// The serialized model trained on all of the training data, if one was kept
+    model: Vec<u8>,
+    /// Whether cross-validation finished
+    status: Status,
+}
+
+impl From<ModelV8> for Model {
+    fn from(model: ModelV8) -> Self {
+        Model {
+            score: model.score,
+            name: model.name,
+            duration: model.duration,
+            model: model.model,
+            status: model.status,
+            interval_models: vec![],
         }
     }
 }
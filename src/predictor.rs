@@ -0,0 +1,387 @@
+//! # Deployable predictors
+//! This module contains [`Predictor`], a lightweight copy of a trained
+//! [`SupervisedModel`](crate::SupervisedModel) made by
+//! [`SupervisedModel::export_predictor`](crate::SupervisedModel::export_predictor). It keeps only
+//! what prediction needs: the settings, the feature schema, the fitted preprocessing, the final
+//! model, or for blending the base models and the metamodel, and the CSV options used to parse
+//! files for batch prediction. The training data and the models that were compared but not used
+//! are left behind, so the saved file is small and holds no training records.
+//!
+//! Prediction takes `&self` and a predictor is `Send + Sync`, so it can be shared between threads,
+//! for example behind an `Arc` in a web server.
+//! ```
+//! # use automl::{SupervisedModel, Settings};
+//! use automl::predictor::Predictor;
+//! let mut model = SupervisedModel::new_from_dataset(
+//!     smartcore::dataset::diabetes::load_dataset(),
+//!     Settings::default_regression(),
+//! );
+//! model.train();
+//! model.export_predictor().save("tests/deployed.aml");
+//!
+//! // Later, somewhere else
+//! let predictor = Predictor::new_from_file("tests/deployed.aml");
+//! let y = predictor.predict_from_vec(vec![vec![0.0; 10]; 3]);
+//! # std::fs::remove_file("tests/deployed.aml");
+//! ```
+
+use crate::algorithms::ClassProbabilities;
+use crate::batch::{BatchError, BatchOptions, BatchPredictions};
+use crate::csv_options::CsvOptions;
+use crate::file_format::{self, Contents, LoadError};
+use crate::schema::{Schema, SchemaError};
+use crate::settings::Algorithm;
+use crate::{FittedPreprocessing, Settings, SupervisedModel};
+use smartcore::linalg::{naive::dense_matrix::DenseMatrix, BaseMatrix};
+
+#[cfg(any(feature = "nd"))]
+use ndarray::Array2;
+
+/// A trained model stripped down to what is needed for prediction
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct Predictor {
+    /// The settings used for training, which some algorithms need to rebuild their models
+    settings: Settings,
+    /// The features the model expects
+    schema: Schema,
+    /// The fitted PCA or SVD, if the settings use one
+    preprocessing: FittedPreprocessing,
+    /// The final model, or for blending the base models in the order the metamodel expects
+    models: Vec<(Algorithm, Vec<u8>)>,
+    /// The metamodel that blends the base models
+    metamodel: Option<(Algorithm, Vec<u8>)>,
+    /// The options the model was built with, which batch prediction parses files with
+    csv_options: CsvOptions,
+}
+
+impl Predictor {
+    /// Put a predictor together from the parts of a trained model
+    pub(crate) fn new(
+        settings: Settings,
+        schema: Schema,
+        preprocessing: FittedPreprocessing,
+        models: Vec<(Algorithm, Vec<u8>)>,
+        metamodel: Option<(Algorithm, Vec<u8>)>,
+        csv_options: CsvOptions,
+    ) -> Self {
+        Self {
+            settings,
+            schema,
+            preprocessing,
+            models,
+            metamodel,
+            csv_options,
+        }
+    }
+
+    /// Upgrade a serialized predictor from format version 8 by saving the default CSV options
+    pub(crate) fn add_csv_options(mut payload: Vec<u8>) -> Result<Vec<u8>, bincode::Error> {
+        payload.extend(bincode::serialize(&CsvOptions::default())?);
+        Ok(payload)
+    }
+
+    /// Upgrade a serialized predictor from format version 6 by tagging its SVC models
+    pub(crate) fn tag_svc_models(payload: Vec<u8>) -> Result<Vec<u8>, bincode::Error> {
+        let mut rest = &payload[crate::settings::end_of_settings_v6(&payload)?..];
+        bincode::deserialize_from::<_, (Schema, FittedPreprocessing)>(&mut rest)?;
+        let start = payload.len() - rest.len();
+        let (mut models, mut metamodel): (Vec<(Algorithm, Vec<u8>)>, Option<(Algorithm, Vec<u8>)>) =
+            bincode::deserialize_from(&mut rest)?;
+
+        for (algorithm, model) in models.iter_mut().chain(metamodel.as_mut()) {
+            crate::algorithms::tag_svc(*algorithm, model)?;
+        }
+        let mut upgraded = payload[..start].to_vec();
+        upgraded.extend(bincode::serialize(&(models, metamodel))?);
+        Ok(upgraded)
+    }
+
+    /// Load a predictor from a file saved previously
+    /// ```
+    /// # use automl::{SupervisedModel, Settings};
+    /// use automl::predictor::Predictor;
+    /// # let mut model = SupervisedModel::new_from_dataset(
+    /// #     smartcore::dataset::diabetes::load_dataset(),
+    /// #     Settings::default_regression(),
+    /// # );
+    /// # model.train();
+    /// # model.export_predictor().save("tests/load_that_predictor.aml");
+    /// let predictor = Predictor::new_from_file("tests/load_that_predictor.aml");
+    /// # std::fs::remove_file("tests/load_that_predictor.aml");
+    /// ```
+    pub fn new_from_file(file_name: &str) -> Self {
+        Self::try_new_from_file(file_name).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Load a predictor from a file saved previously, returning an error if the file cannot be
+    /// read, was written by an incompatible version, is damaged, or holds a full model instead
+    /// ```
+    /// # use automl::{SupervisedModel, Settings};
+    /// use automl::predictor::Predictor;
+    /// # let mut model = SupervisedModel::new_from_dataset(
+    /// #     smartcore::dataset::diabetes::load_dataset(),
+    /// #     Settings::default_regression(),
+    /// # );
+    /// # model.train();
+    /// # model.export_predictor().save("tests/try_load_that_predictor.aml");
+    /// match Predictor::try_new_from_file("tests/try_load_that_predictor.aml") {
+    ///     Ok(predictor) => println!("Loaded a predictor"),
+    ///     Err(error) => println!("{}", error),
+    /// }
+    /// # std::fs::remove_file("tests/try_load_that_predictor.aml");
+    /// ```
+    pub fn try_new_from_file(file_name: &str) -> Result<Self, LoadError> {
+        file_format::load(file_name, Contents::Predictor)
+    }
+
+    /// Save the predictor to a file for later use
+    /// ```
+    /// # use automl::{SupervisedModel, Settings};
+    /// let mut model = SupervisedModel::new_from_dataset(
+    ///     smartcore::dataset::diabetes::load_dataset(),
+    ///     Settings::default_regression(),
+    /// );
+    /// model.train();
+    /// model.export_predictor().save("tests/save_that_predictor.aml");
+    /// # std::fs::remove_file("tests/save_that_predictor.aml");
+    /// ```
+    pub fn save(&self, file_name: &str) {
+        file_format::save(self, Contents::Predictor, file_name);
+    }
+
+    /// Get the names and order of the features the predictor expects
+    /// ```
+    /// # use automl::{SupervisedModel, Settings};
+    /// let mut model = SupervisedModel::new_from_dataset(
+    ///     smartcore::dataset::diabetes::load_dataset(),
+    ///     Settings::default_regression(),
+    /// );
+    /// model.train();
+    /// let predictor = model.export_predictor();
+    /// assert_eq!(predictor.schema(), model.schema());
+    /// ```
+    pub fn schema(&self) -> &Schema {
+        &self.schema
+    }
+
+    /// Get the algorithm that makes the final prediction, which is the metamodel when blending
+    /// ```
+    /// # use automl::{SupervisedModel, Settings};
+    /// let mut model = SupervisedModel::new_from_dataset(
+    ///     smartcore::dataset::diabetes::load_dataset(),
+    ///     Settings::default_regression(),
+    /// );
+    /// model.train();
+    /// let predictor = model.export_predictor();
+    /// assert_eq!(Some(predictor.algorithm()), model.best_algorithm());
+    /// ```
+    pub fn algorithm(&self) -> Algorithm {
+        match &self.metamodel {
+            Some((algorithm, _)) => *algorithm,
+            None => self.models[0].0,
+        }
+    }
+
+    /// Get the metric the models were compared with
+    #[cfg(any(feature = "serve"))]
+    pub(crate) fn metric(&self) -> &crate::settings::Metric {
+        &self.settings.sort_by
+    }
+
+    /// Predict values based on a vec
+    /// ```
+    /// # use automl::{SupervisedModel, Settings};
+    /// let mut model = SupervisedModel::new_from_dataset(
+    ///     smartcore::dataset::diabetes::load_dataset(),
+    ///     Settings::default_regression(),
+    /// );
+    /// model.train();
+    /// let predictor = model.export_predictor();
+    /// predictor.predict_from_vec(vec![vec![5.0; 10]; 5]);
+    /// ```
+    pub fn predict_from_vec(&self, x: Vec<Vec<f32>>) -> Vec<f32> {
+        self.try_predict_from_vec(x)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Predict values based on a vec, returning an error if the rows do not have one value per
+    /// feature
+    /// ```
+    /// # use automl::{SupervisedModel, Settings};
+    /// let mut model = SupervisedModel::new_from_dataset(
+    ///     smartcore::dataset::diabetes::load_dataset(),
+    ///     Settings::default_regression(),
+    /// );
+    /// model.train();
+    /// let predictor = model.export_predictor();
+    /// assert!(predictor.try_predict_from_vec(vec![vec![5.0; 3]]).is_err());
+    /// ```
+    pub fn try_predict_from_vec(&self, x: Vec<Vec<f32>>) -> Result<Vec<f32>, SchemaError> {
+        self.schema.check_rows(&x)?;
+        Ok(self.predict(&DenseMatrix::from_2d_vec(&x)))
+    }
+
+    /// Predict over a CSV file a chunk of rows at a time, returning an iterator over the
+    /// predictions so that files larger than memory can be scored
+    /// ```
+    /// # use automl::{SupervisedModel, Settings};
+    /// use automl::batch::BatchOptions;
+    /// let mut model = SupervisedModel::new_from_dataset(
+    ///     smartcore::dataset::diabetes::load_dataset(),
+    ///     Settings::default_regression(),
+    /// );
+    /// model.train();
+    /// let predictor = model.export_predictor();
+    /// # let csv = "a,b,c,d,e,f,g,h,i,j\n0,0,0,0,0,0,0,0,0,0\n";
+    /// # std::fs::write("tests/batches.csv", csv).unwrap();
+    /// let options = BatchOptions::default().with_chunk_size(1000);
+    /// let predictions = predictor.predict_from_csv_in_batches("tests/batches.csv", options);
+    /// for prediction in predictions.unwrap() {
+    ///     println!("{}", prediction.unwrap().value());
+    /// }
+    /// # std::fs::remove_file("tests/batches.csv");
+    /// ```
+    pub fn predict_from_csv_in_batches(
+        &self,
+        file_name: &str,
+        options: BatchOptions,
+    ) -> Result<BatchPredictions<'_>, BatchError> {
+        BatchPredictions::new(
+            file_name,
+            &self.schema,
+            &self.csv_options,
+            |x| self.predict(x),
+            self.probability_predictor(),
+            options,
+        )
+    }
+
+    /// Predict over a CSV file a chunk of rows at a time and write the predictions to another CSV
+    /// file, returning the number of rows written
+    /// ```
+    /// # use automl::{SupervisedModel, Settings};
+    /// use automl::batch::BatchOptions;
+    /// let mut model = SupervisedModel::new_from_dataset(
+    ///     smartcore::dataset::diabetes::load_dataset(),
+    ///     Settings::default_regression(),
+    /// );
+    /// model.train();
+    /// let predictor = model.export_predictor();
+    /// # let csv = "id,a,b,c,d,e,f,g,h,i,j\n7,0,0,0,0,0,0,0,0,0,0\n";
+    /// # std::fs::write("tests/predictor_input.csv", csv).unwrap();
+    /// let options = BatchOptions::default().with_id_column("id");
+    /// let input = "tests/predictor_input.csv";
+    /// let rows = predictor.predict_csv_to_file(input, "tests/predictor_output.csv", options);
+    /// assert_eq!(rows.unwrap(), 1);
+    /// # std::fs::remove_file("tests/predictor_input.csv");
+    /// # std::fs::remove_file("tests/predictor_output.csv");
+    /// ```
+    pub fn predict_csv_to_file(
+        &self,
+        input_file_name: &str,
+        output_file_name: &str,
+        options: BatchOptions,
+    ) -> Result<usize, BatchError> {
+        self.predict_from_csv_in_batches(input_file_name, options)?
+            .write(output_file_name)
+    }
+
+    /// Predict CSV text with a header, parsed with the saved CSV options as in batch prediction
+    #[cfg(feature = "serve")]
+    pub(crate) fn predict_from_csv_text(&self, text: &str) -> Result<Vec<f32>, BatchError> {
+        BatchPredictions::from_reader(
+            Box::new(text.as_bytes()),
+            &self.schema,
+            &self.csv_options,
+            |x| self.predict(x),
+            self.probability_predictor(),
+            BatchOptions::default(),
+        )?
+        .map(|prediction| prediction.map(|prediction| prediction.value()))
+        .collect()
+    }
+
+    /// Predicts class probabilities with the final model, unless it is blended or cannot
+    fn probability_predictor(
+        &self,
+    ) -> Option<impl Fn(&DenseMatrix<f32>) -> Option<ClassProbabilities> + '_> {
+        if self.metamodel.is_some() {
+            return None;
+        }
+        let (algorithm, model) = &self.models[0];
+        let predict_proba = algorithm.get_probability_predictor()?;
+        Some(move |x: &DenseMatrix<f32>| {
+            let x = SupervisedModel::transform(
+                x.clone(),
+                &self.settings.preprocessing,
+                &self.preprocessing,
+            );
+            predict_proba(&x, model)
+        })
+    }
+
+    /// Make predictions for data that already matches the schema
+    pub(crate) fn predict(&self, x: &DenseMatrix<f32>) -> Vec<f32> {
+        let x = &SupervisedModel::transform(
+            x.clone(),
+            &self.settings.preprocessing,
+            &self.preprocessing,
+        );
+        let mut base_predictions = self
+            .models
+            .iter()
+            .map(|(algorithm, model)| (*algorithm.get_predictor())(x, model, &self.settings));
+        match &self.metamodel {
+            None => base_predictions
+                .next()
+                .expect("A predictor needs a final model."),
+            Some((algorithm, metamodel)) => {
+                let meta_x = DenseMatrix::from_2d_vec(&base_predictions.collect()).transpose();
+                (*algorithm.get_predictor())(&meta_x, metamodel, &self.settings)
+            }
+        }
+    }
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "nd")))]
+#[cfg(any(feature = "nd"))]
+impl Predictor {
+    /// Predict values based on ndarray
+    /// ```
+    /// # use automl::{SupervisedModel, Settings};
+    /// use ndarray::Array2;
+    /// let mut model = SupervisedModel::new_from_dataset(
+    ///     smartcore::dataset::diabetes::load_dataset(),
+    ///     Settings::default_regression(),
+    /// );
+    /// model.train();
+    /// let predictor = model.export_predictor();
+    /// predictor.predict_from_ndarray(Array2::zeros((2, 10)));
+    /// ```
+    pub fn predict_from_ndarray(&self, x: Array2<f32>) -> Vec<f32> {
+        self.try_predict_from_ndarray(x)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Predict values based on ndarray, returning an error if the array does not have one column
+    /// per feature
+    /// ```
+    /// # use automl::{SupervisedModel, Settings};
+    /// use ndarray::Array2;
+    /// let mut model = SupervisedModel::new_from_dataset(
+    ///     smartcore::dataset::diabetes::load_dataset(),
+    ///     Settings::default_regression(),
+    /// );
+    /// model.train();
+    /// let predictor = model.export_predictor();
+    /// assert!(predictor.try_predict_from_ndarray(Array2::zeros((2, 3))).is_err());
+    /// ```
+    pub fn try_predict_from_ndarray(&self, x: Array2<f32>) -> Result<Vec<f32>, SchemaError> {
+        self.schema.check_width(x.shape()[1])?;
+        Ok(self.predict(&DenseMatrix::from_array(
+            x.shape()[0],
+            x.shape()[1],
+            x.as_slice().unwrap(),
+        )))
+    }
+}
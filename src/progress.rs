@@ -0,0 +1,361 @@
+//! # Progress reporting and cancellation
+//! This module contains the hooks used to follow a model comparison while it runs. Implement
+//! [`ProgressObserver`] and attach it to a model to receive a callback as each algorithm starts,
+//! as each cross-validation fold finishes, and when the final model is ready.
+//! ```
+//! # use automl::{SupervisedModel, Settings};
+//! use automl::{progress::ProgressObserver, settings::Algorithm};
+//!
+//! struct FoldCounter(usize);
+//!
+//! impl ProgressObserver for FoldCounter {
+//!     fn on_fold_end(&mut self, _: Algorithm, _: usize, _: usize, _: f32, _: f32) {
+//!         self.0 += 1;
+//!     }
+//! }
+//!
+//! let mut model = SupervisedModel::new_from_dataset(
+//!     smartcore::dataset::diabetes::load_dataset(),
+//!     Settings::default_regression().only(Algorithm::Linear),
+//! )
+//! .with_progress_observer(FoldCounter(0));
+//! model.train();
+//! ```
+//! Setting [`Settings::verbose`](crate::Settings::verbose) to `true` attaches an observer that
+//! prints the same information to the console.
+//!
+//! A comparison can also be stopped early, either by a time limit set through
+//! [`Settings::with_time_budget`](crate::Settings::with_time_budget) and
+//! [`Settings::with_algorithm_timeout`](crate::Settings::with_algorithm_timeout), or from another
+//! thread through a [`CancellationToken`]. Limits are checked between folds and between
+//! algorithms, so a fold that is already running is allowed to finish. Algorithms that are cut
+//! short or skipped keep their place in the comparison with a [`Status`] saying why.
+
+use crate::settings::{Algorithm, Settings};
+use std::fmt::{Display, Formatter};
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+use std::time::{Duration, Instant};
+
+/// A handle that stops a model comparison from another thread
+/// ```
+/// # use automl::{SupervisedModel, Settings};
+/// use automl::progress::CancellationToken;
+/// let token = CancellationToken::new();
+/// let mut model = SupervisedModel::new_from_dataset(
+///     smartcore::dataset::diabetes::load_dataset(),
+///     Settings::default_regression(),
+/// )
+/// .with_cancellation_token(token.clone());
+///
+/// // Every algorithm is skipped once the token has been cancelled
+/// token.cancel();
+/// model.train();
+/// ```
+#[derive(Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    /// Create a token that has not been cancelled
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Ask any comparison holding this token to stop
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    /// Check whether the token has been cancelled
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+/// Reasons for stopping an algorithm before cross-validation finished, or for never starting it
+#[derive(Clone, PartialEq, Debug, serde::Serialize, serde::Deserialize)]
+pub enum Interruption {
+    /// The time budget for the whole comparison ran out
+    TimeBudget,
+    /// The algorithm ran longer than the per-algorithm timeout
+    AlgorithmTimeout,
+    /// The comparison was cancelled through a [`CancellationToken`]
+    Cancelled,
+    /// The algorithm cannot be fit to the training data, for the reason given
+    Unsuitable(String),
+}
+
+impl Display for Interruption {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Interruption::TimeBudget => write!(f, "time budget exhausted"),
+            Interruption::AlgorithmTimeout => write!(f, "algorithm timed out"),
+            Interruption::Cancelled => write!(f, "cancelled"),
+            Interruption::Unsuitable(reason) => write!(f, "{}", reason),
+        }
+    }
+}
+
+/// How far an algorithm got during the comparison
+#[derive(Clone, PartialEq, Debug, serde::Serialize, serde::Deserialize)]
+pub enum Status {
+    /// Every cross-validation fold was run
+    Completed,
+    /// Some folds were run before the comparison was interrupted; the scores cover those folds
+    CutShort(Interruption),
+    /// The algorithm was not run at all
+    Skipped(Interruption),
+    /// The algorithm could not be fit to one of the folds, for the reason given; the scores cover
+    /// the folds before it
+    Failed(String),
+}
+
+impl Display for Status {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Status::Completed => write!(f, "Completed"),
+            Status::CutShort(reason) => write!(f, "Cut short ({})", reason),
+            Status::Skipped(reason) => write!(f, "Skipped ({})", reason),
+            Status::Failed(reason) => write!(f, "Failed ({})", reason),
+        }
+    }
+}
+
+/// Receives progress updates while a [`SupervisedModel`](crate::SupervisedModel) is trained.
+///
+/// Every method has an empty default implementation, so only the events of interest need to be
+/// implemented. Observers must be `Send + Sync` so that a model can be shared between threads once
+/// it is trained.
+pub trait ProgressObserver: Send + Sync {
+    /// Called before cross-validation of an algorithm begins. `position` counts from 1 up to
+    /// `total`, the number of algorithms in the comparison.
+    fn on_algorithm_start(&mut self, _algorithm: Algorithm, _position: usize, _total: usize) {}
+
+    /// Called after each cross-validation fold with the training and testing scores of that fold.
+    /// `fold` counts from 1 up to `number_of_folds`.
+    fn on_fold_end(
+        &mut self,
+        _algorithm: Algorithm,
+        _fold: usize,
+        _number_of_folds: usize,
+        _train_score: f32,
+        _test_score: f32,
+    ) {
+    }
+
+    /// Called once an algorithm has been cross-validated, with its mean testing score, the time
+    /// it took, and the best algorithm and score found so far among those that ran every fold, if
+    /// any have.
+    fn on_algorithm_end(
+        &mut self,
+        _algorithm: Algorithm,
+        _mean_test_score: f32,
+        _duration: Duration,
+        _best: Option<(Algorithm, f32)>,
+    ) {
+    }
+
+    /// Called when the final model is ready, with the algorithm used for predictions (the
+    /// meta-learner when blending) and its testing score.
+    fn on_final_model(&mut self, _algorithm: Algorithm, _test_score: f32) {}
+}
+
+/// Prints progress to the console, used when the settings are verbose
+pub(crate) struct ConsoleObserver {
+    /// When training started
+    start: Instant,
+    /// The name of the sorting metric
+    metric: String,
+}
+
+impl ConsoleObserver {
+    /// Create a console observer that labels scores with the given metric name
+    pub(crate) fn new(metric: String) -> Self {
+        Self {
+            start: Instant::now(),
+            metric,
+        }
+    }
+}
+
+impl ProgressObserver for ConsoleObserver {
+    fn on_algorithm_start(&mut self, algorithm: Algorithm, position: usize, total: usize) {
+        println!("[{}/{}] {}", position, total, algorithm);
+    }
+
+    fn on_fold_end(
+        &mut self,
+        _algorithm: Algorithm,
+        fold: usize,
+        number_of_folds: usize,
+        train_score: f32,
+        test_score: f32,
+    ) {
+        println!(
+            "    Fold {}/{}: training {} = {}, testing {} = {} ({:.2?} elapsed)",
+            fold,
+            number_of_folds,
+            self.metric,
+            train_score,
+            self.metric,
+            test_score,
+            self.start.elapsed()
+        );
+    }
+
+    fn on_algorithm_end(
+        &mut self,
+        _algorithm: Algorithm,
+        mean_test_score: f32,
+        duration: Duration,
+        best: Option<(Algorithm, f32)>,
+    ) {
+        let best = match best {
+            Some((algorithm, score)) => format!("{}, {}", algorithm, score),
+            None => "none completed".to_string(),
+        };
+        println!(
+            "    Mean testing {} = {} in {:.2?} (best so far: {})",
+            self.metric, mean_test_score, duration, best
+        );
+    }
+
+    fn on_final_model(&mut self, algorithm: Algorithm, test_score: f32) {
+        println!(
+            "Final model: {}, testing {} = {} ({:.2?} elapsed)",
+            algorithm,
+            self.metric,
+            test_score,
+            self.start.elapsed()
+        );
+    }
+}
+
+/// Fans progress events out to every attached observer and keeps track of time limits
+pub(crate) struct Monitor {
+    /// The observers attached to the model
+    observers: Vec<Box<dyn ProgressObserver>>,
+    /// Console output, present when the settings are verbose
+    console: Option<ConsoleObserver>,
+    /// Token used to cancel the comparison
+    token: Option<CancellationToken>,
+    /// When the comparison started
+    start: Instant,
+    /// When the current algorithm started
+    algorithm_start: Instant,
+    /// Time allowed for the whole comparison
+    time_budget: Option<Duration>,
+    /// Time allowed for each algorithm
+    algorithm_timeout: Option<Duration>,
+}
+
+impl Monitor {
+    /// Create a monitor from the observers attached to a model and its settings
+    pub(crate) fn new(
+        observers: Vec<Box<dyn ProgressObserver>>,
+        settings: &Settings,
+        token: Option<CancellationToken>,
+    ) -> Self {
+        Self {
+            observers,
+            console: if settings.verbose {
+                Some(ConsoleObserver::new(format!("{}", settings.sort_by)))
+            } else {
+                None
+            },
+            token,
+            start: Instant::now(),
+            algorithm_start: Instant::now(),
+            time_budget: settings.time_budget,
+            algorithm_timeout: settings.algorithm_timeout,
+        }
+    }
+
+    /// Hand the attached observers back once training is done
+    pub(crate) fn into_observers(self) -> Vec<Box<dyn ProgressObserver>> {
+        self.observers
+    }
+
+    /// Check whether the whole comparison should stop, and why
+    pub(crate) fn comparison_interruption(&self) -> Option<Interruption> {
+        if self
+            .token
+            .as_ref()
+            .is_some_and(|token| token.is_cancelled())
+        {
+            Some(Interruption::Cancelled)
+        } else if self
+            .time_budget
+            .is_some_and(|budget| self.start.elapsed() > budget)
+        {
+            Some(Interruption::TimeBudget)
+        } else {
+            None
+        }
+    }
+
+    /// Check whether the current algorithm should stop, and why
+    pub(crate) fn algorithm_interruption(&self) -> Option<Interruption> {
+        self.comparison_interruption().or_else(|| {
+            self.algorithm_timeout
+                .filter(|timeout| self.algorithm_start.elapsed() > *timeout)
+                .map(|_| Interruption::AlgorithmTimeout)
+        })
+    }
+
+    /// Iterate over every observer, including the console
+    fn all(&mut self) -> impl Iterator<Item = &mut (dyn ProgressObserver + 'static)> {
+        self.observers
+            .iter_mut()
+            .map(|observer| observer.as_mut())
+            .chain(
+                self.console
+                    .iter_mut()
+                    .map(|console| console as &mut (dyn ProgressObserver + 'static)),
+            )
+    }
+
+    /// See [`ProgressObserver::on_algorithm_start`]
+    pub(crate) fn algorithm_start(&mut self, algorithm: Algorithm, position: usize, total: usize) {
+        self.algorithm_start = Instant::now();
+        for observer in self.all() {
+            observer.on_algorithm_start(algorithm, position, total);
+        }
+    }
+
+    /// See [`ProgressObserver::on_fold_end`]
+    pub(crate) fn fold_end(
+        &mut self,
+        algorithm: Algorithm,
+        fold: usize,
+        number_of_folds: usize,
+        train_score: f32,
+        test_score: f32,
+    ) {
+        for observer in self.all() {
+            observer.on_fold_end(algorithm, fold, number_of_folds, train_score, test_score);
+        }
+    }
+
+    /// See [`ProgressObserver::on_algorithm_end`]
+    pub(crate) fn algorithm_end(
+        &mut self,
+        algorithm: Algorithm,
+        mean_test_score: f32,
+        duration: Duration,
+        best: Option<(Algorithm, f32)>,
+    ) {
+        for observer in self.all() {
+            observer.on_algorithm_end(algorithm, mean_test_score, duration, best);
+        }
+    }
+
+    /// See [`ProgressObserver::on_final_model`]
+    pub(crate) fn final_model(&mut self, algorithm: Algorithm, test_score: f32) {
+        for observer in self.all() {
+            observer.on_final_model(algorithm, test_score);
+        }
+    }
+}
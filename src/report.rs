@@ -0,0 +1,315 @@
+//! # Comparison reports
+//! This module contains [`Report`], a snapshot of a trained model comparison that can be written
+//! out as JSON, CSV, Markdown or a standalone HTML page. A report holds the settings summary, the
+//! [leaderboard](crate::leaderboard), the meta-model score when blending, and the shape of the
+//! training data.
+//! ```
+//! # use automl::{SupervisedModel, Settings};
+//! let mut model = SupervisedModel::new_from_dataset(
+//!     smartcore::dataset::diabetes::load_dataset(),
+//!     Settings::default_regression(),
+//! );
+//! model.train();
+//! let report = model.report();
+//! std::fs::write("tests/report.md", report.to_markdown()).unwrap();
+//! # std::fs::remove_file("tests/report.md");
+//! ```
+
+use crate::leaderboard::LeaderboardEntry;
+use crate::utils::csv_field;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+/// A structured summary of a model comparison
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct Report {
+    /// The settings summary, as printed by the settings
+    pub(crate) settings: String,
+    /// Number of samples used for training, including any held out for blending
+    pub(crate) number_of_samples: usize,
+    /// Number of features after preprocessing
+    pub(crate) number_of_features: usize,
+    /// Number of samples in each class, for classification
+    pub(crate) class_counts: Option<BTreeMap<String, usize>>,
+    /// The results of the comparison, best first
+    pub(crate) leaderboard: Vec<LeaderboardEntry>,
+    /// The meta-model score, when blending
+    pub(crate) meta_model: Option<LeaderboardEntry>,
+}
+
+impl Report {
+    /// The settings summary
+    /// ```
+    /// # use automl::{SupervisedModel, Settings};
+    /// # let model = SupervisedModel::new_from_dataset(
+    /// #     smartcore::dataset::diabetes::load_dataset(),
+    /// #     Settings::default_regression(),
+    /// # );
+    /// println!("{}", model.report().settings());
+    /// ```
+    pub fn settings(&self) -> &str {
+        &self.settings
+    }
+
+    /// Number of samples and features in the training data, after preprocessing
+    /// ```
+    /// # use automl::{SupervisedModel, Settings};
+    /// # let model = SupervisedModel::new_from_dataset(
+    /// #     smartcore::dataset::diabetes::load_dataset(),
+    /// #     Settings::default_regression(),
+    /// # );
+    /// assert_eq!(model.report().shape(), (442, 10));
+    /// ```
+    pub fn shape(&self) -> (usize, usize) {
+        (self.number_of_samples, self.number_of_features)
+    }
+
+    /// Number of samples in each class, keyed by class label, for classification
+    /// ```
+    /// # use automl::{SupervisedModel, Settings};
+    /// # let model = SupervisedModel::new_from_dataset(
+    /// #     smartcore::dataset::breast_cancer::load_dataset(),
+    /// #     Settings::default_classification(),
+    /// # );
+    /// let counts = model.report().class_counts().unwrap().clone();
+    /// assert_eq!(counts.values().sum::<usize>(), 569);
+    /// ```
+    pub fn class_counts(&self) -> Option<&BTreeMap<String, usize>> {
+        self.class_counts.as_ref()
+    }
+
+    /// The results of the comparison, best first
+    /// ```
+    /// # use automl::{SupervisedModel, Settings};
+    /// # let model = SupervisedModel::new_from_dataset(
+    /// #     smartcore::dataset::diabetes::load_dataset(),
+    /// #     Settings::default_regression(),
+    /// # );
+    /// assert!(model.report().leaderboard().is_empty());
+    /// ```
+    pub fn leaderboard(&self) -> &[LeaderboardEntry] {
+        &self.leaderboard
+    }
+
+    /// The meta-model score, present when the final model is blended
+    /// ```
+    /// # use automl::{SupervisedModel, Settings};
+    /// # let model = SupervisedModel::new_from_dataset(
+    /// #     smartcore::dataset::diabetes::load_dataset(),
+    /// #     Settings::default_regression(),
+    /// # );
+    /// assert!(model.report().meta_model().is_none());
+    /// ```
+    pub fn meta_model(&self) -> Option<&LeaderboardEntry> {
+        self.meta_model.as_ref()
+    }
+
+    /// Write the report as JSON
+    /// ```
+    /// # use automl::{SupervisedModel, Settings};
+    /// # let model = SupervisedModel::new_from_dataset(
+    /// #     smartcore::dataset::diabetes::load_dataset(),
+    /// #     Settings::default_regression(),
+    /// # );
+    /// let json = model.report().to_json();
+    /// ```
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(&self).expect("Cannot serialize report.")
+    }
+
+    /// Write the leaderboard as CSV, one row per algorithm with per-fold scores separated by `;`
+    /// ```
+    /// # use automl::{SupervisedModel, Settings};
+    /// # let model = SupervisedModel::new_from_dataset(
+    /// #     smartcore::dataset::diabetes::load_dataset(),
+    /// #     Settings::default_regression(),
+    /// # );
+    /// let csv = model.report().to_csv();
+    /// ```
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from(
+            "rank,algorithm,status,mean_train_score,std_train_score,mean_test_score,\
+             std_test_score,duration_seconds,train_scores,test_scores,parameters\n",
+        );
+        for (rank, entry) in self.leaderboard.iter().enumerate() {
+            let row = vec![
+                format!("{}", rank + 1),
+                format!("{}", entry.algorithm()),
+                format!("{}", entry.status()),
+                format!("{}", entry.mean_train_score()),
+                format!("{}", entry.std_train_score()),
+                format!("{}", entry.mean_test_score()),
+                format!("{}", entry.std_test_score()),
+                format!("{}", entry.duration().as_secs_f64()),
+                entry
+                    .train_scores()
+                    .iter()
+                    .map(|s| s.to_string())
+                    .collect::<Vec<_>>()
+                    .join(";"),
+                entry
+                    .test_scores()
+                    .iter()
+                    .map(|s| s.to_string())
+                    .collect::<Vec<_>>()
+                    .join(";"),
+                format_parameters(entry, "; "),
+            ];
+            let row: Vec<String> = row.iter().map(|field| csv_field(field)).collect();
+            csv.push_str(&row.join(","));
+            csv.push('\n');
+        }
+        csv
+    }
+
+    /// Write the report as Markdown
+    /// ```
+    /// # use automl::{SupervisedModel, Settings};
+    /// # let model = SupervisedModel::new_from_dataset(
+    /// #     smartcore::dataset::diabetes::load_dataset(),
+    /// #     Settings::default_regression(),
+    /// # );
+    /// let markdown = model.report().to_markdown();
+    /// ```
+    pub fn to_markdown(&self) -> String {
+        let mut md = String::from("# Model Comparison Report\n\n## Data\n\n");
+        writeln!(md, "- Samples: {}", self.number_of_samples).unwrap();
+        writeln!(md, "- Features: {}", self.number_of_features).unwrap();
+        if let Some(class_counts) = &self.class_counts {
+            md.push_str("- Class counts:\n");
+            for (class, count) in class_counts {
+                writeln!(md, "  - {}: {}", class, count).unwrap();
+            }
+        }
+
+        md.push_str("\n## Leaderboard\n\n");
+        md.push_str(
+            "| Rank | Model | Status | Time | Training Score | Testing Score | Parameters |\n",
+        );
+        md.push_str("|---:|---|---|---:|---:|---:|---|\n");
+        for (rank, entry) in self.leaderboard.iter().enumerate() {
+            writeln!(
+                md,
+                "| {} | {} | {} | {:.2?} | {} ± {} | {} ± {} | {} |",
+                rank + 1,
+                entry.algorithm(),
+                entry.status(),
+                entry.duration(),
+                entry.mean_train_score(),
+                entry.std_train_score(),
+                entry.mean_test_score(),
+                entry.std_test_score(),
+                format_parameters(entry, ", ").replace('|', "\\|"),
+            )
+            .unwrap();
+        }
+
+        if let Some(meta_model) = &self.meta_model {
+            md.push_str("\n## Meta Model\n\n");
+            writeln!(md, "- Algorithm: {}", meta_model.algorithm()).unwrap();
+            writeln!(md, "- Training score: {}", meta_model.mean_train_score()).unwrap();
+            writeln!(md, "- Testing score: {}", meta_model.mean_test_score()).unwrap();
+        }
+
+        write!(md, "\n## Settings\n\n```text\n{}\n```\n", self.settings).unwrap();
+        md
+    }
+
+    /// Write the report as a standalone HTML page
+    /// ```
+    /// # use automl::{SupervisedModel, Settings};
+    /// # let model = SupervisedModel::new_from_dataset(
+    /// #     smartcore::dataset::diabetes::load_dataset(),
+    /// #     Settings::default_regression(),
+    /// # );
+    /// let html = model.report().to_html();
+    /// ```
+    pub fn to_html(&self) -> String {
+        let mut html = String::from(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+             <title>Model Comparison Report</title>\n<style>\n\
+             body { font-family: sans-serif; margin: 2em; }\n\
+             table { border-collapse: collapse; }\n\
+             th, td { border: 1px solid #ccc; padding: 0.3em 0.6em; text-align: left; }\n\
+             th { background: #eee; }\n\
+             </style>\n</head>\n<body>\n<h1>Model Comparison Report</h1>\n<h2>Data</h2>\n<ul>\n",
+        );
+        writeln!(html, "<li>Samples: {}</li>", self.number_of_samples).unwrap();
+        writeln!(html, "<li>Features: {}</li>", self.number_of_features).unwrap();
+        if let Some(class_counts) = &self.class_counts {
+            html.push_str("<li>Class counts:<ul>\n");
+            for (class, count) in class_counts {
+                writeln!(html, "<li>{}: {}</li>", escape_html(class), count).unwrap();
+            }
+            html.push_str("</ul></li>\n");
+        }
+        html.push_str("</ul>\n");
+
+        html.push_str(
+            "<h2>Leaderboard</h2>\n<table>\n<tr><th>Rank</th><th>Model</th><th>Status</th>\
+             <th>Time</th><th>Training Score</th><th>Testing Score</th><th>Parameters</th></tr>\n",
+        );
+        for (rank, entry) in self.leaderboard.iter().enumerate() {
+            writeln!(
+                html,
+                "<tr><td>{}</td><td>{}</td><td>{}</td><td>{:.2?}</td><td>{} &plusmn; {}</td>\
+                 <td>{} &plusmn; {}</td><td>{}</td></tr>",
+                rank + 1,
+                entry.algorithm(),
+                entry.status(),
+                entry.duration(),
+                entry.mean_train_score(),
+                entry.std_train_score(),
+                entry.mean_test_score(),
+                entry.std_test_score(),
+                escape_html(&format_parameters(entry, ", ")),
+            )
+            .unwrap();
+        }
+        html.push_str("</table>\n");
+
+        if let Some(meta_model) = &self.meta_model {
+            html.push_str("<h2>Meta Model</h2>\n<ul>\n");
+            writeln!(html, "<li>Algorithm: {}</li>", meta_model.algorithm()).unwrap();
+            writeln!(
+                html,
+                "<li>Training score: {}</li>",
+                meta_model.mean_train_score()
+            )
+            .unwrap();
+            writeln!(
+                html,
+                "<li>Testing score: {}</li>",
+                meta_model.mean_test_score()
+            )
+            .unwrap();
+            html.push_str("</ul>\n");
+        }
+
+        write!(
+            html,
+            "<h2>Settings</h2>\n<pre>{}</pre>\n</body>\n</html>\n",
+            escape_html(&self.settings)
+        )
+        .unwrap();
+        html
+    }
+}
+
+/// Join the parameters of an entry into a single `name = value` list
+fn format_parameters(entry: &LeaderboardEntry, separator: &str) -> String {
+    entry
+        .parameters()
+        .iter()
+        .map(|(name, value)| format!("{} = {}", name, value.replace('\n', " ")))
+        .collect::<Vec<_>>()
+        .join(separator)
+}
+
+/// Escape the characters that have a meaning in HTML
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
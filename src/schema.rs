@@ -0,0 +1,396 @@
+//! # Feature schemas
+//! This module contains [`Schema`], which records the names and order of the features a
+//! [`SupervisedModel`](crate::SupervisedModel) was built with. Names come from CSV headers or
+//! from the `*_with_names` constructors, and default to `x_0`, `x_1`, ... otherwise. They are
+//! saved with the model and used to label explanations and plots, to name the columns created by
+//! preprocessing, and to check the columns of data passed in for prediction.
+//!
+//! Every `predict_from_*` method checks its input against the schema and panics with a
+//! [`SchemaError`] message if they do not match. The matching `try_predict_from_*` methods return
+//! the error instead, listing the columns that are missing, extra or out of order.
+//! ```
+//! # use automl::{SupervisedModel, Settings};
+//! let model = SupervisedModel::new_from_vec_with_names(
+//!     vec![vec![1.0, 2.0], vec![2.0, 1.0], vec![3.0, 3.0]],
+//!     vec![1.0, 2.0, 3.0],
+//!     &["age", "income"],
+//!     Settings::default_regression(),
+//! );
+//! assert_eq!(model.schema().names(), ["age", "income"]);
+//! ```
+
+use crate::settings::PreProcessing;
+use itertools::Itertools;
+use std::fmt::{Display, Formatter};
+
+/// The names and order of the features a model expects
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Schema {
+    /// The name of each feature, in column order
+    names: Vec<String>,
+    /// The type of each feature, in column order
+    types: Vec<FeatureType>,
+    /// Whether the names were supplied with the data rather than generated
+    named: bool,
+}
+
+impl Schema {
+    /// Generate the names `x_0`, `x_1`, ... for data without a header
+    pub(crate) fn anonymous(number_of_features: usize) -> Self {
+        Self {
+            names: (0..number_of_features)
+                .map(|i| format!("x_{}", i))
+                .collect(),
+            types: vec![FeatureType::Numeric; number_of_features],
+            named: false,
+        }
+    }
+
+    /// Use the names supplied with the data
+    pub(crate) fn named(names: Vec<String>) -> Self {
+        let mut unique = names.clone();
+        unique.sort();
+        unique.dedup();
+        assert_eq!(
+            unique.len(),
+            names.len(),
+            "Feature names must be unique, but got {:?}.",
+            names
+        );
+        Self {
+            types: vec![FeatureType::Numeric; names.len()],
+            names,
+            named: true,
+        }
+    }
+
+    /// Record the type of each feature
+    #[cfg(any(feature = "dataframe"))]
+    pub(crate) fn with_types(mut self, types: Vec<FeatureType>) -> Self {
+        self.types = types;
+        self
+    }
+
+    /// Get the name of each feature, in column order
+    /// ```
+    /// # use automl::{SupervisedModel, Settings};
+    /// let model = SupervisedModel::new_from_vec(
+    ///     vec![vec![1.0, 2.0], vec![2.0, 1.0], vec![3.0, 3.0]],
+    ///     vec![1.0, 2.0, 3.0],
+    ///     Settings::default_regression(),
+    /// );
+    /// assert_eq!(model.schema().names(), ["x_0", "x_1"]);
+    /// ```
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    /// Get the type of each feature, in column order
+    /// ```
+    /// # use automl::{SupervisedModel, Settings};
+    /// use automl::schema::FeatureType;
+    /// let model = SupervisedModel::new_from_dataset(
+    ///     smartcore::dataset::diabetes::load_dataset(),
+    ///     Settings::default_regression(),
+    /// );
+    /// assert!(model.schema().types().iter().all(|t| *t == FeatureType::Numeric));
+    /// ```
+    pub fn types(&self) -> &[FeatureType] {
+        &self.types
+    }
+
+    /// Get the number of features
+    /// ```
+    /// # use automl::{SupervisedModel, Settings};
+    /// let model = SupervisedModel::new_from_dataset(
+    ///     smartcore::dataset::diabetes::load_dataset(),
+    ///     Settings::default_regression(),
+    /// );
+    /// assert_eq!(model.schema().len(), 10);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    /// Check whether there are no features
+    /// ```
+    /// # use automl::{SupervisedModel, Settings};
+    /// let model = SupervisedModel::new_from_dataset(
+    ///     smartcore::dataset::diabetes::load_dataset(),
+    ///     Settings::default_regression(),
+    /// );
+    /// assert!(!model.schema().is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// Check whether the names were supplied with the data rather than generated
+    /// ```
+    /// # use automl::{SupervisedModel, Settings};
+    /// let model = SupervisedModel::new_from_vec_with_names(
+    ///     vec![vec![1.0, 2.0], vec![2.0, 1.0], vec![3.0, 3.0]],
+    ///     vec![1.0, 2.0, 3.0],
+    ///     &["age", "income"],
+    ///     Settings::default_regression(),
+    /// );
+    /// assert!(model.schema().is_named());
+    /// ```
+    pub fn is_named(&self) -> bool {
+        self.named
+    }
+
+    /// Get the names of the columns produced by preprocessing
+    pub(crate) fn preprocessed_names(&self, preprocessing: &PreProcessing) -> Vec<String> {
+        let names = &self.names;
+        match *preprocessing {
+            PreProcessing::None => names.clone(),
+            PreProcessing::AddInteractions => {
+                let mut preprocessed = names.clone();
+                for i in 0..names.len() {
+                    for j in (i + 1)..names.len() {
+                        preprocessed.push(format!("{}*{}", names[i], names[j]));
+                    }
+                }
+                preprocessed
+            }
+            PreProcessing::AddPolynomial { order } => {
+                let mut preprocessed = names.clone();
+                for n in 2..=order {
+                    for combo in names.iter().combinations_with_replacement(n) {
+                        preprocessed.push(combo.into_iter().join("*"));
+                    }
+                }
+                preprocessed
+            }
+            PreProcessing::ReplaceWithPCA {
+                number_of_components,
+            } => (0..number_of_components)
+                .map(|i| format!("pca_{}", i))
+                .collect(),
+            PreProcessing::ReplaceWithSVD {
+                number_of_components,
+            } => (0..number_of_components)
+                .map(|i| format!("svd_{}", i))
+                .collect(),
+        }
+    }
+
+    /// Check that every row has one value per feature
+    pub(crate) fn check_rows(&self, x: &[Vec<f32>]) -> Result<(), SchemaError> {
+        match x.iter().position(|row| row.len() != self.len()) {
+            Some(row) => Err(SchemaError::RowLength {
+                row,
+                expected: self.len(),
+                found: x[row].len(),
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// Check that a matrix has one column per feature
+    pub(crate) fn check_width(&self, width: usize) -> Result<(), SchemaError> {
+        if width == self.len() {
+            Ok(())
+        } else {
+            Err(SchemaError::NumberOfColumns {
+                expected: self.len(),
+                found: width,
+            })
+        }
+    }
+
+    /// Check that column names match the schema, in the same order. Names are only compared if the
+    /// schema itself is named.
+    pub(crate) fn check_names(&self, names: &[String]) -> Result<(), SchemaError> {
+        if !self.named || names == self.names {
+            return Ok(());
+        }
+        let missing = difference(&self.names, names);
+        let extra = difference(names, &self.names);
+        let out_of_order = if missing.is_empty() && extra.is_empty() {
+            self.names
+                .iter()
+                .zip(names)
+                .filter(|(expected, found)| expected != found)
+                .map(|(expected, _)| expected.clone())
+                .collect()
+        } else {
+            vec![]
+        };
+        Err(SchemaError::Columns {
+            missing,
+            extra,
+            out_of_order,
+        })
+    }
+
+    /// Check that named columns match the schema, in the same order and with the same types. Names
+    /// are only compared if the schema itself is named.
+    #[cfg(any(feature = "dataframe"))]
+    pub(crate) fn check_columns(
+        &self,
+        names: Option<&[String]>,
+        types: &[FeatureType],
+    ) -> Result<(), SchemaError> {
+        if let Some(names) = names {
+            self.check_names(names)?;
+        }
+        self.check_width(types.len())?;
+        match self.types.iter().zip(types).position(|(a, b)| a != b) {
+            Some(column) => Err(SchemaError::Type {
+                column: self.names[column].clone(),
+                expected: self.types[column],
+                found: types[column],
+            }),
+            None => Ok(()),
+        }
+    }
+}
+
+/// The names in `a` that are not in `b`
+fn difference(a: &[String], b: &[String]) -> Vec<String> {
+    a.iter().filter(|name| !b.contains(name)).cloned().collect()
+}
+
+/// The kind of values a feature holds
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum FeatureType {
+    /// Integers or floating point numbers
+    Numeric,
+    /// True or false
+    Boolean,
+    /// Strings
+    Text,
+    /// Anything else, such as dates
+    Other,
+}
+
+impl Display for FeatureType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FeatureType::Numeric => write!(f, "numeric"),
+            FeatureType::Boolean => write!(f, "boolean"),
+            FeatureType::Text => write!(f, "text"),
+            FeatureType::Other => write!(f, "other"),
+        }
+    }
+}
+
+/// Describes how data passed in for prediction differs from the schema
+/// ```
+/// # use automl::{SupervisedModel, Settings};
+/// use automl::{schema::SchemaError, settings::Algorithm};
+/// let mut model = SupervisedModel::new_from_dataset(
+///     smartcore::dataset::diabetes::load_dataset(),
+///     Settings::default_regression().only(Algorithm::Linear),
+/// );
+/// model.train();
+/// let error = model.try_predict_from_vec(vec![vec![0.0; 9]]).unwrap_err();
+/// assert_eq!(
+///     error,
+///     SchemaError::RowLength { row: 0, expected: 10, found: 9 }
+/// );
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SchemaError {
+    /// The data has the wrong number of columns
+    NumberOfColumns {
+        /// The number of features in the schema
+        expected: usize,
+        /// The number of columns in the data
+        found: usize,
+    },
+    /// A row has the wrong number of values
+    RowLength {
+        /// The index of the first row with the wrong length
+        row: usize,
+        /// The number of features in the schema
+        expected: usize,
+        /// The number of values in the row
+        found: usize,
+    },
+    /// The column names do not match the schema
+    Columns {
+        /// Features in the schema that are not in the data
+        missing: Vec<String>,
+        /// Columns in the data that are not in the schema
+        extra: Vec<String>,
+        /// Features that are present but in a different position
+        out_of_order: Vec<String>,
+    },
+    /// A column holds a different type of value than the model was trained on
+    Type {
+        /// The name of the feature
+        column: String,
+        /// The type in the schema
+        expected: FeatureType,
+        /// The type in the data
+        found: FeatureType,
+    },
+    /// A column has missing values, which the models cannot handle
+    MissingValues {
+        /// The name of the column
+        column: String,
+    },
+    /// The file could not be opened or parsed
+    Unreadable {
+        /// The path of the file
+        file: String,
+        /// Why it could not be read
+        reason: String,
+    },
+}
+
+impl Display for SchemaError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SchemaError::NumberOfColumns { expected, found } => {
+                write!(f, "Expected {} columns but got {}.", expected, found)
+            }
+            SchemaError::RowLength {
+                row,
+                expected,
+                found,
+            } => write!(
+                f,
+                "Expected {} values in every row but row {} has {}.",
+                expected, row, found
+            ),
+            SchemaError::Columns {
+                missing,
+                extra,
+                out_of_order,
+            } => {
+                write!(f, "The columns do not match the schema.")?;
+                for (label, names) in [
+                    ("Missing", missing),
+                    ("Extra", extra),
+                    ("Out of order", out_of_order),
+                ] {
+                    if !names.is_empty() {
+                        write!(f, " {}: {}.", label, names.join(", "))?;
+                    }
+                }
+                Ok(())
+            }
+            SchemaError::Type {
+                column,
+                expected,
+                found,
+            } => write!(
+                f,
+                "Expected column {} to be {} but it is {}.",
+                column, expected, found
+            ),
+            SchemaError::MissingValues { column } => {
+                write!(f, "Column {} has missing values.", column)
+            }
+            SchemaError::Unreadable { file, reason } => {
+                write!(f, "Cannot read {}: {}", file, reason)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SchemaError {}
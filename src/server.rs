@@ -0,0 +1,368 @@
+//! # Prediction server
+//! This module contains [`PredictionServer`], a small HTTP server that loads a saved model or
+//! predictor and answers prediction requests. It has three endpoints:
+//! * `POST /predict` takes rows as JSON, either arrays of values in schema order or objects keyed
+//!   by feature name, optionally wrapped as `{"rows": [...]}`. A body sent with a `text/csv`
+//!   content type is read as CSV with a header instead. The response is
+//!   `{"predictions": [...]}`.
+//! * `GET /health` answers `{"status": "ok"}` once a model is loaded.
+//! * `GET /metadata` describes the model: the algorithm, the metric it was compared with, its
+//!   cross-validated scores, which are only available for full models, and the feature schema.
+//!
+//! CSV bodies are parsed with the CSV options the model was built with, in the same way as batch
+//! prediction from a file.
+//!
+//! The model file is checked for changes before each request and reloaded if it has been
+//! replaced, so a newly trained model can be deployed by overwriting the file. If the new file
+//! cannot be loaded, the previous model keeps serving and any [`ServerObserver`] is told why.
+//! Requests are handled one at a time.
+//! ```no_run
+//! use automl::server::PredictionServer;
+//! let server = PredictionServer::new("model.aml", "127.0.0.1:8080").unwrap();
+//! server.run();
+//! ```
+
+use crate::file_format::{read_header, Contents, LoadError};
+use crate::predictor::Predictor;
+use crate::SupervisedModel;
+use serde_json::{json, Value};
+use std::fmt::{Display, Formatter};
+use std::net::SocketAddr;
+use std::sync::RwLock;
+use std::time::SystemTime;
+use tiny_http::{Header, Method, Request, Response, Server};
+
+/// An HTTP server that predicts with a saved model
+pub struct PredictionServer {
+    /// The file the model is loaded from
+    model_file: String,
+    /// The model currently being served
+    loaded: RwLock<Loaded>,
+    /// The underlying HTTP server
+    server: Server,
+    /// Notified of problems that do not stop the server
+    observers: Vec<Box<dyn ServerObserver>>,
+}
+
+/// Receives events from a running [`PredictionServer`] that it cannot report in a response.
+///
+/// Every method has an empty default implementation, so only the events of interest need to be
+/// implemented.
+pub trait ServerObserver: Send + Sync {
+    /// Called when the model file has changed but cannot be loaded, so the previous model keeps
+    /// serving
+    fn on_reload_error(&self, _model_file: &str, _error: &ServeError) {}
+
+    /// Called when a response cannot be sent to a client
+    fn on_response_error(&self, _error: &std::io::Error) {}
+}
+
+/// A model loaded from file, with what the metadata endpoint reports about it
+struct Loaded {
+    /// The model, stripped down for prediction
+    predictor: Predictor,
+    /// The response to `GET /metadata`
+    metadata: Value,
+    /// When the model file was last modified
+    modified: Option<SystemTime>,
+}
+
+impl PredictionServer {
+    /// Load a model or predictor file and listen on an address. Use port 0 to pick a free port.
+    /// ```
+    /// # use automl::{SupervisedModel, Settings};
+    /// use automl::server::PredictionServer;
+    /// let mut model = SupervisedModel::new_from_dataset(
+    ///     smartcore::dataset::diabetes::load_dataset(),
+    ///     Settings::default_regression(),
+    /// );
+    /// model.train();
+    /// model.save("tests/served_model.aml");
+    /// let server = PredictionServer::new("tests/served_model.aml", "127.0.0.1:0").unwrap();
+    /// # std::fs::remove_file("tests/served_model.aml");
+    /// ```
+    pub fn new(model_file: &str, address: &str) -> Result<Self, ServeError> {
+        let loaded = Loaded::new(model_file)?;
+        let server = Server::http(address).map_err(|error| ServeError::Bind(error.to_string()))?;
+        Ok(Self {
+            model_file: model_file.to_string(),
+            loaded: RwLock::new(loaded),
+            server,
+            observers: vec![],
+        })
+    }
+
+    /// Attach an observer that is told about problems the server carries on after, such as a
+    /// replaced model file that cannot be loaded
+    /// ```
+    /// # use automl::{SupervisedModel, Settings};
+    /// use automl::server::{PredictionServer, ServeError, ServerObserver};
+    /// struct Logger;
+    /// impl ServerObserver for Logger {
+    ///     fn on_reload_error(&self, model_file: &str, error: &ServeError) {
+    ///         eprintln!("Cannot reload {}: {}", model_file, error);
+    ///     }
+    /// }
+    ///
+    /// # let mut model = SupervisedModel::new_from_dataset(
+    /// #     smartcore::dataset::diabetes::load_dataset(),
+    /// #     Settings::default_regression(),
+    /// # );
+    /// # model.train();
+    /// # model.save("tests/observed_model.aml");
+    /// let server = PredictionServer::new("tests/observed_model.aml", "127.0.0.1:0")
+    ///     .unwrap()
+    ///     .with_observer(Logger);
+    /// # std::fs::remove_file("tests/observed_model.aml");
+    /// ```
+    pub fn with_observer(mut self, observer: impl ServerObserver + 'static) -> Self {
+        self.observers.push(Box::new(observer));
+        self
+    }
+
+    /// Get the address the server is listening on
+    /// ```
+    /// # use automl::{SupervisedModel, Settings};
+    /// use automl::server::PredictionServer;
+    /// let mut model = SupervisedModel::new_from_dataset(
+    ///     smartcore::dataset::diabetes::load_dataset(),
+    ///     Settings::default_regression(),
+    /// );
+    /// model.train();
+    /// model.save("tests/addressed_model.aml");
+    /// let server = PredictionServer::new("tests/addressed_model.aml", "127.0.0.1:0").unwrap();
+    /// assert!(server.address().ip().is_loopback());
+    /// # std::fs::remove_file("tests/addressed_model.aml");
+    /// ```
+    pub fn address(&self) -> SocketAddr {
+        self.server.server_addr()
+    }
+
+    /// Handle requests until [`stop`](Self::stop) is called
+    /// ```no_run
+    /// use automl::server::PredictionServer;
+    /// let server = PredictionServer::new("model.aml", "127.0.0.1:8080").unwrap();
+    /// server.run();
+    /// ```
+    pub fn run(&self) {
+        for request in self.server.incoming_requests() {
+            self.reload_if_changed();
+            self.handle(request);
+        }
+    }
+
+    /// Stop a server that is running on another thread
+    /// ```
+    /// # use automl::{SupervisedModel, Settings};
+    /// use automl::server::PredictionServer;
+    /// use std::sync::Arc;
+    /// let mut model = SupervisedModel::new_from_dataset(
+    ///     smartcore::dataset::diabetes::load_dataset(),
+    ///     Settings::default_regression(),
+    /// );
+    /// model.train();
+    /// model.save("tests/stopped_model.aml");
+    /// let server = Arc::new(PredictionServer::new("tests/stopped_model.aml", "127.0.0.1:0").unwrap());
+    /// let running = server.clone();
+    /// let handle = std::thread::spawn(move || running.run());
+    /// server.stop();
+    /// handle.join().unwrap();
+    /// # std::fs::remove_file("tests/stopped_model.aml");
+    /// ```
+    pub fn stop(&self) {
+        self.server.unblock();
+    }
+
+    /// Reload the model if its file has been modified since it was loaded
+    fn reload_if_changed(&self) {
+        let modified = modified(&self.model_file);
+        if modified == self.loaded.read().unwrap().modified {
+            return;
+        }
+        match Loaded::new(&self.model_file) {
+            Ok(loaded) => *self.loaded.write().unwrap() = loaded,
+            Err(error) => {
+                for observer in &self.observers {
+                    observer.on_reload_error(&self.model_file, &error);
+                }
+            }
+        }
+    }
+
+    /// Answer a single request
+    fn handle(&self, mut request: Request) {
+        let (status, body) = match (request.method(), request.url()) {
+            (Method::Get, "/health") => (200, json!({"status": "ok"})),
+            (Method::Get, "/metadata") => (200, self.loaded.read().unwrap().metadata.clone()),
+            (Method::Post, "/predict") => {
+                let csv = request.headers().iter().any(|header| {
+                    header.field.equiv("Content-Type")
+                        && header.value.as_str().starts_with("text/csv")
+                });
+                let mut text = String::new();
+                match request.as_reader().read_to_string(&mut text) {
+                    Ok(_) => self.predict(&text, csv),
+                    Err(error) => (400, json!({ "error": error.to_string() })),
+                }
+            }
+            (_, "/health" | "/metadata" | "/predict") => {
+                (405, json!({"error": "Method not allowed"}))
+            }
+            _ => (404, json!({"error": "Not found"})),
+        };
+        let response = Response::from_string(body.to_string())
+            .with_status_code(status)
+            .with_header(
+                Header::from_bytes(&b"Content-Type"[..], &b"application/json"[..]).unwrap(),
+            );
+        if let Err(error) = request.respond(response) {
+            for observer in &self.observers {
+                observer.on_response_error(&error);
+            }
+        }
+    }
+
+    /// Predict the rows in a request body
+    fn predict(&self, body: &str, csv: bool) -> (u16, Value) {
+        let predictor = &self.loaded.read().unwrap().predictor;
+        let predictions = if csv {
+            predictor
+                .predict_from_csv_text(body)
+                .map_err(|error| error.to_string())
+        } else {
+            parse_json(body, predictor.schema().names()).and_then(|rows| {
+                predictor
+                    .try_predict_from_vec(rows)
+                    .map_err(|error| error.to_string())
+            })
+        };
+        match predictions {
+            Ok(predictions) => (200, json!({ "predictions": predictions })),
+            Err(error) => (400, json!({ "error": error })),
+        }
+    }
+}
+
+impl Loaded {
+    /// Load a model or predictor file and describe it
+    fn new(model_file: &str) -> Result<Self, ServeError> {
+        let modified = modified(model_file);
+        let header = read_header(model_file)?;
+        let (predictor, scores) = match header.contents() {
+            Contents::SupervisedModel => {
+                let model = SupervisedModel::try_new_from_file(model_file)?;
+                let predictor = model.export_predictor();
+                let report = model.report();
+                let scores = report
+                    .meta_model()
+                    .or_else(|| {
+                        report
+                            .leaderboard()
+                            .iter()
+                            .find(|entry| entry.algorithm() == predictor.algorithm())
+                    })
+                    .map(|entry| {
+                        json!({
+                            "train": entry.mean_train_score(),
+                            "test": entry.mean_test_score(),
+                        })
+                    });
+                (predictor, scores)
+            }
+            Contents::Predictor => (Predictor::try_new_from_file(model_file)?, None),
+        };
+        let schema = predictor.schema();
+        let metadata = json!({
+            "contents": header.contents().to_string(),
+            "algorithm": predictor.algorithm().to_string(),
+            "metric": predictor.metric().to_string(),
+            "scores": scores,
+            "features": schema
+                .names()
+                .iter()
+                .zip(schema.types())
+                .map(|(name, dtype)| json!({"name": name, "type": dtype.to_string()}))
+                .collect::<Vec<Value>>(),
+            "crate_version": header.crate_version(),
+            "format_version": header.format_version(),
+        });
+        Ok(Self {
+            predictor,
+            metadata,
+            modified,
+        })
+    }
+}
+
+/// Get when a file was last modified
+fn modified(file_name: &str) -> Option<SystemTime> {
+    std::fs::metadata(file_name)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+/// Read JSON rows, given as arrays in schema order or as objects keyed by feature name
+fn parse_json(body: &str, names: &[String]) -> Result<Vec<Vec<f32>>, String> {
+    let value: Value = serde_json::from_str(body).map_err(|error| error.to_string())?;
+    let rows = match &value {
+        Value::Object(object) => object.get("rows"),
+        _ => Some(&value),
+    }
+    .and_then(Value::as_array)
+    .ok_or("Expected an array of rows or an object with a \"rows\" array")?;
+    rows.iter()
+        .enumerate()
+        .map(|(index, row)| match row {
+            Value::Array(values) => values
+                .iter()
+                .map(|value| number(value, index))
+                .collect::<Result<Vec<f32>, String>>(),
+            Value::Object(values) => names
+                .iter()
+                .map(|name| match values.get(name) {
+                    Some(value) => number(value, index),
+                    None => Err(format!("Row {} is missing {}", index, name)),
+                })
+                .collect::<Result<Vec<f32>, String>>(),
+            _ => Err(format!("Row {} is not an array or an object", index)),
+        })
+        .collect()
+}
+
+/// Read a JSON value as a number
+fn number(value: &Value, row: usize) -> Result<f32, String> {
+    match value {
+        Value::Number(number) => Ok(number.as_f64().unwrap() as f32),
+        Value::Bool(flag) => Ok(if *flag { 1.0 } else { 0.0 }),
+        _ => Err(format!(
+            "Row {} has a value that is not a number: {}",
+            row, value
+        )),
+    }
+}
+
+/// Reasons a server cannot start, or cannot reload its model
+#[derive(Debug)]
+pub enum ServeError {
+    /// The model file cannot be loaded
+    Load(LoadError),
+    /// The server cannot listen on the address
+    Bind(String),
+}
+
+impl Display for ServeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ServeError::Load(error) => write!(f, "{}", error),
+            ServeError::Bind(error) => write!(f, "Cannot listen on the address: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for ServeError {}
+
+impl From<LoadError> for ServeError {
+    fn from(error: LoadError) -> Self {
+        ServeError::Load(error)
+    }
+}
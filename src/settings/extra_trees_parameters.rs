@@ -0,0 +1,82 @@
+//! Parameters for extremely randomized trees
+
+/// Parameters for extremely randomized trees, shared by regression and classification
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+pub struct ExtraTreesParameters {
+    /// The maximum depth of each tree, if limited
+    pub(crate) max_depth: Option<u16>,
+    /// The minimum number of samples in a leaf
+    pub(crate) min_samples_leaf: usize,
+    /// The minimum number of samples needed to split a node
+    pub(crate) min_samples_split: usize,
+    /// The number of trees in the forest
+    pub(crate) n_trees: u16,
+    /// The number of features tried at each split, or the default for the task
+    pub(crate) m: Option<usize>,
+}
+
+impl ExtraTreesParameters {
+    /// Define the maximum depth of each tree
+    /// ```
+    /// # use automl::settings::ExtraTreesParameters;
+    /// let parameters = ExtraTreesParameters::default().with_max_depth(10);
+    /// ```
+    pub fn with_max_depth(mut self, max_depth: u16) -> Self {
+        self.max_depth = Some(max_depth);
+        self
+    }
+
+    /// Define the minimum number of samples in a leaf
+    /// ```
+    /// # use automl::settings::ExtraTreesParameters;
+    /// let parameters = ExtraTreesParameters::default().with_min_samples_leaf(5);
+    /// ```
+    pub fn with_min_samples_leaf(mut self, min_samples_leaf: usize) -> Self {
+        self.min_samples_leaf = min_samples_leaf;
+        self
+    }
+
+    /// Define the minimum number of samples needed to split a node
+    /// ```
+    /// # use automl::settings::ExtraTreesParameters;
+    /// let parameters = ExtraTreesParameters::default().with_min_samples_split(10);
+    /// ```
+    pub fn with_min_samples_split(mut self, min_samples_split: usize) -> Self {
+        self.min_samples_split = min_samples_split;
+        self
+    }
+
+    /// Define the number of trees in the forest
+    /// ```
+    /// # use automl::settings::ExtraTreesParameters;
+    /// let parameters = ExtraTreesParameters::default().with_n_trees(200);
+    /// ```
+    pub fn with_n_trees(mut self, n_trees: u16) -> Self {
+        assert!(n_trees > 0, "A forest needs at least one tree.");
+        self.n_trees = n_trees;
+        self
+    }
+
+    /// Define the number of randomly chosen features tried at each split. By default regression
+    /// tries every feature and classification the square root of the number of features.
+    /// ```
+    /// # use automl::settings::ExtraTreesParameters;
+    /// let parameters = ExtraTreesParameters::default().with_m(3);
+    /// ```
+    pub fn with_m(mut self, m: usize) -> Self {
+        self.m = Some(m);
+        self
+    }
+}
+
+impl Default for ExtraTreesParameters {
+    fn default() -> Self {
+        Self {
+            max_depth: None,
+            min_samples_leaf: 1,
+            min_samples_split: 2,
+            n_trees: 100,
+            m: None,
+        }
+    }
+}
@@ -0,0 +1,75 @@
+//! Parameters for generalized linear models
+
+/// Parameters for generalized linear models with a log link, shared by Poisson, gamma and Tweedie
+/// regression
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+pub struct GLMParameters {
+    /// The strength of the L2 penalty on the coefficients of the standardized features
+    pub(crate) alpha: f32,
+    /// The maximum number of Newton steps
+    pub(crate) max_iter: usize,
+    /// The largest gradient entry at which the fit has converged
+    pub(crate) tol: f32,
+    /// The power of the Tweedie distribution, used by Tweedie regression only
+    pub(crate) power: f32,
+}
+
+impl GLMParameters {
+    /// Define the strength of the L2 penalty. The features are standardized first, so the penalty
+    /// treats them equally whatever their units, and the intercept is never penalized.
+    /// ```
+    /// # use automl::settings::GLMParameters;
+    /// let parameters = GLMParameters::default().with_alpha(0.1);
+    /// ```
+    pub fn with_alpha(mut self, alpha: f32) -> Self {
+        assert!(alpha >= 0.0, "The L2 penalty cannot be negative.");
+        self.alpha = alpha;
+        self
+    }
+
+    /// Define the maximum number of Newton steps
+    /// ```
+    /// # use automl::settings::GLMParameters;
+    /// let parameters = GLMParameters::default().with_max_iter(300);
+    /// ```
+    pub fn with_max_iter(mut self, max_iter: usize) -> Self {
+        self.max_iter = max_iter;
+        self
+    }
+
+    /// Define the largest gradient entry at which the fit has converged
+    /// ```
+    /// # use automl::settings::GLMParameters;
+    /// let parameters = GLMParameters::default().with_tol(1e-6);
+    /// ```
+    pub fn with_tol(mut self, tol: f32) -> Self {
+        assert!(tol > 0.0, "The tolerance must be greater than zero.");
+        self.tol = tol;
+        self
+    }
+
+    /// Define the power of the Tweedie distribution. Powers between one and two give compound
+    /// Poisson-gamma targets that can be exactly zero, and powers of two or more need positive
+    /// targets. Poisson regression always uses a power of one and gamma regression a power of two.
+    /// Powers below one are not supported, and Tweedie regression is skipped with the reason in
+    /// the leaderboard when given one.
+    /// ```
+    /// # use automl::settings::GLMParameters;
+    /// let parameters = GLMParameters::default().with_power(1.2);
+    /// ```
+    pub fn with_power(mut self, power: f32) -> Self {
+        self.power = power;
+        self
+    }
+}
+
+impl Default for GLMParameters {
+    fn default() -> Self {
+        Self {
+            alpha: 0.0001,
+            max_iter: 100,
+            tol: 1e-4,
+            power: 1.5,
+        }
+    }
+}
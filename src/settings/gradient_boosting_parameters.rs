@@ -0,0 +1,137 @@
+//! Parameters for gradient boosted trees
+
+/// Parameters for gradient boosted trees, shared by regression and classification
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+pub struct GradientBoostingParameters {
+    /// How much each tree contributes to the prediction
+    pub(crate) learning_rate: f32,
+    /// The maximum number of boosting rounds
+    pub(crate) n_estimators: usize,
+    /// The maximum depth of each tree
+    pub(crate) max_depth: u16,
+    /// The minimum number of samples in a leaf of each tree
+    pub(crate) min_samples_leaf: usize,
+    /// The minimum number of samples needed to split a node of each tree
+    pub(crate) min_samples_split: usize,
+    /// The fraction of the training rows each tree is fit to
+    pub(crate) subsample: f32,
+    /// The fraction of the training rows held out for early stopping, if any
+    pub(crate) validation_fraction: Option<f32>,
+    /// The number of rounds without improvement before stopping early
+    pub(crate) n_iter_no_change: usize,
+}
+
+impl GradientBoostingParameters {
+    /// Define how much each tree contributes to the prediction
+    /// ```
+    /// # use automl::settings::GradientBoostingParameters;
+    /// let parameters = GradientBoostingParameters::default().with_learning_rate(0.05);
+    /// ```
+    pub fn with_learning_rate(mut self, learning_rate: f32) -> Self {
+        assert!(
+            learning_rate > 0.0,
+            "The learning rate must be greater than zero."
+        );
+        self.learning_rate = learning_rate;
+        self
+    }
+
+    /// Define the maximum number of boosting rounds
+    /// ```
+    /// # use automl::settings::GradientBoostingParameters;
+    /// let parameters = GradientBoostingParameters::default().with_n_estimators(500);
+    /// ```
+    pub fn with_n_estimators(mut self, n_estimators: usize) -> Self {
+        self.n_estimators = n_estimators;
+        self
+    }
+
+    /// Define the maximum depth of each tree
+    /// ```
+    /// # use automl::settings::GradientBoostingParameters;
+    /// let parameters = GradientBoostingParameters::default().with_max_depth(5);
+    /// ```
+    pub fn with_max_depth(mut self, max_depth: u16) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Define the minimum number of samples in a leaf of each tree
+    /// ```
+    /// # use automl::settings::GradientBoostingParameters;
+    /// let parameters = GradientBoostingParameters::default().with_min_samples_leaf(5);
+    /// ```
+    pub fn with_min_samples_leaf(mut self, min_samples_leaf: usize) -> Self {
+        self.min_samples_leaf = min_samples_leaf;
+        self
+    }
+
+    /// Define the minimum number of samples needed to split a node of each tree
+    /// ```
+    /// # use automl::settings::GradientBoostingParameters;
+    /// let parameters = GradientBoostingParameters::default().with_min_samples_split(10);
+    /// ```
+    pub fn with_min_samples_split(mut self, min_samples_split: usize) -> Self {
+        self.min_samples_split = min_samples_split;
+        self
+    }
+
+    /// Define the fraction of the training rows, drawn without replacement, that each tree is fit
+    /// to. Values below one give stochastic gradient boosting.
+    /// ```
+    /// # use automl::settings::GradientBoostingParameters;
+    /// let parameters = GradientBoostingParameters::default().with_subsample(0.8);
+    /// ```
+    pub fn with_subsample(mut self, subsample: f32) -> Self {
+        assert!(
+            subsample > 0.0 && subsample <= 1.0,
+            "The subsample fraction must be greater than zero and at most one."
+        );
+        self.subsample = subsample;
+        self
+    }
+
+    /// Stop adding trees once the loss on a held out fraction of the training rows has not
+    /// improved for [`n_iter_no_change`](Self::with_n_iter_no_change) rounds, and keep only the
+    /// trees up to the best round
+    /// ```
+    /// # use automl::settings::GradientBoostingParameters;
+    /// let parameters = GradientBoostingParameters::default()
+    ///     .with_validation_fraction(0.1)
+    ///     .with_n_iter_no_change(5);
+    /// ```
+    pub fn with_validation_fraction(mut self, validation_fraction: f32) -> Self {
+        assert!(
+            validation_fraction > 0.0 && validation_fraction < 1.0,
+            "The validation fraction must be between zero and one."
+        );
+        self.validation_fraction = Some(validation_fraction);
+        self
+    }
+
+    /// Define how many rounds without improvement on the validation rows are allowed before
+    /// stopping early. This has no effect unless a validation fraction is set.
+    /// ```
+    /// # use automl::settings::GradientBoostingParameters;
+    /// let parameters = GradientBoostingParameters::default().with_n_iter_no_change(5);
+    /// ```
+    pub fn with_n_iter_no_change(mut self, n_iter_no_change: usize) -> Self {
+        self.n_iter_no_change = n_iter_no_change;
+        self
+    }
+}
+
+impl Default for GradientBoostingParameters {
+    fn default() -> Self {
+        Self {
+            learning_rate: 0.1,
+            n_estimators: 100,
+            max_depth: 3,
+            min_samples_leaf: 1,
+            min_samples_split: 2,
+            subsample: 1.0,
+            validation_fraction: None,
+            n_iter_no_change: 10,
+        }
+    }
+}
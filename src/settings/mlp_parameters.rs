@@ -0,0 +1,167 @@
+//! Parameters for multilayer perceptrons
+
+use crate::utils::{Activation, Optimizer};
+
+/// Parameters for multilayer perceptrons, shared by regression and classification
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+pub struct MLPParameters {
+    /// The number of neurons in each hidden layer
+    pub(crate) hidden_layers: Vec<usize>,
+    /// The activation of the hidden layers
+    pub(crate) activation: Activation,
+    /// The rule used to update the weights
+    pub(crate) optimizer: Optimizer,
+    /// The step size of the optimizer
+    pub(crate) learning_rate: f32,
+    /// The strength of the L2 penalty on the weights
+    pub(crate) alpha: f32,
+    /// The number of rows in each minibatch, if set
+    pub(crate) batch_size: Option<usize>,
+    /// The maximum number of passes over the training rows
+    pub(crate) max_iter: usize,
+    /// The smallest decrease in loss that counts as an improvement
+    pub(crate) tol: f32,
+    /// The fraction of the training rows held out to judge improvement, if any
+    pub(crate) validation_fraction: Option<f32>,
+    /// The number of passes without improvement before stopping early
+    pub(crate) n_iter_no_change: usize,
+}
+
+impl MLPParameters {
+    /// Define the number of neurons in each hidden layer
+    /// ```
+    /// # use automl::settings::MLPParameters;
+    /// let parameters = MLPParameters::default().with_hidden_layers(&[64, 32]);
+    /// ```
+    pub fn with_hidden_layers(mut self, hidden_layers: &[usize]) -> Self {
+        assert!(
+            hidden_layers.iter().all(|neurons| *neurons > 0),
+            "Every hidden layer needs at least one neuron."
+        );
+        self.hidden_layers = hidden_layers.to_vec();
+        self
+    }
+
+    /// Define the activation function of the hidden layers
+    /// ```
+    /// # use automl::settings::MLPParameters;
+    /// use automl::settings::Activation;
+    /// let parameters = MLPParameters::default().with_activation(Activation::Tanh);
+    /// ```
+    pub fn with_activation(mut self, activation: Activation) -> Self {
+        self.activation = activation;
+        self
+    }
+
+    /// Define the optimizer used to update the weights
+    /// ```
+    /// # use automl::settings::MLPParameters;
+    /// use automl::settings::Optimizer;
+    /// let parameters = MLPParameters::default().with_optimizer(Optimizer::SGD(0.9));
+    /// ```
+    pub fn with_optimizer(mut self, optimizer: Optimizer) -> Self {
+        self.optimizer = optimizer;
+        self
+    }
+
+    /// Define the step size of the optimizer
+    /// ```
+    /// # use automl::settings::MLPParameters;
+    /// let parameters = MLPParameters::default().with_learning_rate(0.01);
+    /// ```
+    pub fn with_learning_rate(mut self, learning_rate: f32) -> Self {
+        assert!(
+            learning_rate > 0.0,
+            "The learning rate must be greater than zero."
+        );
+        self.learning_rate = learning_rate;
+        self
+    }
+
+    /// Define the strength of the L2 penalty on the weights
+    /// ```
+    /// # use automl::settings::MLPParameters;
+    /// let parameters = MLPParameters::default().with_alpha(0.01);
+    /// ```
+    pub fn with_alpha(mut self, alpha: f32) -> Self {
+        assert!(alpha >= 0.0, "The L2 penalty cannot be negative.");
+        self.alpha = alpha;
+        self
+    }
+
+    /// Define the number of rows in each minibatch. By default batches have 200 rows, or all of
+    /// the rows if there are fewer.
+    /// ```
+    /// # use automl::settings::MLPParameters;
+    /// let parameters = MLPParameters::default().with_batch_size(32);
+    /// ```
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        assert!(batch_size > 0, "A batch needs at least one row.");
+        self.batch_size = Some(batch_size);
+        self
+    }
+
+    /// Define the maximum number of passes over the training rows
+    /// ```
+    /// # use automl::settings::MLPParameters;
+    /// let parameters = MLPParameters::default().with_max_iter(500);
+    /// ```
+    pub fn with_max_iter(mut self, max_iter: usize) -> Self {
+        self.max_iter = max_iter;
+        self
+    }
+
+    /// Define the smallest decrease in loss that counts as an improvement
+    /// ```
+    /// # use automl::settings::MLPParameters;
+    /// let parameters = MLPParameters::default().with_tol(1e-5);
+    /// ```
+    pub fn with_tol(mut self, tol: f32) -> Self {
+        self.tol = tol;
+        self
+    }
+
+    /// Judge improvement on a held out fraction of the training rows instead of on the training
+    /// loss, and keep the weights from the best pass over the data
+    /// ```
+    /// # use automl::settings::MLPParameters;
+    /// let parameters = MLPParameters::default()
+    ///     .with_validation_fraction(0.1)
+    ///     .with_n_iter_no_change(5);
+    /// ```
+    pub fn with_validation_fraction(mut self, validation_fraction: f32) -> Self {
+        assert!(
+            validation_fraction > 0.0 && validation_fraction < 1.0,
+            "The validation fraction must be between zero and one."
+        );
+        self.validation_fraction = Some(validation_fraction);
+        self
+    }
+
+    /// Define how many passes over the data without improvement are allowed before stopping early
+    /// ```
+    /// # use automl::settings::MLPParameters;
+    /// let parameters = MLPParameters::default().with_n_iter_no_change(5);
+    /// ```
+    pub fn with_n_iter_no_change(mut self, n_iter_no_change: usize) -> Self {
+        self.n_iter_no_change = n_iter_no_change;
+        self
+    }
+}
+
+impl Default for MLPParameters {
+    fn default() -> Self {
+        Self {
+            hidden_layers: vec![100],
+            activation: Activation::default(),
+            optimizer: Optimizer::default(),
+            learning_rate: 0.001,
+            alpha: 0.0001,
+            batch_size: None,
+            max_iter: 200,
+            tol: 1e-4,
+            validation_fraction: None,
+            n_iter_no_change: 10,
+        }
+    }
+}
@@ -3,11 +3,14 @@
 //! ## Complete regression customization
 //! ```
 //! use automl::settings::{
-//!     Algorithm, DecisionTreeRegressorParameters, Distance, ElasticNetParameters,
+//!     Activation, Algorithm, DecisionTreeRegressorParameters, Distance, ElasticNetParameters,
+//!     ExtraTreesRegressorParameters, GammaRegressorParameters, GradientBoostingParameters,
+//!     GradientBoostingQuantileRegressorParameters, GradientBoostingRegressorParameters,
 //!     KNNAlgorithmName, KNNRegressorParameters, KNNWeightFunction, Kernel, LassoParameters,
-//!     LinearRegressionParameters, LinearRegressionSolverName, Metric,
+//!     LinearRegressionParameters, LinearRegressionSolverName, MLPRegressorParameters, Metric,
+//!     Optimizer, PoissonRegressorParameters, QuantileRegressorParameters,
 //!     RandomForestRegressorParameters, RidgeRegressionParameters, RidgeRegressionSolverName,
-//!     SVRParameters,
+//!     SVRParameters, TweedieRegressorParameters,
 //!  };
 //!
 //!  let settings = automl::Settings::default_regression()
@@ -67,14 +70,61 @@
 //!             .with_min_samples_split(20)
 //!             .with_max_depth(5)
 //!             .with_min_samples_leaf(20),
+//!     )
+//!     .with_gradient_boosting_regressor_settings(
+//!         GradientBoostingRegressorParameters::default()
+//!             .with_learning_rate(0.05)
+//!             .with_n_estimators(200)
+//!             .with_max_depth(4)
+//!             .with_subsample(0.8)
+//!             .with_validation_fraction(0.1)
+//!             .with_n_iter_no_change(10),
+//!     )
+//!     .with_extra_trees_regressor_settings(
+//!         ExtraTreesRegressorParameters::default()
+//!             .with_n_trees(100)
+//!             .with_max_depth(10)
+//!             .with_min_samples_leaf(2)
+//!             .with_m(5),
+//!     )
+//!     .with_mlp_regressor_settings(
+//!         MLPRegressorParameters::default()
+//!             .with_hidden_layers(&[64, 32])
+//!             .with_activation(Activation::ReLU)
+//!             .with_optimizer(Optimizer::Adam)
+//!             .with_learning_rate(0.001)
+//!             .with_alpha(0.0001)
+//!             .with_batch_size(32)
+//!             .with_max_iter(200)
+//!             .with_validation_fraction(0.1),
+//!     )
+//!     .with_poisson_regressor_settings(
+//!         PoissonRegressorParameters::default()
+//!             .with_alpha(0.0001)
+//!             .with_max_iter(100)
+//!             .with_tol(1e-4),
+//!     )
+//!     .with_gamma_regressor_settings(GammaRegressorParameters::default().with_alpha(0.01))
+//!     .with_tweedie_regressor_settings(TweedieRegressorParameters::default().with_power(1.5))
+//!     .with_quantile_regressor_settings(
+//!         QuantileRegressorParameters::default()
+//!             .with_quantile(0.5)
+//!             .with_alpha(0.0001),
+//!     )
+//!     .with_gradient_boosting_quantile_regressor_settings(
+//!         GradientBoostingQuantileRegressorParameters::default()
+//!             .with_quantile(0.9)
+//!             .with_boosting(GradientBoostingParameters::default().with_n_estimators(200)),
 //!     );
 //! ```
 //! ## Complete classification customization
 //! ```
 //! use automl::settings::{
-//!     Algorithm, CategoricalNBParameters, DecisionTreeClassifierParameters, Distance,
-//!     GaussianNBParameters, KNNAlgorithmName, KNNClassifierParameters, KNNWeightFunction, Kernel,
-//!     LogisticRegressionParameters, Metric, RandomForestClassifierParameters, SVCParameters,
+//!     Activation, Algorithm, CategoricalNBParameters, DecisionTreeClassifierParameters, Distance,
+//!     ExtraTreesClassifierParameters, GaussianNBParameters, GradientBoostingClassifierParameters,
+//!     KNNAlgorithmName, KNNClassifierParameters, KNNWeightFunction, Kernel,
+//!     LogisticRegressionParameters, MLPClassifierParameters, Metric, Optimizer,
+//!     RandomForestClassifierParameters, SVCParameters,
 //! };
 //!
 //! let settings = automl::Settings::default_classification()
@@ -113,10 +163,31 @@
 //!             .with_weight(KNNWeightFunction::Uniform),
 //!     )
 //!     .with_gaussian_nb_settings(GaussianNBParameters::default().with_priors(vec![1.0, 1.0]))
-//!     .with_categorical_nb_settings(CategoricalNBParameters::default().with_alpha(1.0));
+//!     .with_categorical_nb_settings(CategoricalNBParameters::default().with_alpha(1.0))
+//!     .with_gradient_boosting_classifier_settings(
+//!         GradientBoostingClassifierParameters::default()
+//!             .with_learning_rate(0.05)
+//!             .with_n_estimators(200)
+//!             .with_max_depth(4)
+//!             .with_subsample(0.8),
+//!     )
+//!     .with_extra_trees_classifier_settings(
+//!         ExtraTreesClassifierParameters::default()
+//!             .with_n_trees(100)
+//!             .with_max_depth(10)
+//!             .with_min_samples_leaf(2),
+//!     )
+//!     .with_mlp_classifier_settings(
+//!         MLPClassifierParameters::default()
+//!             .with_hidden_layers(&[32])
+//!             .with_activation(Activation::Tanh)
+//!             .with_optimizer(Optimizer::SGD(0.9))
+//!             .with_learning_rate(0.01)
+//!             .with_max_iter(100),
+//!     );
 //! ```
 
-pub use crate::utils::{Distance, Kernel};
+pub use crate::utils::{Activation, Distance, Kernel, Optimizer};
 
 /// Weighting functions for k-nearest neighbor (KNN) regression (re-export from [Smartcore](https://docs.rs/smartcore/))
 pub use smartcore::neighbors::KNNWeightFunction;
@@ -173,23 +244,74 @@ mod knn_classifier_parameters;
 pub use knn_classifier_parameters::KNNClassifierParameters;
 
 mod svc_parameters;
-pub use svc_parameters::SVCParameters;
+pub use svc_parameters::{MulticlassStrategy, SVCParameters};
+
+mod gradient_boosting_parameters;
+pub use gradient_boosting_parameters::GradientBoostingParameters;
+
+/// Parameters for gradient boosted tree regression
+pub type GradientBoostingRegressorParameters = GradientBoostingParameters;
+
+/// Parameters for gradient boosted tree classification
+pub type GradientBoostingClassifierParameters = GradientBoostingParameters;
+
+mod extra_trees_parameters;
+pub use extra_trees_parameters::ExtraTreesParameters;
+
+/// Parameters for extremely randomized trees regression
+pub type ExtraTreesRegressorParameters = ExtraTreesParameters;
+
+/// Parameters for extremely randomized trees classification
+pub type ExtraTreesClassifierParameters = ExtraTreesParameters;
+
+mod mlp_parameters;
+pub use mlp_parameters::MLPParameters;
+
+/// Parameters for multilayer perceptron regression
+pub type MLPRegressorParameters = MLPParameters;
+
+/// Parameters for multilayer perceptron classification
+pub type MLPClassifierParameters = MLPParameters;
+
+mod glm_parameters;
+pub use glm_parameters::GLMParameters;
+
+/// Parameters for Poisson regression
+pub type PoissonRegressorParameters = GLMParameters;
+
+/// Parameters for gamma regression
+pub type GammaRegressorParameters = GLMParameters;
+
+/// Parameters for Tweedie regression
+pub type TweedieRegressorParameters = GLMParameters;
+
+mod quantile_parameters;
+pub use quantile_parameters::{
+    GradientBoostingQuantileRegressorParameters, QuantileRegressorParameters,
+};
 
 use smartcore::linalg::naive::dense_matrix::DenseMatrix;
 use std::fmt::{Display, Formatter};
 
 use super::algorithms::{
-    CategoricalNaiveBayesClassifierWrapper, DecisionTreeClassifierWrapper,
-    DecisionTreeRegressorWrapper, ElasticNetRegressorWrapper, GaussianNaiveBayesClassifierWrapper,
-    KNNClassifierWrapper, KNNRegressorWrapper, LassoRegressorWrapper, LinearRegressorWrapper,
-    LogisticRegressionWrapper, ModelWrapper, RandomForestClassifierWrapper,
-    RandomForestRegressorWrapper, RidgeRegressorWrapper, SupportVectorClassifierWrapper,
-    SupportVectorRegressorWrapper,
+    CategoricalNaiveBayesClassifierWrapper, ClassProbabilities, DecisionTreeClassifierWrapper,
+    DecisionTreeRegressorWrapper, ElasticNetRegressorWrapper, ExtraTreesClassifierWrapper,
+    ExtraTreesRegressorWrapper, GammaRegressorWrapper, GaussianNaiveBayesClassifierWrapper,
+    GradientBoostingClassifierWrapper, GradientBoostingQuantileRegressorWrapper,
+    GradientBoostingRegressorWrapper, KNNClassifierWrapper, KNNRegressorWrapper,
+    LassoRegressorWrapper, LinearRegressorWrapper, LogisticRegressionWrapper, MLPClassifierWrapper,
+    MLPRegressorWrapper, ModelWrapper, PoissonRegressorWrapper, QuantileRegressorWrapper,
+    RandomForestClassifierWrapper, RandomForestRegressorWrapper, RidgeRegressorWrapper,
+    SupportVectorClassifierWrapper, SupportVectorRegressorWrapper, TweedieRegressorWrapper,
 };
 
 mod settings_struct;
 #[doc(no_inline)]
 pub use settings_struct::Settings;
+pub(crate) use settings_struct::{
+    add_extra_trees, add_glm, add_gradient_boosting, add_mlp, add_quantile, add_svc_strategy,
+    end_of_settings_v6, end_of_settings_v8,
+};
 
 /// Metrics for evaluating algorithms
 #[non_exhaustive]
@@ -205,6 +327,14 @@ pub enum Metric {
     Accuracy,
     /// Sort by none
     None,
+    /// Sort by mean Poisson deviance, for counts
+    PoissonDeviance,
+    /// Sort by mean gamma deviance, for positive amounts
+    GammaDeviance,
+    /// Sort by mean Tweedie deviance with the given power
+    TweedieDeviance(f32),
+    /// Sort by mean pinball loss at the given quantile, for quantile regression
+    PinballLoss(f32),
 }
 
 impl Display for Metric {
@@ -215,12 +345,16 @@ impl Display for Metric {
             Metric::MeanSquaredError => write!(f, "MSE"),
             Metric::Accuracy => write!(f, "Accuracy"),
             Metric::None => panic!("A metric must be set."),
+            Metric::PoissonDeviance => write!(f, "Poisson deviance"),
+            Metric::GammaDeviance => write!(f, "Gamma deviance"),
+            Metric::TweedieDeviance(power) => write!(f, "Tweedie deviance (power {})", power),
+            Metric::PinballLoss(quantile) => write!(f, "Pinball loss (quantile {})", quantile),
         }
     }
 }
 
 /// Algorithm options
-#[derive(PartialEq, Copy, Clone, serde::Serialize, serde::Deserialize)]
+#[derive(PartialEq, Copy, Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub enum Algorithm {
     /// Decision tree regressor
     DecisionTreeRegressor,
@@ -252,8 +386,33 @@ pub enum Algorithm {
     GaussianNaiveBayes,
     /// Categorical Naive Bayes classifier
     CategoricalNaiveBayes,
+    /// Gradient boosted tree regressor
+    GradientBoostingRegressor,
+    /// Gradient boosted tree classifier
+    GradientBoostingClassifier,
+    /// Extremely randomized trees regressor
+    ExtraTreesRegressor,
+    /// Extremely randomized trees classifier
+    ExtraTreesClassifier,
+    /// Multilayer perceptron regressor
+    MLPRegressor,
+    /// Multilayer perceptron classifier
+    MLPClassifier,
+    /// Poisson regressor with a log link
+    PoissonRegressor,
+    /// Gamma regressor with a log link
+    GammaRegressor,
+    /// Tweedie regressor with a log link
+    TweedieRegressor,
+    /// Linear quantile regressor
+    QuantileRegressor,
+    /// Gradient boosted tree quantile regressor
+    GradientBoostingQuantileRegressor,
 }
 
+/// Predicts class probabilities for some rows with a serialized model
+type ProbabilityPredictor = Box<dyn Fn(&DenseMatrix<f32>, &Vec<u8>) -> Option<ClassProbabilities>>;
+
 impl Algorithm {
     pub(crate) fn get_predictor(
         &self,
@@ -276,6 +435,43 @@ impl Algorithm {
             Algorithm::CategoricalNaiveBayes => {
                 Box::new(CategoricalNaiveBayesClassifierWrapper::predict)
             }
+            Algorithm::GradientBoostingRegressor => {
+                Box::new(GradientBoostingRegressorWrapper::predict)
+            }
+            Algorithm::GradientBoostingClassifier => {
+                Box::new(GradientBoostingClassifierWrapper::predict)
+            }
+            Algorithm::ExtraTreesRegressor => Box::new(ExtraTreesRegressorWrapper::predict),
+            Algorithm::ExtraTreesClassifier => Box::new(ExtraTreesClassifierWrapper::predict),
+            Algorithm::MLPRegressor => Box::new(MLPRegressorWrapper::predict),
+            Algorithm::MLPClassifier => Box::new(MLPClassifierWrapper::predict),
+            Algorithm::PoissonRegressor => Box::new(PoissonRegressorWrapper::predict),
+            Algorithm::GammaRegressor => Box::new(GammaRegressorWrapper::predict),
+            Algorithm::TweedieRegressor => Box::new(TweedieRegressorWrapper::predict),
+            Algorithm::QuantileRegressor => Box::new(QuantileRegressorWrapper::predict),
+            Algorithm::GradientBoostingQuantileRegressor => {
+                Box::new(GradientBoostingQuantileRegressorWrapper::predict)
+            }
+        }
+    }
+
+    /// The function that predicts class probabilities with this algorithm, if it can
+    pub(crate) fn get_probability_predictor(&self) -> Option<ProbabilityPredictor> {
+        match self {
+            Algorithm::LogisticRegression => {
+                Some(Box::new(LogisticRegressionWrapper::predict_proba))
+            }
+            Algorithm::RandomForestClassifier => {
+                Some(Box::new(RandomForestClassifierWrapper::predict_proba))
+            }
+            Algorithm::GradientBoostingClassifier => {
+                Some(Box::new(GradientBoostingClassifierWrapper::predict_proba))
+            }
+            Algorithm::ExtraTreesClassifier => {
+                Some(Box::new(ExtraTreesClassifierWrapper::predict_proba))
+            }
+            Algorithm::MLPClassifier => Some(Box::new(MLPClassifierWrapper::predict_proba)),
+            _ => None,
         }
     }
 
@@ -300,6 +496,23 @@ impl Algorithm {
             Algorithm::CategoricalNaiveBayes => {
                 Box::new(CategoricalNaiveBayesClassifierWrapper::train)
             }
+            Algorithm::GradientBoostingRegressor => {
+                Box::new(GradientBoostingRegressorWrapper::train)
+            }
+            Algorithm::GradientBoostingClassifier => {
+                Box::new(GradientBoostingClassifierWrapper::train)
+            }
+            Algorithm::ExtraTreesRegressor => Box::new(ExtraTreesRegressorWrapper::train),
+            Algorithm::ExtraTreesClassifier => Box::new(ExtraTreesClassifierWrapper::train),
+            Algorithm::MLPRegressor => Box::new(MLPRegressorWrapper::train),
+            Algorithm::MLPClassifier => Box::new(MLPClassifierWrapper::train),
+            Algorithm::PoissonRegressor => Box::new(PoissonRegressorWrapper::train),
+            Algorithm::GammaRegressor => Box::new(GammaRegressorWrapper::train),
+            Algorithm::TweedieRegressor => Box::new(TweedieRegressorWrapper::train),
+            Algorithm::QuantileRegressor => Box::new(QuantileRegressorWrapper::train),
+            Algorithm::GradientBoostingQuantileRegressor => {
+                Box::new(GradientBoostingQuantileRegressorWrapper::train)
+            }
         }
     }
 }
@@ -322,6 +535,19 @@ impl Display for Algorithm {
             Algorithm::SVC => write!(f, "Support Vector Classifier"),
             Algorithm::GaussianNaiveBayes => write!(f, "Gaussian Naive Bayes"),
             Algorithm::CategoricalNaiveBayes => write!(f, "Categorical Naive Bayes"),
+            Algorithm::GradientBoostingRegressor => write!(f, "Gradient Boosting Regressor"),
+            Algorithm::GradientBoostingClassifier => write!(f, "Gradient Boosting Classifier"),
+            Algorithm::ExtraTreesRegressor => write!(f, "Extra Trees Regressor"),
+            Algorithm::ExtraTreesClassifier => write!(f, "Extra Trees Classifier"),
+            Algorithm::MLPRegressor => write!(f, "MLP Regressor"),
+            Algorithm::MLPClassifier => write!(f, "MLP Classifier"),
+            Algorithm::PoissonRegressor => write!(f, "Poisson Regressor"),
+            Algorithm::GammaRegressor => write!(f, "Gamma Regressor"),
+            Algorithm::TweedieRegressor => write!(f, "Tweedie Regressor"),
+            Algorithm::QuantileRegressor => write!(f, "Quantile Regressor"),
+            Algorithm::GradientBoostingQuantileRegressor => {
+                write!(f, "Gradient Boosting Quantile Regressor")
+            }
         }
     }
 }
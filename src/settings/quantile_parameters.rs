@@ -0,0 +1,125 @@
+//! Parameters for quantile regression
+
+use super::GradientBoostingParameters;
+
+/// Parameters for linear quantile regression
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+pub struct QuantileRegressorParameters {
+    /// The quantile of the target that is predicted
+    pub(crate) quantile: f32,
+    /// The strength of the L2 penalty on the coefficients of the standardized features
+    pub(crate) alpha: f32,
+    /// The maximum number of reweighted least squares steps
+    pub(crate) max_iter: usize,
+    /// The relative change in the coefficients at which the fit has converged
+    pub(crate) tol: f32,
+}
+
+impl QuantileRegressorParameters {
+    /// Define the quantile of the target that is predicted, so that 0.5 predicts the median.
+    /// Prediction intervals fit the model again at a fixed set of quantiles after the comparison.
+    /// ```
+    /// # use automl::settings::QuantileRegressorParameters;
+    /// let parameters = QuantileRegressorParameters::default().with_quantile(0.9);
+    /// ```
+    pub fn with_quantile(mut self, quantile: f32) -> Self {
+        assert!(
+            quantile > 0.0 && quantile < 1.0,
+            "The quantile must be between zero and one."
+        );
+        self.quantile = quantile;
+        self
+    }
+
+    /// Define the strength of the L2 penalty. The features are standardized first, and the
+    /// intercept is never penalized.
+    /// ```
+    /// # use automl::settings::QuantileRegressorParameters;
+    /// let parameters = QuantileRegressorParameters::default().with_alpha(0.1);
+    /// ```
+    pub fn with_alpha(mut self, alpha: f32) -> Self {
+        assert!(alpha >= 0.0, "The L2 penalty cannot be negative.");
+        self.alpha = alpha;
+        self
+    }
+
+    /// Define the maximum number of reweighted least squares steps
+    /// ```
+    /// # use automl::settings::QuantileRegressorParameters;
+    /// let parameters = QuantileRegressorParameters::default().with_max_iter(300);
+    /// ```
+    pub fn with_max_iter(mut self, max_iter: usize) -> Self {
+        self.max_iter = max_iter;
+        self
+    }
+
+    /// Define the relative change in the coefficients at which the fit has converged
+    /// ```
+    /// # use automl::settings::QuantileRegressorParameters;
+    /// let parameters = QuantileRegressorParameters::default().with_tol(1e-6);
+    /// ```
+    pub fn with_tol(mut self, tol: f32) -> Self {
+        assert!(tol > 0.0, "The tolerance must be greater than zero.");
+        self.tol = tol;
+        self
+    }
+}
+
+impl Default for QuantileRegressorParameters {
+    fn default() -> Self {
+        Self {
+            quantile: 0.5,
+            alpha: 0.0001,
+            max_iter: 100,
+            tol: 1e-4,
+        }
+    }
+}
+
+/// Parameters for gradient boosted tree quantile regression
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+pub struct GradientBoostingQuantileRegressorParameters {
+    /// The quantile of the target that is predicted
+    pub(crate) quantile: f32,
+    /// How the trees are grown and boosted
+    pub(crate) boosting: GradientBoostingParameters,
+}
+
+impl GradientBoostingQuantileRegressorParameters {
+    /// Define the quantile of the target that is predicted, so that 0.5 predicts the median.
+    /// Prediction intervals fit the model again at a fixed set of quantiles after the comparison.
+    /// ```
+    /// # use automl::settings::GradientBoostingQuantileRegressorParameters;
+    /// let parameters = GradientBoostingQuantileRegressorParameters::default().with_quantile(0.1);
+    /// ```
+    pub fn with_quantile(mut self, quantile: f32) -> Self {
+        assert!(
+            quantile > 0.0 && quantile < 1.0,
+            "The quantile must be between zero and one."
+        );
+        self.quantile = quantile;
+        self
+    }
+
+    /// Define how the trees are grown and boosted
+    /// ```
+    /// # use automl::settings::{
+    /// #     GradientBoostingParameters, GradientBoostingQuantileRegressorParameters,
+    /// # };
+    /// let parameters = GradientBoostingQuantileRegressorParameters::default()
+    ///     .with_boosting(GradientBoostingParameters::default().with_n_estimators(300));
+    /// ```
+    pub fn with_boosting(mut self, boosting: GradientBoostingParameters) -> Self {
+        self.boosting = boosting;
+        self
+    }
+}
+
+impl Default for GradientBoostingQuantileRegressorParameters {
+    fn default() -> Self {
+        Self {
+            quantile: 0.5,
+            boosting: GradientBoostingParameters::default(),
+        }
+    }
+}
@@ -5,15 +5,20 @@ use comfy_table::{
 
 use super::{
     Algorithm, CategoricalNBParameters, DecisionTreeClassifierParameters,
-    DecisionTreeRegressorParameters, ElasticNetParameters, FinalModel, GaussianNBParameters,
-    KNNClassifierParameters, KNNRegressorParameters, LassoParameters, LinearRegressionParameters,
-    LinearRegressionSolverName, LogisticRegressionParameters, Metric, PreProcessing,
+    DecisionTreeRegressorParameters, ElasticNetParameters, ExtraTreesClassifierParameters,
+    ExtraTreesRegressorParameters, FinalModel, GammaRegressorParameters, GaussianNBParameters,
+    GradientBoostingClassifierParameters, GradientBoostingQuantileRegressorParameters,
+    GradientBoostingRegressorParameters, KNNClassifierParameters, KNNRegressorParameters,
+    LassoParameters, LinearRegressionParameters, LinearRegressionSolverName,
+    LogisticRegressionParameters, MLPClassifierParameters, MLPRegressorParameters, Metric,
+    MulticlassStrategy, PoissonRegressorParameters, PreProcessing, QuantileRegressorParameters,
     RandomForestClassifierParameters, RandomForestRegressorParameters, RidgeRegressionParameters,
-    RidgeRegressionSolverName, SVCParameters, SVRParameters,
+    RidgeRegressionSolverName, SVCParameters, SVRParameters, TweedieRegressorParameters,
 };
 
 use crate::utils::{
-    debug_option, print_knn_search_algorithm, print_knn_weight_function, print_option,
+    debug_option, mean_pinball_loss, mean_tweedie_deviance, print_knn_search_algorithm,
+    print_knn_weight_function, print_option, Kernel,
 };
 
 use smartcore::{
@@ -22,8 +27,10 @@ use smartcore::{
     tree::decision_tree_classifier::SplitCriterion,
 };
 
+use std::collections::BTreeMap;
 use std::fmt::{Display, Formatter};
 use std::io::{Read, Write};
+use std::time::Duration;
 
 /// Settings for supervised models
 #[derive(serde::Serialize, serde::Deserialize)]
@@ -33,7 +40,14 @@ pub struct Settings {
     pub(crate) skiplist: Vec<Algorithm>,
     number_of_folds: usize,
     pub(crate) shuffle: bool,
-    verbose: bool,
+    /// Whether progress is printed to the terminal
+    pub(crate) verbose: bool,
+    /// How long the whole comparison may take, if limited
+    #[serde(default)]
+    pub(crate) time_budget: Option<Duration>,
+    /// How long each algorithm may take, if limited
+    #[serde(default)]
+    pub(crate) algorithm_timeout: Option<Duration>,
     pub(crate) final_model_approach: FinalModel,
     pub(crate) preprocessing: PreProcessing,
     pub(crate) linear_settings: Option<LinearRegressionParameters>,
@@ -51,6 +65,40 @@ pub struct Settings {
     pub(crate) decision_tree_classifier_settings: Option<DecisionTreeClassifierParameters>,
     pub(crate) gaussian_nb_settings: Option<GaussianNBParameters<f32>>,
     pub(crate) categorical_nb_settings: Option<CategoricalNBParameters<f32>>,
+    /// Parameters for gradient boosted tree regression, if it is compared
+    #[serde(default)]
+    pub(crate) gradient_boosting_regressor_settings: Option<GradientBoostingRegressorParameters>,
+    /// Parameters for gradient boosted tree classification, if it is compared
+    #[serde(default)]
+    pub(crate) gradient_boosting_classifier_settings: Option<GradientBoostingClassifierParameters>,
+    /// Parameters for extremely randomized trees regression, if it is compared
+    #[serde(default)]
+    pub(crate) extra_trees_regressor_settings: Option<ExtraTreesRegressorParameters>,
+    /// Parameters for extremely randomized trees classification, if it is compared
+    #[serde(default)]
+    pub(crate) extra_trees_classifier_settings: Option<ExtraTreesClassifierParameters>,
+    /// Parameters for multilayer perceptron regression, if it is compared
+    #[serde(default)]
+    pub(crate) mlp_regressor_settings: Option<MLPRegressorParameters>,
+    /// Parameters for multilayer perceptron classification, if it is compared
+    #[serde(default)]
+    pub(crate) mlp_classifier_settings: Option<MLPClassifierParameters>,
+    /// Parameters for Poisson regression, if it is compared
+    #[serde(default)]
+    pub(crate) poisson_regressor_settings: Option<PoissonRegressorParameters>,
+    /// Parameters for gamma regression, if it is compared
+    #[serde(default)]
+    pub(crate) gamma_regressor_settings: Option<GammaRegressorParameters>,
+    /// Parameters for Tweedie regression, if it is compared
+    #[serde(default)]
+    pub(crate) tweedie_regressor_settings: Option<TweedieRegressorParameters>,
+    /// Parameters for linear quantile regression, if it is compared
+    #[serde(default)]
+    pub(crate) quantile_regressor_settings: Option<QuantileRegressorParameters>,
+    /// Parameters for gradient boosted tree quantile regression, if it is compared
+    #[serde(default)]
+    pub(crate) gradient_boosting_quantile_regressor_settings:
+        Option<GradientBoostingQuantileRegressorParameters>,
 }
 
 impl Default for Settings {
@@ -75,11 +123,24 @@ impl Default for Settings {
                 Algorithm::DecisionTreeRegressor,
                 Algorithm::RandomForestRegressor,
                 Algorithm::KNNRegressor,
+                Algorithm::GradientBoostingRegressor,
+                Algorithm::GradientBoostingClassifier,
+                Algorithm::ExtraTreesRegressor,
+                Algorithm::ExtraTreesClassifier,
+                Algorithm::MLPRegressor,
+                Algorithm::MLPClassifier,
+                Algorithm::PoissonRegressor,
+                Algorithm::GammaRegressor,
+                Algorithm::TweedieRegressor,
+                Algorithm::QuantileRegressor,
+                Algorithm::GradientBoostingQuantileRegressor,
             ],
             preprocessing: PreProcessing::None,
             number_of_folds: 10,
             shuffle: false,
             verbose: false,
+            time_budget: None,
+            algorithm_timeout: None,
             linear_settings: None,
             svr_settings: None,
             lasso_settings: None,
@@ -95,6 +156,17 @@ impl Default for Settings {
             decision_tree_classifier_settings: None,
             gaussian_nb_settings: None,
             categorical_nb_settings: None,
+            gradient_boosting_regressor_settings: None,
+            gradient_boosting_classifier_settings: None,
+            extra_trees_regressor_settings: None,
+            extra_trees_classifier_settings: None,
+            mlp_regressor_settings: None,
+            mlp_classifier_settings: None,
+            poisson_regressor_settings: None,
+            gamma_regressor_settings: None,
+            tweedie_regressor_settings: None,
+            quantile_regressor_settings: None,
+            gradient_boosting_quantile_regressor_settings: None,
         }
     }
 }
@@ -107,16 +179,104 @@ impl Settings {
     }
 
     pub(crate) fn get_metric(&self) -> Box<dyn Fn(&Vec<f32>, &Vec<f32>) -> f32> {
-        Box::new(match self.sort_by {
-            Metric::RSquared => r2,
-            Metric::MeanAbsoluteError => mean_absolute_error,
-            Metric::MeanSquaredError => mean_squared_error,
-            Metric::Accuracy => accuracy,
+        match self.sort_by {
+            Metric::RSquared => Box::new(r2),
+            Metric::MeanAbsoluteError => Box::new(mean_absolute_error),
+            Metric::MeanSquaredError => Box::new(mean_squared_error),
+            Metric::Accuracy => Box::new(accuracy),
             Metric::None => panic!("A metric must be set."),
-        })
+            Metric::PoissonDeviance => {
+                Box::new(|y_true, y_pred| mean_tweedie_deviance(y_true, y_pred, 1.0))
+            }
+            Metric::GammaDeviance => {
+                Box::new(|y_true, y_pred| mean_tweedie_deviance(y_true, y_pred, 2.0))
+            }
+            Metric::TweedieDeviance(power) => {
+                Box::new(move |y_true, y_pred| mean_tweedie_deviance(y_true, y_pred, power))
+            }
+            Metric::PinballLoss(quantile) => {
+                Box::new(move |y_true, y_pred| mean_pinball_loss(y_true, y_pred, quantile))
+            }
+        }
+    }
+
+    /// Whether the settings are for a classification problem
+    pub(crate) fn is_classification(&self) -> bool {
+        matches!(self.model_type, ModelType::Classification)
+    }
+
+    /// The parameters of an algorithm by name, as shown in reports
+    pub(crate) fn get_parameters(&self, algorithm: Algorithm) -> BTreeMap<String, String> {
+        let parameters = match algorithm {
+            Algorithm::Linear => serde_yaml::to_value(&self.linear_settings),
+            Algorithm::Lasso => serde_yaml::to_value(&self.lasso_settings),
+            Algorithm::Ridge => serde_yaml::to_value(&self.ridge_settings),
+            Algorithm::ElasticNet => serde_yaml::to_value(&self.elastic_net_settings),
+            Algorithm::RandomForestRegressor => {
+                serde_yaml::to_value(&self.random_forest_regressor_settings)
+            }
+            Algorithm::KNNRegressor => serde_yaml::to_value(&self.knn_regressor_settings),
+            Algorithm::SVR => serde_yaml::to_value(&self.svr_settings),
+            Algorithm::DecisionTreeRegressor => {
+                serde_yaml::to_value(&self.decision_tree_regressor_settings)
+            }
+            Algorithm::LogisticRegression => serde_yaml::to_value(&self.logistic_settings),
+            Algorithm::RandomForestClassifier => {
+                serde_yaml::to_value(&self.random_forest_classifier_settings)
+            }
+            Algorithm::DecisionTreeClassifier => {
+                serde_yaml::to_value(&self.decision_tree_classifier_settings)
+            }
+            Algorithm::KNNClassifier => serde_yaml::to_value(&self.knn_classifier_settings),
+            Algorithm::SVC => serde_yaml::to_value(&self.svc_settings),
+            Algorithm::GaussianNaiveBayes => serde_yaml::to_value(&self.gaussian_nb_settings),
+            Algorithm::CategoricalNaiveBayes => serde_yaml::to_value(&self.categorical_nb_settings),
+            Algorithm::GradientBoostingRegressor => {
+                serde_yaml::to_value(&self.gradient_boosting_regressor_settings)
+            }
+            Algorithm::GradientBoostingClassifier => {
+                serde_yaml::to_value(&self.gradient_boosting_classifier_settings)
+            }
+            Algorithm::ExtraTreesRegressor => {
+                serde_yaml::to_value(&self.extra_trees_regressor_settings)
+            }
+            Algorithm::ExtraTreesClassifier => {
+                serde_yaml::to_value(&self.extra_trees_classifier_settings)
+            }
+            Algorithm::MLPRegressor => serde_yaml::to_value(&self.mlp_regressor_settings),
+            Algorithm::MLPClassifier => serde_yaml::to_value(&self.mlp_classifier_settings),
+            Algorithm::PoissonRegressor => serde_yaml::to_value(&self.poisson_regressor_settings),
+            Algorithm::GammaRegressor => serde_yaml::to_value(&self.gamma_regressor_settings),
+            Algorithm::TweedieRegressor => serde_yaml::to_value(&self.tweedie_regressor_settings),
+            Algorithm::QuantileRegressor => serde_yaml::to_value(&self.quantile_regressor_settings),
+            Algorithm::GradientBoostingQuantileRegressor => {
+                serde_yaml::to_value(&self.gradient_boosting_quantile_regressor_settings)
+            }
+        }
+        .expect("Cannot serialize parameters.");
+
+        // Flatten the parameters into one entry per field, including the fields of nested
+        // parameters such as the boosting parameters of quantile regression
+        let mut flattened = BTreeMap::new();
+        if let serde_yaml::Value::Mapping(mapping) = parameters {
+            for (key, value) in mapping {
+                let entries = match value {
+                    serde_yaml::Value::Mapping(nested) => nested.into_iter().collect(),
+                    value => vec![(key, value)],
+                };
+                for (key, value) in entries {
+                    let key =
+                        serde_yaml::to_string(&key).expect("Cannot serialize parameter name.");
+                    let value = serde_yaml::to_string(&value).expect("Cannot serialize parameter.");
+                    flattened.insert(clean_yaml(&key), clean_yaml(&value));
+                }
+            }
+        }
+        flattened
     }
 
-    /// Creates default settings for regression
+    /// Creates default settings for regression. Gradient boosting, extra trees and multilayer
+    /// perceptrons are slower to compare and are left out unless their settings are given.
     /// ```
     /// # use automl::Settings;
     /// let settings = Settings::default_regression();
@@ -134,11 +294,16 @@ impl Settings {
                 Algorithm::DecisionTreeClassifier,
                 Algorithm::CategoricalNaiveBayes,
                 Algorithm::GaussianNaiveBayes,
+                Algorithm::GradientBoostingClassifier,
+                Algorithm::ExtraTreesClassifier,
+                Algorithm::MLPClassifier,
             ],
             preprocessing: PreProcessing::None,
             number_of_folds: 10,
             shuffle: false,
             verbose: false,
+            time_budget: None,
+            algorithm_timeout: None,
             linear_settings: Some(LinearRegressionParameters::default()),
             svr_settings: Some(SVRParameters::default()),
             lasso_settings: Some(LassoParameters::default()),
@@ -154,10 +319,22 @@ impl Settings {
             decision_tree_classifier_settings: None,
             gaussian_nb_settings: None,
             categorical_nb_settings: None,
+            gradient_boosting_regressor_settings: None,
+            gradient_boosting_classifier_settings: None,
+            extra_trees_regressor_settings: None,
+            extra_trees_classifier_settings: None,
+            mlp_regressor_settings: None,
+            mlp_classifier_settings: None,
+            poisson_regressor_settings: None,
+            gamma_regressor_settings: None,
+            tweedie_regressor_settings: None,
+            quantile_regressor_settings: None,
+            gradient_boosting_quantile_regressor_settings: None,
         }
     }
 
-    /// Creates default settings for classification
+    /// Creates default settings for classification. Gradient boosting, extra trees and multilayer
+    /// perceptrons are slower to compare and are left out unless their settings are given.
     /// ```
     /// # use automl::Settings;
     /// let settings = Settings::default_classification();
@@ -176,11 +353,21 @@ impl Settings {
                 Algorithm::DecisionTreeRegressor,
                 Algorithm::RandomForestRegressor,
                 Algorithm::KNNRegressor,
+                Algorithm::GradientBoostingRegressor,
+                Algorithm::ExtraTreesRegressor,
+                Algorithm::MLPRegressor,
+                Algorithm::PoissonRegressor,
+                Algorithm::GammaRegressor,
+                Algorithm::TweedieRegressor,
+                Algorithm::QuantileRegressor,
+                Algorithm::GradientBoostingQuantileRegressor,
             ],
             preprocessing: PreProcessing::None,
             number_of_folds: 10,
             shuffle: false,
             verbose: false,
+            time_budget: None,
+            algorithm_timeout: None,
             linear_settings: None,
             svr_settings: None,
             lasso_settings: None,
@@ -196,6 +383,17 @@ impl Settings {
             decision_tree_classifier_settings: Some(DecisionTreeClassifierParameters::default()),
             gaussian_nb_settings: Some(GaussianNBParameters::default()),
             categorical_nb_settings: Some(CategoricalNBParameters::default()),
+            gradient_boosting_regressor_settings: None,
+            gradient_boosting_classifier_settings: None,
+            extra_trees_regressor_settings: None,
+            extra_trees_classifier_settings: None,
+            mlp_regressor_settings: None,
+            mlp_classifier_settings: None,
+            poisson_regressor_settings: None,
+            gamma_regressor_settings: None,
+            tweedie_regressor_settings: None,
+            quantile_regressor_settings: None,
+            gradient_boosting_quantile_regressor_settings: None,
         }
     }
 
@@ -259,6 +457,29 @@ impl Settings {
         self
     }
 
+    /// Specify how long the whole model comparison may take. Algorithms that have not finished
+    /// when the budget runs out are cut short, and any remaining algorithms are skipped.
+    /// ```
+    /// # use automl::Settings;
+    /// use std::time::Duration;
+    /// let settings = Settings::default().with_time_budget(Duration::from_secs(60));
+    /// ```
+    pub fn with_time_budget(mut self, budget: Duration) -> Self {
+        self.time_budget = Some(budget);
+        self
+    }
+
+    /// Specify how long a single algorithm may spend in cross-validation before it is cut short
+    /// ```
+    /// # use automl::Settings;
+    /// use std::time::Duration;
+    /// let settings = Settings::default().with_algorithm_timeout(Duration::from_secs(10));
+    /// ```
+    pub fn with_algorithm_timeout(mut self, timeout: Duration) -> Self {
+        self.algorithm_timeout = Some(timeout);
+        self
+    }
+
     /// Specify what type of preprocessing should be performed
     /// ```
     /// # use automl::Settings;
@@ -277,6 +498,9 @@ impl Settings {
     /// let settings = Settings::default().with_final_model(FinalModel::Best);
     /// ```
     pub fn with_final_model(mut self, approach: FinalModel) -> Self {
+        if let FinalModel::Blending { algorithm, .. } = approach {
+            self.add_default_parameters(algorithm);
+        }
         self.final_model_approach = approach;
         self
     }
@@ -314,11 +538,76 @@ impl Settings {
             Algorithm::DecisionTreeRegressor,
             Algorithm::RandomForestRegressor,
             Algorithm::KNNRegressor,
+            Algorithm::GradientBoostingRegressor,
+            Algorithm::GradientBoostingClassifier,
+            Algorithm::ExtraTreesRegressor,
+            Algorithm::ExtraTreesClassifier,
+            Algorithm::MLPRegressor,
+            Algorithm::MLPClassifier,
+            Algorithm::PoissonRegressor,
+            Algorithm::GammaRegressor,
+            Algorithm::TweedieRegressor,
+            Algorithm::QuantileRegressor,
+            Algorithm::GradientBoostingQuantileRegressor,
         ];
         self.skiplist.retain(|&algo| algo != only);
+
+        self.add_default_parameters(only);
         self
     }
 
+    /// Give an algorithm that is left out of the defaults its default parameters, so that asking
+    /// for it by name trains it
+    fn add_default_parameters(&mut self, algorithm: Algorithm) {
+        match algorithm {
+            Algorithm::GradientBoostingRegressor => {
+                self.gradient_boosting_regressor_settings
+                    .get_or_insert_with(Default::default);
+            }
+            Algorithm::GradientBoostingClassifier => {
+                self.gradient_boosting_classifier_settings
+                    .get_or_insert_with(Default::default);
+            }
+            Algorithm::ExtraTreesRegressor => {
+                self.extra_trees_regressor_settings
+                    .get_or_insert_with(Default::default);
+            }
+            Algorithm::ExtraTreesClassifier => {
+                self.extra_trees_classifier_settings
+                    .get_or_insert_with(Default::default);
+            }
+            Algorithm::MLPRegressor => {
+                self.mlp_regressor_settings
+                    .get_or_insert_with(Default::default);
+            }
+            Algorithm::MLPClassifier => {
+                self.mlp_classifier_settings
+                    .get_or_insert_with(Default::default);
+            }
+            Algorithm::PoissonRegressor => {
+                self.poisson_regressor_settings
+                    .get_or_insert_with(Default::default);
+            }
+            Algorithm::GammaRegressor => {
+                self.gamma_regressor_settings
+                    .get_or_insert_with(Default::default);
+            }
+            Algorithm::TweedieRegressor => {
+                self.tweedie_regressor_settings
+                    .get_or_insert_with(Default::default);
+            }
+            Algorithm::QuantileRegressor => {
+                self.quantile_regressor_settings
+                    .get_or_insert_with(Default::default);
+            }
+            Algorithm::GradientBoostingQuantileRegressor => {
+                self.gradient_boosting_quantile_regressor_settings
+                    .get_or_insert_with(Default::default);
+            }
+            _ => {}
+        }
+    }
+
     /// Adds a specific sorting function to the settings
     /// ```
     /// # use automl::Settings;
@@ -445,6 +734,62 @@ impl Settings {
         self
     }
 
+    /// Specify settings for gradient boosting classifier, which also adds it to the comparison
+    /// ```
+    /// # use automl::Settings;
+    /// use automl::settings::GradientBoostingClassifierParameters;
+    /// let settings = Settings::default()
+    ///     .with_gradient_boosting_classifier_settings(GradientBoostingClassifierParameters::default()
+    ///         .with_learning_rate(0.05)
+    ///         .with_n_estimators(200)
+    ///         .with_max_depth(4)
+    ///         .with_subsample(0.8)
+    ///     );
+    /// ```
+    pub fn with_gradient_boosting_classifier_settings(
+        mut self,
+        settings: GradientBoostingClassifierParameters,
+    ) -> Self {
+        self.gradient_boosting_classifier_settings = Some(settings);
+        self
+    }
+
+    /// Specify settings for extra trees classifier, which also adds it to the comparison
+    /// ```
+    /// # use automl::Settings;
+    /// use automl::settings::ExtraTreesClassifierParameters;
+    /// let settings = Settings::default()
+    ///     .with_extra_trees_classifier_settings(ExtraTreesClassifierParameters::default()
+    ///         .with_n_trees(100)
+    ///         .with_max_depth(10)
+    ///         .with_min_samples_leaf(2)
+    ///         .with_m(5)
+    ///     );
+    /// ```
+    pub fn with_extra_trees_classifier_settings(
+        mut self,
+        settings: ExtraTreesClassifierParameters,
+    ) -> Self {
+        self.extra_trees_classifier_settings = Some(settings);
+        self
+    }
+
+    /// Specify settings for multilayer perceptron classifier, which also adds it to the comparison
+    /// ```
+    /// # use automl::Settings;
+    /// use automl::settings::{Activation, MLPClassifierParameters};
+    /// let settings = Settings::default()
+    ///     .with_mlp_classifier_settings(MLPClassifierParameters::default()
+    ///         .with_hidden_layers(&[32])
+    ///         .with_activation(Activation::Tanh)
+    ///         .with_max_iter(100)
+    ///     );
+    /// ```
+    pub fn with_mlp_classifier_settings(mut self, settings: MLPClassifierParameters) -> Self {
+        self.mlp_classifier_settings = Some(settings);
+        self
+    }
+
     /// Specify settings for linear regression
     /// ```
     /// # use automl::Settings;
@@ -584,6 +929,134 @@ impl Settings {
         self.decision_tree_regressor_settings = Some(settings);
         self
     }
+
+    /// Specify settings for gradient boosting regressor, which also adds it to the comparison
+    /// ```
+    /// # use automl::Settings;
+    /// use automl::settings::GradientBoostingRegressorParameters;
+    /// let settings = Settings::default()
+    ///     .with_gradient_boosting_regressor_settings(GradientBoostingRegressorParameters::default()
+    ///         .with_learning_rate(0.05)
+    ///         .with_n_estimators(200)
+    ///         .with_max_depth(4)
+    ///         .with_validation_fraction(0.1)
+    ///     );
+    /// ```
+    pub fn with_gradient_boosting_regressor_settings(
+        mut self,
+        settings: GradientBoostingRegressorParameters,
+    ) -> Self {
+        self.gradient_boosting_regressor_settings = Some(settings);
+        self
+    }
+
+    /// Specify settings for extra trees regressor, which also adds it to the comparison
+    /// ```
+    /// # use automl::Settings;
+    /// use automl::settings::ExtraTreesRegressorParameters;
+    /// let settings = Settings::default()
+    ///     .with_extra_trees_regressor_settings(ExtraTreesRegressorParameters::default()
+    ///         .with_n_trees(100)
+    ///         .with_max_depth(10)
+    ///         .with_min_samples_leaf(2)
+    ///         .with_min_samples_split(5)
+    ///     );
+    /// ```
+    pub fn with_extra_trees_regressor_settings(
+        mut self,
+        settings: ExtraTreesRegressorParameters,
+    ) -> Self {
+        self.extra_trees_regressor_settings = Some(settings);
+        self
+    }
+
+    /// Specify settings for multilayer perceptron regressor, which also adds it to the comparison
+    /// ```
+    /// # use automl::Settings;
+    /// use automl::settings::{Activation, MLPRegressorParameters};
+    /// let settings = Settings::default()
+    ///     .with_mlp_regressor_settings(MLPRegressorParameters::default()
+    ///         .with_hidden_layers(&[64, 32])
+    ///         .with_batch_size(32)
+    ///         .with_validation_fraction(0.1)
+    ///     );
+    /// ```
+    pub fn with_mlp_regressor_settings(mut self, settings: MLPRegressorParameters) -> Self {
+        self.mlp_regressor_settings = Some(settings);
+        self
+    }
+
+    /// Specify settings for Poisson regression, which also adds it to the comparison. It is
+    /// skipped when the targets are negative or all zero.
+    /// ```
+    /// # use automl::Settings;
+    /// use automl::settings::PoissonRegressorParameters;
+    /// let settings = Settings::default()
+    ///     .with_poisson_regressor_settings(PoissonRegressorParameters::default().with_alpha(0.1));
+    /// ```
+    pub fn with_poisson_regressor_settings(mut self, settings: PoissonRegressorParameters) -> Self {
+        self.poisson_regressor_settings = Some(settings);
+        self
+    }
+
+    /// Specify settings for gamma regression, which also adds it to the comparison. It is skipped
+    /// unless every target is positive.
+    /// ```
+    /// # use automl::Settings;
+    /// use automl::settings::GammaRegressorParameters;
+    /// let settings = Settings::default()
+    ///     .with_gamma_regressor_settings(GammaRegressorParameters::default().with_max_iter(300));
+    /// ```
+    pub fn with_gamma_regressor_settings(mut self, settings: GammaRegressorParameters) -> Self {
+        self.gamma_regressor_settings = Some(settings);
+        self
+    }
+
+    /// Specify settings for Tweedie regression, which also adds it to the comparison. It is
+    /// skipped when the targets are invalid for its power, as for Poisson or gamma regression.
+    /// ```
+    /// # use automl::Settings;
+    /// use automl::settings::TweedieRegressorParameters;
+    /// let settings = Settings::default()
+    ///     .with_tweedie_regressor_settings(TweedieRegressorParameters::default().with_power(1.3));
+    /// ```
+    pub fn with_tweedie_regressor_settings(mut self, settings: TweedieRegressorParameters) -> Self {
+        self.tweedie_regressor_settings = Some(settings);
+        self
+    }
+
+    /// Specify settings for linear quantile regression, which also adds it to the comparison.
+    /// Prediction intervals use it when it finished cross-validation.
+    /// ```
+    /// # use automl::Settings;
+    /// use automl::settings::QuantileRegressorParameters;
+    /// let parameters = QuantileRegressorParameters::default().with_alpha(0.1);
+    /// let settings = Settings::default().with_quantile_regressor_settings(parameters);
+    /// ```
+    pub fn with_quantile_regressor_settings(
+        mut self,
+        settings: QuantileRegressorParameters,
+    ) -> Self {
+        self.quantile_regressor_settings = Some(settings);
+        self
+    }
+
+    /// Specify settings for gradient boosted tree quantile regression, which also adds it to the
+    /// comparison. Prediction intervals use it when it finished cross-validation.
+    /// ```
+    /// # use automl::Settings;
+    /// use automl::settings::GradientBoostingQuantileRegressorParameters;
+    /// let settings = Settings::default().with_gradient_boosting_quantile_regressor_settings(
+    ///     GradientBoostingQuantileRegressorParameters::default().with_quantile(0.9),
+    /// );
+    /// ```
+    pub fn with_gradient_boosting_quantile_regressor_settings(
+        mut self,
+        settings: GradientBoostingQuantileRegressorParameters,
+    ) -> Self {
+        self.gradient_boosting_quantile_regressor_settings = Some(settings);
+        self
+    }
 }
 
 #[cfg_attr(docsrs, doc(cfg(feature = "display")))]
@@ -620,6 +1093,11 @@ impl Display for Settings {
                 "    Number of CV Folds",
                 &*format!("{}", self.number_of_folds),
             ])
+            .add_row(vec!["    Time Budget", &*debug_option(self.time_budget)])
+            .add_row(vec![
+                "    Algorithm Timeout",
+                &*debug_option(self.algorithm_timeout),
+            ])
             .add_row(vec![
                 "    Pre-Processing",
                 &*format!("{}", self.preprocessing),
@@ -858,6 +1336,194 @@ impl Display for Settings {
                 ]);
         }
 
+        // Algorithms without parameters are not compared, so there is nothing to show
+        if let (false, Some(parameters)) = (
+            self.skiplist
+                .contains(&Algorithm::GradientBoostingRegressor),
+            &self.gradient_boosting_regressor_settings,
+        ) {
+            table
+                .add_row(vec![Cell::new(Algorithm::GradientBoostingRegressor)
+                    .add_attribute(Attribute::Italic)])
+                .add_row(vec![
+                    "    Learning rate",
+                    &*format!("{}", parameters.learning_rate),
+                ])
+                .add_row(vec![
+                    "    Number of estimators",
+                    &*format!("{}", parameters.n_estimators),
+                ])
+                .add_row(vec!["    Max Depth", &*format!("{}", parameters.max_depth)])
+                .add_row(vec![
+                    "    Min samples for leaf",
+                    &*format!("{}", parameters.min_samples_leaf),
+                ])
+                .add_row(vec![
+                    "    Min samples for split",
+                    &*format!("{}", parameters.min_samples_split),
+                ])
+                .add_row(vec!["    Subsample", &*format!("{}", parameters.subsample)])
+                .add_row(vec![
+                    "    Validation fraction",
+                    &*print_option(parameters.validation_fraction),
+                ])
+                .add_row(vec![
+                    "    Rounds without improvement",
+                    &*format!("{}", parameters.n_iter_no_change),
+                ]);
+        }
+
+        if let (false, Some(parameters)) = (
+            self.skiplist.contains(&Algorithm::ExtraTreesRegressor),
+            &self.extra_trees_regressor_settings,
+        ) {
+            table
+                .add_row(vec![
+                    Cell::new(Algorithm::ExtraTreesRegressor).add_attribute(Attribute::Italic)
+                ])
+                .add_row(vec!["    Max Depth", &*print_option(parameters.max_depth)])
+                .add_row(vec![
+                    "    Min samples for leaf",
+                    &*format!("{}", parameters.min_samples_leaf),
+                ])
+                .add_row(vec![
+                    "    Min samples for split",
+                    &*format!("{}", parameters.min_samples_split),
+                ])
+                .add_row(vec![
+                    "    Number of trees",
+                    &*format!("{}", parameters.n_trees),
+                ])
+                .add_row(vec![
+                    "    Number of split candidates",
+                    &*print_option(parameters.m),
+                ]);
+        }
+
+        if let (false, Some(parameters)) = (
+            self.skiplist.contains(&Algorithm::MLPRegressor),
+            &self.mlp_regressor_settings,
+        ) {
+            table
+                .add_row(vec![
+                    Cell::new(Algorithm::MLPRegressor).add_attribute(Attribute::Italic)
+                ])
+                .add_row(vec![
+                    "    Hidden layers",
+                    &*format!("{:?}", parameters.hidden_layers),
+                ])
+                .add_row(vec![
+                    "    Activation",
+                    &*format!("{}", parameters.activation),
+                ])
+                .add_row(vec!["    Optimizer", &*format!("{}", parameters.optimizer)])
+                .add_row(vec![
+                    "    Learning rate",
+                    &*format!("{}", parameters.learning_rate),
+                ])
+                .add_row(vec!["    L2 penalty", &*format!("{}", parameters.alpha)])
+                .add_row(vec![
+                    "    Batch size",
+                    &*print_option(parameters.batch_size),
+                ])
+                .add_row(vec![
+                    "    Max iterations",
+                    &*format!("{}", parameters.max_iter),
+                ])
+                .add_row(vec!["    Tolerance", &*format!("{}", parameters.tol)])
+                .add_row(vec![
+                    "    Validation fraction",
+                    &*print_option(parameters.validation_fraction),
+                ])
+                .add_row(vec![
+                    "    Iterations without improvement",
+                    &*format!("{}", parameters.n_iter_no_change),
+                ]);
+        }
+
+        for (algorithm, settings) in [
+            (
+                Algorithm::PoissonRegressor,
+                &self.poisson_regressor_settings,
+            ),
+            (Algorithm::GammaRegressor, &self.gamma_regressor_settings),
+            (
+                Algorithm::TweedieRegressor,
+                &self.tweedie_regressor_settings,
+            ),
+        ] {
+            if let (false, Some(parameters)) = (self.skiplist.contains(&algorithm), settings) {
+                table
+                    .add_row(vec![Cell::new(algorithm).add_attribute(Attribute::Italic)])
+                    .add_row(vec!["    L2 penalty", &*format!("{}", parameters.alpha)])
+                    .add_row(vec![
+                        "    Max iterations",
+                        &*format!("{}", parameters.max_iter),
+                    ])
+                    .add_row(vec!["    Tolerance", &*format!("{}", parameters.tol)]);
+                if algorithm == Algorithm::TweedieRegressor {
+                    table.add_row(vec!["    Power", &*format!("{}", parameters.power)]);
+                }
+            }
+        }
+
+        if let (false, Some(parameters)) = (
+            self.skiplist.contains(&Algorithm::QuantileRegressor),
+            &self.quantile_regressor_settings,
+        ) {
+            table
+                .add_row(vec![
+                    Cell::new(Algorithm::QuantileRegressor).add_attribute(Attribute::Italic)
+                ])
+                .add_row(vec!["    Quantile", &*format!("{}", parameters.quantile)])
+                .add_row(vec!["    L2 penalty", &*format!("{}", parameters.alpha)])
+                .add_row(vec![
+                    "    Max iterations",
+                    &*format!("{}", parameters.max_iter),
+                ])
+                .add_row(vec!["    Tolerance", &*format!("{}", parameters.tol)]);
+        }
+
+        if let (false, Some(parameters)) = (
+            self.skiplist
+                .contains(&Algorithm::GradientBoostingQuantileRegressor),
+            &self.gradient_boosting_quantile_regressor_settings,
+        ) {
+            let boosting = &parameters.boosting;
+            table
+                .add_row(vec![Cell::new(
+                    Algorithm::GradientBoostingQuantileRegressor,
+                )
+                .add_attribute(Attribute::Italic)])
+                .add_row(vec!["    Quantile", &*format!("{}", parameters.quantile)])
+                .add_row(vec![
+                    "    Learning rate",
+                    &*format!("{}", boosting.learning_rate),
+                ])
+                .add_row(vec![
+                    "    Number of estimators",
+                    &*format!("{}", boosting.n_estimators),
+                ])
+                .add_row(vec!["    Max Depth", &*format!("{}", boosting.max_depth)])
+                .add_row(vec![
+                    "    Min samples for leaf",
+                    &*format!("{}", boosting.min_samples_leaf),
+                ])
+                .add_row(vec![
+                    "    Min samples for split",
+                    &*format!("{}", boosting.min_samples_split),
+                ])
+                .add_row(vec!["    Subsample", &*format!("{}", boosting.subsample)])
+                .add_row(vec![
+                    "    Validation fraction",
+                    &*print_option(boosting.validation_fraction),
+                ])
+                .add_row(vec![
+                    "    Rounds without improvement",
+                    &*format!("{}", boosting.n_iter_no_change),
+                ]);
+        }
+
         if !self.skiplist.contains(&Algorithm::LogisticRegression) {
             table
                 .add_row(vec![
@@ -985,6 +1651,10 @@ impl Display for Settings {
                 .add_row(vec![
                     "    Kernel",
                     &*format!("{}", self.svc_settings.as_ref().unwrap().kernel),
+                ])
+                .add_row(vec![
+                    "    Multiclass strategy",
+                    &*format!("{}", self.svc_settings.as_ref().unwrap().strategy),
                 ]);
         }
 
@@ -1059,10 +1729,119 @@ impl Display for Settings {
                 ]);
         }
 
+        if let (false, Some(parameters)) = (
+            self.skiplist
+                .contains(&Algorithm::GradientBoostingClassifier),
+            &self.gradient_boosting_classifier_settings,
+        ) {
+            table
+                .add_row(vec![Cell::new(Algorithm::GradientBoostingClassifier)
+                    .add_attribute(Attribute::Italic)])
+                .add_row(vec![
+                    "    Learning rate",
+                    &*format!("{}", parameters.learning_rate),
+                ])
+                .add_row(vec![
+                    "    Number of estimators",
+                    &*format!("{}", parameters.n_estimators),
+                ])
+                .add_row(vec!["    Max Depth", &*format!("{}", parameters.max_depth)])
+                .add_row(vec![
+                    "    Min samples for leaf",
+                    &*format!("{}", parameters.min_samples_leaf),
+                ])
+                .add_row(vec![
+                    "    Min samples for split",
+                    &*format!("{}", parameters.min_samples_split),
+                ])
+                .add_row(vec!["    Subsample", &*format!("{}", parameters.subsample)])
+                .add_row(vec![
+                    "    Validation fraction",
+                    &*print_option(parameters.validation_fraction),
+                ])
+                .add_row(vec![
+                    "    Rounds without improvement",
+                    &*format!("{}", parameters.n_iter_no_change),
+                ]);
+        }
+
+        if let (false, Some(parameters)) = (
+            self.skiplist.contains(&Algorithm::ExtraTreesClassifier),
+            &self.extra_trees_classifier_settings,
+        ) {
+            table
+                .add_row(vec![
+                    Cell::new(Algorithm::ExtraTreesClassifier).add_attribute(Attribute::Italic)
+                ])
+                .add_row(vec!["    Max Depth", &*print_option(parameters.max_depth)])
+                .add_row(vec![
+                    "    Min samples for leaf",
+                    &*format!("{}", parameters.min_samples_leaf),
+                ])
+                .add_row(vec![
+                    "    Min samples for split",
+                    &*format!("{}", parameters.min_samples_split),
+                ])
+                .add_row(vec![
+                    "    Number of trees",
+                    &*format!("{}", parameters.n_trees),
+                ])
+                .add_row(vec![
+                    "    Number of split candidates",
+                    &*print_option(parameters.m),
+                ]);
+        }
+
+        if let (false, Some(parameters)) = (
+            self.skiplist.contains(&Algorithm::MLPClassifier),
+            &self.mlp_classifier_settings,
+        ) {
+            table
+                .add_row(vec![
+                    Cell::new(Algorithm::MLPClassifier).add_attribute(Attribute::Italic)
+                ])
+                .add_row(vec![
+                    "    Hidden layers",
+                    &*format!("{:?}", parameters.hidden_layers),
+                ])
+                .add_row(vec![
+                    "    Activation",
+                    &*format!("{}", parameters.activation),
+                ])
+                .add_row(vec!["    Optimizer", &*format!("{}", parameters.optimizer)])
+                .add_row(vec![
+                    "    Learning rate",
+                    &*format!("{}", parameters.learning_rate),
+                ])
+                .add_row(vec!["    L2 penalty", &*format!("{}", parameters.alpha)])
+                .add_row(vec![
+                    "    Batch size",
+                    &*print_option(parameters.batch_size),
+                ])
+                .add_row(vec![
+                    "    Max iterations",
+                    &*format!("{}", parameters.max_iter),
+                ])
+                .add_row(vec!["    Tolerance", &*format!("{}", parameters.tol)])
+                .add_row(vec![
+                    "    Validation fraction",
+                    &*print_option(parameters.validation_fraction),
+                ])
+                .add_row(vec![
+                    "    Iterations without improvement",
+                    &*format!("{}", parameters.n_iter_no_change),
+                ]);
+        }
+
         write!(f, "{}\n", table)
     }
 }
 
+/// Strip the document marker and trailing newline that serde_yaml adds to every value
+fn clean_yaml(yaml: &str) -> String {
+    yaml.trim_start_matches("---").trim().to_string()
+}
+
 #[derive(serde::Serialize, serde::Deserialize)]
 enum ModelType {
     None,
@@ -1079,3 +1858,209 @@ impl Display for ModelType {
         }
     }
 }
+
+/// The fields that settings have had since format version 2, up to the SVC parameters, which
+/// later versions have changed or added to
+#[derive(serde::Deserialize)]
+struct SettingsHead {
+    /// The metric
+    _sort_by: Metric,
+    /// Regression or classification
+    _model_type: ModelType,
+    /// The skipped algorithms
+    _skiplist: Vec<Algorithm>,
+    /// The number of folds
+    _number_of_folds: usize,
+    /// Whether to shuffle
+    _shuffle: bool,
+    /// Whether to print progress
+    _verbose: bool,
+    /// The time budget
+    _time_budget: Option<Duration>,
+    /// The per-algorithm timeout
+    _algorithm_timeout: Option<Duration>,
+    /// The final model approach
+    _final_model_approach: FinalModel,
+    /// The preprocessing
+    _preprocessing: PreProcessing,
+    /// Linear regression settings
+    _linear_settings: Option<LinearRegressionParameters>,
+    /// SVR settings
+    _svr_settings: Option<SVRParameters>,
+    /// Lasso settings
+    _lasso_settings: Option<LassoParameters<f32>>,
+    /// Ridge settings
+    _ridge_settings: Option<RidgeRegressionParameters<f32>>,
+    /// Elastic net settings
+    _elastic_net_settings: Option<ElasticNetParameters<f32>>,
+    /// Decision tree regressor settings
+    _decision_tree_regressor_settings: Option<DecisionTreeRegressorParameters>,
+    /// Random forest regressor settings
+    _random_forest_regressor_settings: Option<RandomForestRegressorParameters>,
+    /// KNN regressor settings
+    _knn_regressor_settings: Option<KNNRegressorParameters>,
+    /// Logistic regression settings
+    _logistic_settings: Option<LogisticRegressionParameters>,
+    /// Random forest classifier settings
+    _random_forest_classifier_settings: Option<RandomForestClassifierParameters>,
+    /// KNN classifier settings
+    _knn_classifier_settings: Option<KNNClassifierParameters>,
+}
+
+/// The fields of settings saved with format version 2, up to and including the SVC parameters,
+/// which gained a multiclass strategy as their last field in version 3
+#[derive(serde::Deserialize)]
+struct SettingsPrefixV2 {
+    /// The fields before the SVC parameters
+    _head: SettingsHead,
+    /// The epoch, regularization, tolerance and kernel of the SVC parameters
+    svc_settings: Option<(usize, f32, f32, Kernel)>,
+}
+
+/// All the fields of settings saved with format version 3
+#[derive(serde::Deserialize)]
+struct SettingsV3 {
+    /// The fields before the SVC parameters
+    _head: SettingsHead,
+    /// SVC settings
+    _svc_settings: Option<SVCParameters>,
+    /// Decision tree classifier settings
+    _decision_tree_classifier_settings: Option<DecisionTreeClassifierParameters>,
+    /// Gaussian naive Bayes settings
+    _gaussian_nb_settings: Option<GaussianNBParameters<f32>>,
+    /// Categorical naive Bayes settings
+    _categorical_nb_settings: Option<CategoricalNBParameters<f32>>,
+}
+
+/// Settings as saved with format version 4, which appended the boosting parameters to those of
+/// version 3
+#[derive(serde::Deserialize)]
+struct SettingsV4 {
+    /// The fields of version 3
+    _v3: SettingsV3,
+    /// Gradient boosting regressor settings
+    _gradient_boosting_regressor_settings: Option<GradientBoostingRegressorParameters>,
+    /// Gradient boosting classifier settings
+    _gradient_boosting_classifier_settings: Option<GradientBoostingClassifierParameters>,
+}
+
+/// Version 5 settings, which are the version 4 settings followed by the extra trees parameters
+#[derive(serde::Deserialize)]
+struct SettingsV5 {
+    /// The fields of version 4
+    _v4: SettingsV4,
+    /// Extra trees regressor settings
+    _extra_trees_regressor_settings: Option<ExtraTreesRegressorParameters>,
+    /// Extra trees classifier settings
+    _extra_trees_classifier_settings: Option<ExtraTreesClassifierParameters>,
+}
+
+/// The settings layout of format versions 6 and 7, ending with the perceptron parameters
+#[derive(serde::Deserialize)]
+struct SettingsV6 {
+    /// The fields of version 5
+    _v5: SettingsV5,
+    /// Multilayer perceptron regressor settings
+    _mlp_regressor_settings: Option<MLPRegressorParameters>,
+    /// Multilayer perceptron classifier settings
+    _mlp_classifier_settings: Option<MLPClassifierParameters>,
+}
+
+/// Find where a prefix of the settings of type `T` ends in a serialized model or predictor, which
+/// both start with their settings
+fn end_of<T: serde::de::DeserializeOwned>(payload: &[u8]) -> Result<(T, usize), bincode::Error> {
+    let mut rest = payload;
+    let prefix: T = bincode::deserialize_from(&mut rest)?;
+    Ok((prefix, payload.len() - rest.len()))
+}
+
+/// Upgrade a serialized model or predictor from format version 2 by adding the default multiclass
+/// strategy to the SVC parameters
+pub(crate) fn add_svc_strategy(mut payload: Vec<u8>) -> Result<Vec<u8>, bincode::Error> {
+    let (prefix, position) = end_of::<SettingsPrefixV2>(&payload)?;
+    if prefix.svc_settings.is_some() {
+        let strategy = bincode::serialize(&MulticlassStrategy::default())?;
+        payload.splice(position..position, strategy);
+    }
+    Ok(payload)
+}
+
+/// Upgrade a serialized model or predictor from format version 3. Gradient boosting was not
+/// available then, so it gets unset parameters at the end of the settings.
+pub(crate) fn add_gradient_boosting(mut payload: Vec<u8>) -> Result<Vec<u8>, bincode::Error> {
+    let (_, position) = end_of::<SettingsV3>(&payload)?;
+    let missing = bincode::serialize(&(
+        None::<GradientBoostingRegressorParameters>,
+        None::<GradientBoostingClassifierParameters>,
+    ))?;
+    payload.splice(position..position, missing);
+    Ok(payload)
+}
+
+/// Give a version 4 payload unset extra trees parameters, right after its boosting parameters
+pub(crate) fn add_extra_trees(mut payload: Vec<u8>) -> Result<Vec<u8>, bincode::Error> {
+    let (_, position) = end_of::<SettingsV4>(&payload)?;
+    let missing = bincode::serialize(&(
+        None::<ExtraTreesRegressorParameters>,
+        None::<ExtraTreesClassifierParameters>,
+    ))?;
+    payload.splice(position..position, missing);
+    Ok(payload)
+}
+
+/// Make room for perceptron parameters in the settings of a version 5 payload, leaving them unset
+pub(crate) fn add_mlp(mut payload: Vec<u8>) -> Result<Vec<u8>, bincode::Error> {
+    let (_, position) = end_of::<SettingsV5>(&payload)?;
+    let missing = bincode::serialize(&(
+        None::<MLPRegressorParameters>,
+        None::<MLPClassifierParameters>,
+    ))?;
+    payload.splice(position..position, missing);
+    Ok(payload)
+}
+
+/// Append unset Poisson, gamma and Tweedie parameters to the settings of a version 7 payload
+pub(crate) fn add_glm(mut payload: Vec<u8>) -> Result<Vec<u8>, bincode::Error> {
+    let (_, position) = end_of::<SettingsV6>(&payload)?;
+    let missing = bincode::serialize(&(
+        None::<PoissonRegressorParameters>,
+        None::<GammaRegressorParameters>,
+        None::<TweedieRegressorParameters>,
+    ))?;
+    payload.splice(position..position, missing);
+    Ok(payload)
+}
+
+/// The settings layout of format version 8, ending with the generalized linear model parameters
+#[derive(serde::Deserialize)]
+struct SettingsV8 {
+    /// The fields of versions 6 and 7
+    _v6: SettingsV6,
+    /// Poisson regressor settings
+    _poisson_regressor_settings: Option<PoissonRegressorParameters>,
+    /// Gamma regressor settings
+    _gamma_regressor_settings: Option<GammaRegressorParameters>,
+    /// Tweedie regressor settings
+    _tweedie_regressor_settings: Option<TweedieRegressorParameters>,
+}
+
+/// Leave the quantile regression parameters unset in the settings of a version 8 payload
+pub(crate) fn add_quantile(mut payload: Vec<u8>) -> Result<Vec<u8>, bincode::Error> {
+    let (_, position) = end_of::<SettingsV8>(&payload)?;
+    let missing = bincode::serialize(&(
+        None::<QuantileRegressorParameters>,
+        None::<GradientBoostingQuantileRegressorParameters>,
+    ))?;
+    payload.splice(position..position, missing);
+    Ok(payload)
+}
+
+/// Find where the settings end in a serialized model or predictor from format version 6
+pub(crate) fn end_of_settings_v6(payload: &[u8]) -> Result<usize, bincode::Error> {
+    end_of::<SettingsV6>(payload).map(|(_, position)| position)
+}
+
+/// Find where the settings end in a serialized model from format version 8
+pub(crate) fn end_of_settings_v8(payload: &[u8]) -> Result<usize, bincode::Error> {
+    end_of::<SettingsV8>(payload).map(|(_, position)| position)
+}
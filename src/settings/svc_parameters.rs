@@ -1,4 +1,5 @@
 pub use crate::utils::Kernel;
+use std::fmt::{Display, Formatter};
 
 /// Parameters for support vector classification
 #[derive(serde::Serialize, serde::Deserialize)]
@@ -7,6 +8,9 @@ pub struct SVCParameters {
     pub(crate) c: f32,
     pub(crate) tol: f32,
     pub(crate) kernel: Kernel,
+    /// How binary classifiers are combined when there are more than two classes
+    #[serde(default)]
+    pub(crate) strategy: MulticlassStrategy,
 }
 
 impl SVCParameters {
@@ -33,6 +37,19 @@ impl SVCParameters {
         self.kernel = kernel;
         self
     }
+
+    /// Define how binary classifiers are combined when there are more than two classes
+    /// ```
+    /// # use automl::Settings;
+    /// use automl::settings::{MulticlassStrategy, SVCParameters};
+    /// let settings = Settings::default_classification().with_svc_settings(
+    ///     SVCParameters::default().with_multiclass_strategy(MulticlassStrategy::OneVsRest),
+    /// );
+    /// ```
+    pub fn with_multiclass_strategy(mut self, strategy: MulticlassStrategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
 }
 
 impl Default for SVCParameters {
@@ -42,6 +59,28 @@ impl Default for SVCParameters {
             c: 1.0,
             tol: 1e-3,
             kernel: Kernel::Linear,
+            strategy: MulticlassStrategy::default(),
+        }
+    }
+}
+
+/// Ways of combining binary support vector classifiers to separate more than two classes
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum MulticlassStrategy {
+    /// Train a classifier for each class against all the others and pick the class whose
+    /// classifier gives the largest decision value
+    OneVsRest,
+    /// Train a classifier for each pair of classes and pick the class that wins the most pairs,
+    /// breaking ties in favor of the smaller label
+    #[default]
+    OneVsOne,
+}
+
+impl Display for MulticlassStrategy {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MulticlassStrategy::OneVsRest => write!(f, "One vs rest"),
+            MulticlassStrategy::OneVsOne => write!(f, "One vs one"),
         }
     }
 }
@@ -93,7 +93,134 @@ impl Display for Distance {
     }
 }
 
+/// Activation functions for the hidden layers of a multilayer perceptron
+#[derive(Clone, Copy, Default, serde::Serialize, serde::Deserialize)]
+pub enum Activation {
+    /// No transformation
+    Identity,
+
+    /// Logistic sigmoid
+    Logistic,
+
+    /// Hyperbolic tangent
+    Tanh,
+
+    /// Rectified linear unit
+    #[default]
+    ReLU,
+}
+
+impl Display for Activation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Activation::Identity => write!(f, "Identity"),
+            Activation::Logistic => write!(f, "Logistic"),
+            Activation::Tanh => write!(f, "Tanh"),
+            Activation::ReLU => write!(f, "ReLU"),
+        }
+    }
+}
+
+/// Optimizers for the weights of a multilayer perceptron
+#[derive(Clone, Copy, Default, serde::Serialize, serde::Deserialize)]
+pub enum Optimizer {
+    /// Stochastic gradient descent, parameterized by momentum
+    SGD(f32),
+
+    /// Adam, with the usual decay rates of 0.9 and 0.999
+    #[default]
+    Adam,
+}
+
+impl Display for Optimizer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Optimizer::SGD(momentum) => write!(f, "SGD\n    momentum = {}", momentum),
+            Optimizer::Adam => write!(f, "Adam"),
+        }
+    }
+}
+
 /// Function to do element-wise multiplication fo two vectors
 pub fn elementwise_multiply(v1: &Vec<f32>, v2: &Vec<f32>) -> Vec<f32> {
     v1.iter().zip(v2).map(|(&i1, &i2)| i1 * i2).collect()
 }
+
+/// Mean and population standard deviation of a set of values, both zero when there are none
+pub(crate) fn mean_and_std(values: &[f32]) -> (f32, f32) {
+    if values.is_empty() {
+        return (0.0, 0.0);
+    }
+    let n = values.len() as f32;
+    let mean = values.iter().sum::<f32>() / n;
+    let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f32>() / n;
+    (mean, variance.sqrt())
+}
+
+/// The mean Tweedie deviance of predictions with the given power, where a power of one is the
+/// Poisson deviance and a power of two the gamma deviance. Predictions are clipped to be positive.
+pub(crate) fn mean_tweedie_deviance(y_true: &[f32], y_pred: &[f32], power: f32) -> f32 {
+    let total = y_true
+        .iter()
+        .zip(y_pred)
+        .map(|(y, mean)| tweedie_unit_deviance(*y as f64, (*mean as f64).max(1e-8), power as f64))
+        .sum::<f64>();
+    (total / y_true.len() as f64) as f32
+}
+
+/// The deviance of one target from a predicted mean under a Tweedie distribution
+pub(crate) fn tweedie_unit_deviance(y: f64, mean: f64, power: f64) -> f64 {
+    if power == 1.0 {
+        let log_ratio = if y > 0.0 { y * (y / mean).ln() } else { 0.0 };
+        2.0 * (log_ratio - y + mean)
+    } else if power == 2.0 {
+        2.0 * ((mean / y).ln() + y / mean - 1.0)
+    } else {
+        2.0 * (y.max(0.0).powf(2.0 - power) / ((1.0 - power) * (2.0 - power))
+            - y * mean.powf(1.0 - power) / (1.0 - power)
+            + mean.powf(2.0 - power) / (2.0 - power))
+    }
+}
+
+/// The mean pinball loss of predictions of the given quantile, which weights targets above the
+/// prediction by the quantile and targets below it by one minus the quantile
+pub(crate) fn mean_pinball_loss(y_true: &[f32], y_pred: &[f32], quantile: f32) -> f32 {
+    let total = y_true
+        .iter()
+        .zip(y_pred)
+        .map(|(y, prediction)| {
+            let residual = y - prediction;
+            if residual < 0.0 {
+                (quantile - 1.0) * residual
+            } else {
+                quantile * residual
+            }
+        })
+        .sum::<f32>();
+    total / y_true.len() as f32
+}
+
+/// The smallest value with at least the given fraction of the values at or below it
+pub(crate) fn quantile_of(mut values: Vec<f32>, quantile: f32) -> f32 {
+    values.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    let rank = (quantile * values.len() as f32).ceil() as usize;
+    values[rank.clamp(1, values.len()) - 1]
+}
+
+/// Copy a value that does not implement `Clone`, such as a fitted smartcore model, by serializing
+/// it
+pub(crate) fn clone_by_serialization<T: serde::Serialize + serde::de::DeserializeOwned>(
+    value: &T,
+) -> T {
+    bincode::deserialize(&bincode::serialize(value).expect("Cannot serialize value."))
+        .expect("Cannot deserialize value.")
+}
+
+/// Quote a CSV field if it contains a separator, quote or line break
+pub(crate) fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
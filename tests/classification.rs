@@ -68,6 +68,360 @@ mod classification_tests {
         test_from_settings(settings);
     }
 
+    #[test]
+    fn test_report() {
+        let settings = Settings::default_classification()
+            .only(Algorithm::DecisionTreeClassifier)
+            .with_final_model(FinalModel::Blending {
+                algorithm: Algorithm::LogisticRegression,
+                meta_training_fraction: 0.15,
+                meta_testing_fraction: 0.15,
+            });
+        let mut classifier = SupervisedModel::new_from_dataset(load_dataset(), settings);
+        classifier.train();
+        let report = classifier.report();
+
+        // Check the summary
+        assert_eq!(report.shape(), (569, 30));
+        assert_eq!(report.class_counts().unwrap().values().sum::<usize>(), 569);
+        assert_eq!(report.leaderboard().len(), 1);
+        assert_eq!(
+            report.meta_model().unwrap().algorithm(),
+            Algorithm::LogisticRegression
+        );
+
+        // Check each format
+        let json: serde_json::Value = serde_json::from_str(&report.to_json()).unwrap();
+        assert_eq!(json["leaderboard"].as_array().unwrap().len(), 1);
+        assert_eq!(report.to_csv().lines().count(), 2);
+        assert!(report.to_markdown().contains("## Meta Model"));
+        assert!(report.to_html().starts_with("<!DOCTYPE html>"));
+    }
+
+    #[test]
+    fn test_explanations() {
+        let settings = Settings::default_classification()
+            .skip(Algorithm::DecisionTreeClassifier)
+            .skip(Algorithm::KNNClassifier)
+            .skip(Algorithm::SVC)
+            .skip(Algorithm::GaussianNaiveBayes)
+            .skip(Algorithm::CategoricalNaiveBayes);
+        let mut classifier = SupervisedModel::new_from_dataset(load_dataset(), settings);
+        classifier.train();
+
+        // Binary logistic regression has a single row of coefficients
+        let coefficients = classifier
+            .coefficients(Algorithm::LogisticRegression)
+            .unwrap();
+        assert_eq!(coefficients.coefficients().len(), 1);
+        assert_eq!(coefficients.coefficients()[0].len(), 30);
+        assert_eq!(coefficients.intercepts().len(), 1);
+
+        // Forest importances are spread over every tree
+        let importance = classifier
+            .impurity_importance(Algorithm::RandomForestClassifier)
+            .unwrap();
+        assert_eq!(importance.len(), 30);
+        let total: f32 = importance.iter().map(|feature| feature.mean()).sum();
+        assert!((total - 1.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_shap_additivity() {
+        let dataset = load_dataset();
+        let x: Vec<Vec<f32>> = dataset.data[..60]
+            .chunks(dataset.num_features)
+            .map(|row| row.to_vec())
+            .collect();
+
+        // TreeSHAP for a tree and sampled Kernel SHAP for 30 features add up to the prediction
+        for algorithm in [
+            Algorithm::DecisionTreeClassifier,
+            Algorithm::LogisticRegression,
+        ] {
+            let settings = Settings::default_classification().only(algorithm);
+            let mut classifier = SupervisedModel::new_from_dataset(load_dataset(), settings);
+            classifier.train();
+            let predictions = classifier.predict_from_vec(x.clone());
+            for (explanation, prediction) in classifier.explain(x.clone()).iter().zip(predictions) {
+                assert!((explanation.output() - prediction).abs() < 1e-3);
+            }
+        }
+    }
+
+    #[test]
+    fn test_multiclass_svc() {
+        let dataset = smartcore::dataset::iris::load_dataset();
+        let x: Vec<Vec<f32>> = dataset
+            .data
+            .chunks(dataset.num_features)
+            .map(|row| row.to_vec())
+            .collect();
+
+        for strategy in [MulticlassStrategy::OneVsRest, MulticlassStrategy::OneVsOne] {
+            let settings = Settings::default_classification()
+                .only(Algorithm::SVC)
+                .with_svc_settings(SVCParameters::default().with_multiclass_strategy(strategy));
+            let mut classifier = SupervisedModel::new_from_dataset(
+                smartcore::dataset::iris::load_dataset(),
+                settings,
+            );
+            classifier.train();
+
+            // Every prediction is one of the three species, and most of them are right
+            let predictions = classifier.predict_from_vec(x.clone());
+            assert!(predictions
+                .iter()
+                .all(|label| [0.0, 1.0, 2.0].contains(label)));
+            let correct = predictions
+                .iter()
+                .zip(&dataset.target)
+                .filter(|(prediction, target)| prediction == target)
+                .count();
+            assert!(correct as f32 / dataset.num_samples as f32 > 0.8);
+
+            // All of the binary models survive saving and exporting
+            classifier.save("tests/multiclass_svc.aml");
+            let loaded = SupervisedModel::new_from_file("tests/multiclass_svc.aml");
+            std::fs::remove_file("tests/multiclass_svc.aml").unwrap();
+            assert_eq!(loaded.predict_from_vec(x.clone()), predictions);
+            assert_eq!(
+                classifier.export_predictor().predict_from_vec(x.clone()),
+                predictions
+            );
+        }
+    }
+
+    #[test]
+    fn test_old_svc_files() {
+        // Files saved before SVC models were tagged as binary or multiclass, holding models of three
+        // well separated classes, or of the first two classes for the binary files
+        let x = vec![vec![0.2, 0.1], vec![10.2, -5.0], vec![20.1, -9.9]];
+        let mut files = vec![];
+        for version in 3..file_format::FORMAT_VERSION {
+            files.push((format!("tests/fixtures/format_v{}_svc", version), 3));
+        }
+        files.push(("tests/fixtures/format_v6_binary_svc".to_string(), 2));
+
+        for (file, number_of_classes) in files {
+            let expected = &[0.0, 1.0, 2.0][..number_of_classes];
+            let x = x[..number_of_classes].to_vec();
+            let model = SupervisedModel::new_from_file(&format!("{}_model.aml", file));
+            assert_eq!(model.predict_from_vec(x.clone()), expected);
+            let predictor = predictor::Predictor::new_from_file(&format!("{}_predictor.aml", file));
+            assert_eq!(predictor.predict_from_vec(x), expected);
+        }
+    }
+
+    #[test]
+    fn test_gradient_boosting() {
+        // Boosting handles both a binary and a three class problem
+        for dataset in [load_dataset(), smartcore::dataset::iris::load_dataset()] {
+            let x: Vec<Vec<f32>> = dataset
+                .data
+                .chunks(dataset.num_features)
+                .map(|row| row.to_vec())
+                .collect();
+            let target = dataset.target.clone();
+            let settings = Settings::default_classification()
+                .only(Algorithm::GradientBoostingClassifier)
+                .with_number_of_folds(3)
+                .shuffle_data(true)
+                .with_gradient_boosting_classifier_settings(
+                    GradientBoostingClassifierParameters::default()
+                        .with_n_estimators(50)
+                        .with_validation_fraction(0.1),
+                );
+            let mut classifier = SupervisedModel::new_from_dataset(dataset, settings);
+            classifier.train();
+            assert!(classifier.leaderboard()[0].mean_test_score() > 0.8);
+
+            // Predictions are class labels
+            let predictions = classifier.predict_from_vec(x);
+            assert!(predictions.iter().all(|label| target.contains(label)));
+        }
+
+        // Leaving out the only row of a class leaves a single class to train on, which is
+        // recorded as a failure rather than aborting the comparison
+        let x: Vec<Vec<f32>> = (0..20).map(|i| vec![i as f32]).collect();
+        let y: Vec<f32> = (0..20).map(|i| if i == 0 { 1.0 } else { 0.0 }).collect();
+        let settings = Settings::default_classification()
+            .only(Algorithm::GradientBoostingClassifier)
+            .with_number_of_folds(20);
+        let mut classifier = SupervisedModel::new_from_vec(x, y, settings);
+        classifier.train();
+        assert!(matches!(
+            classifier.leaderboard()[0].status(),
+            automl::progress::Status::Failed(_)
+        ));
+    }
+
+    #[test]
+    fn test_extra_trees() {
+        // Extra trees handle both a binary and a three class problem
+        for dataset in [load_dataset(), smartcore::dataset::iris::load_dataset()] {
+            let x: Vec<Vec<f32>> = dataset
+                .data
+                .chunks(dataset.num_features)
+                .map(|row| row.to_vec())
+                .collect();
+            let target = dataset.target.clone();
+            let settings = Settings::default_classification()
+                .only(Algorithm::ExtraTreesClassifier)
+                .with_number_of_folds(3)
+                .shuffle_data(true)
+                .with_extra_trees_classifier_settings(
+                    ExtraTreesClassifierParameters::default()
+                        .with_n_trees(50)
+                        .with_min_samples_leaf(2),
+                );
+            let mut classifier = SupervisedModel::new_from_dataset(dataset, settings);
+            classifier.train();
+            assert!(classifier.leaderboard()[0].mean_test_score() > 0.8);
+
+            // Predictions are class labels
+            let predictions = classifier.predict_from_vec(x);
+            assert!(predictions.iter().all(|label| target.contains(label)));
+
+            // Importances are spread over every tree
+            let total: f32 = classifier
+                .impurity_importance(Algorithm::ExtraTreesClassifier)
+                .unwrap()
+                .iter()
+                .map(|feature| feature.mean())
+                .sum();
+            assert!((total - 1.0).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn test_mlp() {
+        // The network handles both a binary and a three class problem
+        for dataset in [load_dataset(), smartcore::dataset::iris::load_dataset()] {
+            let x: Vec<Vec<f32>> = dataset
+                .data
+                .chunks(dataset.num_features)
+                .map(|row| row.to_vec())
+                .collect();
+            let target = dataset.target.clone();
+            let settings = Settings::default_classification()
+                .only(Algorithm::MLPClassifier)
+                .with_number_of_folds(3)
+                .shuffle_data(true)
+                .with_mlp_classifier_settings(
+                    MLPClassifierParameters::default()
+                        .with_hidden_layers(&[32])
+                        .with_learning_rate(0.01)
+                        .with_validation_fraction(0.1),
+                );
+            let mut classifier = SupervisedModel::new_from_dataset(dataset, settings);
+            classifier.train();
+            assert!(classifier.leaderboard()[0].mean_test_score() > 0.8);
+
+            // Predictions are class labels
+            let predictions = classifier.predict_from_vec(x);
+            assert!(predictions.iter().all(|label| target.contains(label)));
+        }
+    }
+
+    #[test]
+    fn test_batch_probabilities() {
+        use automl::batch::BatchOptions;
+
+        let algorithms = [
+            Algorithm::LogisticRegression,
+            Algorithm::RandomForestClassifier,
+            Algorithm::GradientBoostingClassifier,
+            Algorithm::ExtraTreesClassifier,
+            Algorithm::MLPClassifier,
+        ];
+        let loaders: [fn() -> smartcore::dataset::Dataset<f32, f32>; 2] =
+            [load_dataset, smartcore::dataset::iris::load_dataset];
+        for load in loaders {
+            let dataset = load();
+            let x: Vec<Vec<f32>> = dataset
+                .data
+                .chunks(dataset.num_features)
+                .map(|row| row.to_vec())
+                .collect();
+            let mut classes = dataset.target.clone();
+            classes.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            classes.dedup();
+            for algorithm in algorithms {
+                let settings = Settings::default_classification()
+                    .only(algorithm)
+                    .with_number_of_folds(2);
+                let mut classifier = SupervisedModel::new_from_dataset(load(), settings);
+                classifier.train();
+
+                let mut csv = classifier.schema().names().join(",");
+                for row in &x {
+                    let fields: Vec<String> = row.iter().map(|value| value.to_string()).collect();
+                    csv.push('\n');
+                    csv.push_str(&fields.join(","));
+                }
+                std::fs::write("tests/batch_probabilities.csv", csv).unwrap();
+
+                // Each row has a probability for every class that sum to one, and the most
+                // probable class is nearly always the predicted one
+                let options = BatchOptions::default().with_probabilities(true);
+                let mut agreements = 0;
+                for prediction in classifier
+                    .predict_from_csv_in_batches("tests/batch_probabilities.csv", options.clone())
+                    .unwrap()
+                {
+                    let prediction = prediction.unwrap();
+                    let probabilities = prediction.probabilities().unwrap();
+                    let labels: Vec<f32> = probabilities.iter().map(|(label, _)| *label).collect();
+                    assert_eq!(labels, classes, "{}", algorithm);
+                    let total: f32 = probabilities
+                        .iter()
+                        .map(|(_, probability)| probability)
+                        .sum();
+                    assert!((total - 1.0).abs() < 1e-3, "{}", algorithm);
+                    let (most_probable, _) = probabilities.iter().fold(
+                        (f32::NAN, -1.0),
+                        |best, (label, probability)| {
+                            if *probability > best.1 {
+                                (*label, *probability)
+                            } else {
+                                best
+                            }
+                        },
+                    );
+                    if most_probable == prediction.value() {
+                        agreements += 1;
+                    }
+                }
+                assert!(agreements as f32 > 0.95 * x.len() as f32, "{}", algorithm);
+
+                // A saved predictor writes a column for each class
+                classifier
+                    .export_predictor()
+                    .save("tests/batch_probabilities.aml");
+                let predictor =
+                    automl::predictor::Predictor::new_from_file("tests/batch_probabilities.aml");
+                predictor
+                    .predict_csv_to_file(
+                        "tests/batch_probabilities.csv",
+                        "tests/batch_probabilities_output.csv",
+                        options,
+                    )
+                    .unwrap();
+                let output =
+                    std::fs::read_to_string("tests/batch_probabilities_output.csv").unwrap();
+                let columns: Vec<String> = classes
+                    .iter()
+                    .map(|label| format!("probability_{}", label))
+                    .collect();
+                assert!(output.starts_with(&format!("prediction,{}\n", columns.join(","))));
+            }
+        }
+        std::fs::remove_file("tests/batch_probabilities.csv").unwrap();
+        std::fs::remove_file("tests/batch_probabilities.aml").unwrap();
+        std::fs::remove_file("tests/batch_probabilities_output.csv").unwrap();
+    }
+
     fn test_from_settings(settings: Settings) {
         // Check training
         let dataset = load_dataset();
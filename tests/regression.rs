@@ -47,6 +47,61 @@ mod regression_tests {
         );
     }
 
+    #[test]
+    #[cfg(feature = "csv")]
+    fn test_csv_options() {
+        use automl::csv_options::CsvOptions;
+
+        // A semicolon separated file with comments, quoted fields and missing values
+        let mut csv = "# generated for testing\n\"id\";a;b;y\n".to_string();
+        for i in 0..40 {
+            let b = if i % 7 == 0 {
+                "NA".to_string()
+            } else {
+                (i % 3).to_string()
+            };
+            csv.push_str(&format!("\"row {}\";{};{};{}\n", i, i, b, 2 * i));
+        }
+        std::fs::write("tests/options.csv", csv).unwrap();
+
+        let options = CsvOptions::default()
+            .with_delimiter(';')
+            .with_comment_prefix("#")
+            .with_null_values(&["NA"])
+            .with_missing_value(0.0)
+            .with_ignored_columns(&["id"]);
+        let settings = Settings::default_regression().only(Algorithm::Linear);
+        let mut regressor = SupervisedModel::new_from_csv_with_options(
+            "tests/options.csv",
+            2,
+            true,
+            settings,
+            options,
+        );
+        assert_eq!(regressor.schema().names(), ["a", "b"]);
+        regressor.train();
+
+        // The saved options are used again for prediction, including after loading
+        regressor.save("tests/options_model.aml");
+        let loaded = SupervisedModel::new_from_file("tests/options_model.aml");
+        std::fs::remove_file("tests/options_model.aml").unwrap();
+        let error = loaded
+            .try_predict_from_csv("tests/options.csv", true)
+            .unwrap_err();
+        std::fs::remove_file("tests/options.csv").unwrap();
+        assert!(error.to_string().contains("Extra: y"));
+
+        // Files that cannot be read are errors rather than panics
+        assert!(matches!(
+            loaded.try_predict_from_csv("tests/missing.csv", true),
+            Err(schema::SchemaError::Unreadable { .. })
+        ));
+        std::fs::write("tests/invalid_utf8.csv", b"a;b\n1;\xff\n").unwrap();
+        let error = loaded.try_predict_from_csv("tests/invalid_utf8.csv", true);
+        std::fs::remove_file("tests/invalid_utf8.csv").unwrap();
+        assert!(matches!(error, Err(schema::SchemaError::Unreadable { .. })));
+    }
+
     #[test]
     fn test_add_interactions_preprocessing() {
         let settings =
@@ -71,6 +126,1392 @@ mod regression_tests {
         test_from_settings(settings);
     }
 
+    #[test]
+    fn test_gradient_boosting() {
+        let dataset = load_dataset();
+        let x: Vec<Vec<f32>> = dataset
+            .data
+            .chunks(dataset.num_features)
+            .map(|row| row.to_vec())
+            .collect();
+
+        // Both with all rows and with subsampling and early stopping, boosting beats the mean
+        for parameters in [
+            GradientBoostingRegressorParameters::default(),
+            GradientBoostingRegressorParameters::default()
+                .with_learning_rate(0.05)
+                .with_subsample(0.8)
+                .with_validation_fraction(0.2)
+                .with_n_iter_no_change(5),
+        ] {
+            let settings = Settings::default_regression()
+                .only(Algorithm::GradientBoostingRegressor)
+                .with_number_of_folds(3)
+                .with_gradient_boosting_regressor_settings(parameters);
+            let mut regressor = SupervisedModel::new_from_dataset(load_dataset(), settings);
+            regressor.train();
+            assert!(regressor.leaderboard()[0].mean_test_score() > 0.2);
+
+            // The trees survive saving
+            let predictions = regressor.predict_from_vec(x.clone());
+            regressor.save("tests/gradient_boosting.aml");
+            let loaded = SupervisedModel::new_from_file("tests/gradient_boosting.aml");
+            std::fs::remove_file("tests/gradient_boosting.aml").unwrap();
+            assert_eq!(loaded.predict_from_vec(x.clone()), predictions);
+        }
+
+        // The validation loss on a target that jumps around at every row soon stops improving,
+        // so early stopping keeps only the first few rounds and the saved predictor shrinks
+        let x: Vec<Vec<f32>> = (0..200).map(|i| vec![i as f32]).collect();
+        let y: Vec<f32> = (0..200).map(|i| ((i * 37) % 101) as f32).collect();
+        let saved_size = |parameters: GradientBoostingRegressorParameters| {
+            let settings = Settings::default_regression()
+                .only(Algorithm::GradientBoostingRegressor)
+                .with_number_of_folds(2)
+                .with_gradient_boosting_regressor_settings(parameters.with_n_estimators(200));
+            let mut regressor = SupervisedModel::new_from_vec(x.clone(), y.clone(), settings);
+            regressor.train();
+            regressor
+                .export_predictor()
+                .save("tests/early_stopping.aml");
+            let size = std::fs::metadata("tests/early_stopping.aml").unwrap().len();
+            std::fs::remove_file("tests/early_stopping.aml").unwrap();
+            size
+        };
+        let every_round = saved_size(GradientBoostingRegressorParameters::default());
+        let stopped_early = saved_size(
+            GradientBoostingRegressorParameters::default()
+                .with_validation_fraction(0.3)
+                .with_n_iter_no_change(3),
+        );
+        assert!(stopped_early * 4 < every_round);
+    }
+
+    #[test]
+    fn test_extra_trees() {
+        let dataset = load_dataset();
+        let x: Vec<Vec<f32>> = dataset
+            .data
+            .chunks(dataset.num_features)
+            .map(|row| row.to_vec())
+            .collect();
+
+        // Both fully grown and shallow forests beat the mean
+        for parameters in [
+            ExtraTreesRegressorParameters::default(),
+            ExtraTreesRegressorParameters::default()
+                .with_n_trees(30)
+                .with_max_depth(4)
+                .with_min_samples_leaf(5)
+                .with_m(3),
+        ] {
+            let settings = Settings::default_regression()
+                .only(Algorithm::ExtraTreesRegressor)
+                .with_number_of_folds(3)
+                .with_extra_trees_regressor_settings(parameters);
+            let mut regressor = SupervisedModel::new_from_dataset(load_dataset(), settings);
+            regressor.train();
+            assert!(regressor.leaderboard()[0].mean_test_score() > 0.2);
+
+            // The trees survive saving
+            let predictions = regressor.predict_from_vec(x.clone());
+            regressor.save("tests/extra_trees.aml");
+            let loaded = SupervisedModel::new_from_file("tests/extra_trees.aml");
+            std::fs::remove_file("tests/extra_trees.aml").unwrap();
+            assert_eq!(loaded.predict_from_vec(x.clone()), predictions);
+
+            let importances = regressor
+                .impurity_importance(Algorithm::ExtraTreesRegressor)
+                .unwrap();
+            assert!(importances.iter().all(|feature| feature.mean().is_finite()));
+        }
+
+        // Every fit draws new thresholds, so two fits of one shallow tree on the same rows
+        // disagree, even though every feature is considered at each split
+        let fit = || {
+            let settings = Settings::default_regression()
+                .only(Algorithm::ExtraTreesRegressor)
+                .with_number_of_folds(2)
+                .with_extra_trees_regressor_settings(
+                    ExtraTreesRegressorParameters::default()
+                        .with_n_trees(1)
+                        .with_max_depth(3)
+                        .with_m(10),
+                );
+            let mut regressor = SupervisedModel::new_from_dataset(load_dataset(), settings);
+            regressor.train();
+            regressor.predict_from_vec(x.clone())
+        };
+        assert_ne!(fit(), fit());
+
+        // A forest of single leaves makes no feature important, rather than giving undefined
+        // importances
+        let settings = Settings::default_regression()
+            .only(Algorithm::ExtraTreesRegressor)
+            .with_number_of_folds(3)
+            .with_extra_trees_regressor_settings(
+                ExtraTreesRegressorParameters::default()
+                    .with_n_trees(5)
+                    .with_min_samples_split(1000),
+            );
+        let mut regressor = SupervisedModel::new_from_dataset(load_dataset(), settings);
+        regressor.train();
+        let importances = regressor
+            .impurity_importance(Algorithm::ExtraTreesRegressor)
+            .unwrap();
+        assert!(importances.iter().all(|feature| feature.mean() == 0.0));
+    }
+
+    #[test]
+    fn test_mlp() {
+        let dataset = load_dataset();
+        let x: Vec<Vec<f32>> = dataset
+            .data
+            .chunks(dataset.num_features)
+            .map(|row| row.to_vec())
+            .collect();
+
+        // Both with Adam and with momentum and early stopping, the network beats the mean
+        for parameters in [
+            MLPRegressorParameters::default(),
+            MLPRegressorParameters::default()
+                .with_hidden_layers(&[32, 16])
+                .with_activation(Activation::Tanh)
+                .with_optimizer(Optimizer::SGD(0.9))
+                .with_learning_rate(0.01)
+                .with_alpha(0.01)
+                .with_batch_size(32)
+                .with_validation_fraction(0.2),
+        ] {
+            let settings = Settings::default_regression()
+                .only(Algorithm::MLPRegressor)
+                .with_number_of_folds(3)
+                .with_mlp_regressor_settings(parameters);
+            let mut regressor = SupervisedModel::new_from_dataset(load_dataset(), settings);
+            regressor.train();
+            assert!(regressor.leaderboard()[0].mean_test_score() > 0.2);
+
+            // The weights survive saving
+            let predictions = regressor.predict_from_vec(x.clone());
+            regressor.save("tests/mlp.aml");
+            let loaded = SupervisedModel::new_from_file("tests/mlp.aml");
+            std::fs::remove_file("tests/mlp.aml").unwrap();
+            assert_eq!(loaded.predict_from_vec(x.clone()), predictions);
+        }
+
+        // The network can also blend the predictions of the other models
+        let settings = Settings::default_regression()
+            .skip(Algorithm::MLPRegressor)
+            .with_number_of_folds(3)
+            .with_final_model(FinalModel::Blending {
+                algorithm: Algorithm::MLPRegressor,
+                meta_training_fraction: 0.15,
+                meta_testing_fraction: 0.15,
+            });
+        let mut regressor = SupervisedModel::new_from_dataset(load_dataset(), settings);
+        regressor.train();
+        assert_eq!(regressor.predict_from_vec(x.clone()).len(), x.len());
+
+        // A network that fits a parabola, or a line with a penalty, from its training rows
+        let grid: Vec<Vec<f32>> = (-50..=50).map(|i| vec![i as f32 / 50.0]).collect();
+        let fit = |y: &[f32], parameters: MLPRegressorParameters| {
+            let settings = Settings::default_regression()
+                .only(Algorithm::MLPRegressor)
+                .with_number_of_folds(2)
+                .with_mlp_regressor_settings(parameters);
+            let mut regressor = SupervisedModel::new_from_vec(grid.clone(), y.to_vec(), settings);
+            regressor.train();
+            regressor.predict_from_vec(grid.clone())
+        };
+        let spread = |values: &[f32]| {
+            let mean = values.iter().sum::<f32>() / values.len() as f32;
+            values
+                .iter()
+                .map(|value| (value - mean).powi(2))
+                .sum::<f32>()
+        };
+        let r_squared = |predictions: &[f32], y: &[f32]| {
+            let error: f32 = predictions
+                .iter()
+                .zip(y)
+                .map(|(prediction, value)| (prediction - value).powi(2))
+                .sum();
+            1.0 - error / spread(y)
+        };
+        let trained = MLPRegressorParameters::default()
+            .with_hidden_layers(&[16])
+            .with_learning_rate(0.01)
+            .with_max_iter(1000)
+            .with_n_iter_no_change(100);
+
+        // Without an activation the network is linear and cannot bend to follow the parabola
+        let parabola: Vec<f32> = grid.iter().map(|row| row[0] * row[0]).collect();
+        let identity = fit(
+            &parabola,
+            trained.clone().with_activation(Activation::Identity),
+        );
+        let tanh = fit(&parabola, trained.clone().with_activation(Activation::Tanh));
+        assert!(r_squared(&identity, &parabola) < 0.1);
+        assert!(r_squared(&tanh, &parabola) > 0.9);
+
+        // A strong L2 penalty shrinks the weights, and with them the slope of the fitted line
+        let line: Vec<f32> = grid.iter().map(|row| 2.0 * row[0] + 1.0).collect();
+        let unpenalized = fit(&line, trained.clone().with_alpha(0.0));
+        let penalized = fit(&line, trained.with_alpha(1000.0));
+        assert!(r_squared(&unpenalized, &line) > 0.9);
+        assert!(spread(&penalized) < 0.2 * spread(&unpenalized));
+
+        // A step far too large for the loss makes gradient descent diverge, while the steps of
+        // Adam stay bounded by the learning rate
+        let too_large = MLPRegressorParameters::default()
+            .with_hidden_layers(&[])
+            .with_activation(Activation::Identity)
+            .with_learning_rate(5.0)
+            .with_n_iter_no_change(200);
+        let sgd = fit(&line, too_large.clone().with_optimizer(Optimizer::SGD(0.0)));
+        let adam = fit(&line, too_large.with_optimizer(Optimizer::Adam));
+        assert!(sgd.iter().any(|prediction| !(prediction.abs() < 1e9)));
+        assert!(adam.iter().all(|prediction| prediction.abs() < 1e3));
+    }
+
+    #[test]
+    fn test_glm() {
+        // Skewed counts whose log mean is linear in the features
+        let x: Vec<Vec<f32>> = (0..200)
+            .map(|i| vec![(i % 20) as f32 / 10.0, ((i * 7) % 13) as f32 / 6.0])
+            .collect();
+        let counts: Vec<f32> = x
+            .iter()
+            .enumerate()
+            .map(|(i, row)| {
+                let mean = (0.2 + 1.2 * row[0] - 0.6 * row[1]).exp();
+                (mean * ((i * 37) % 101) as f32 / 50.0).floor()
+            })
+            .collect();
+        let glms = [
+            Algorithm::PoissonRegressor,
+            Algorithm::GammaRegressor,
+            Algorithm::TweedieRegressor,
+        ];
+        let train = |y: &Vec<f32>, sort_by: Metric| {
+            let settings = Settings::default_regression()
+                .sorted_by(sort_by)
+                .with_number_of_folds(3)
+                .with_poisson_regressor_settings(PoissonRegressorParameters::default())
+                .with_gamma_regressor_settings(GammaRegressorParameters::default())
+                .with_tweedie_regressor_settings(
+                    TweedieRegressorParameters::default().with_power(1.2),
+                );
+            let mut regressor = SupervisedModel::new_from_vec(x.clone(), y.clone(), settings);
+            regressor.train();
+            regressor
+        };
+        let score = |regressor: &SupervisedModel, algorithm: Algorithm| {
+            regressor
+                .leaderboard()
+                .iter()
+                .find(|entry| entry.algorithm() == algorithm)
+                .map(|entry| entry.mean_test_score())
+        };
+        let skipped = |regressor: &SupervisedModel, algorithm: Algorithm| {
+            regressor
+                .leaderboard()
+                .iter()
+                .find(|entry| entry.algorithm() == algorithm)
+                .map_or(false, |entry| {
+                    matches!(
+                        entry.status(),
+                        progress::Status::Skipped(progress::Interruption::Unsuitable(_))
+                    )
+                })
+        };
+
+        // Counts with zeros leave out gamma regression, and the Poisson fit has a lower deviance
+        // than the linear one while never predicting a negative count
+        let regressor = train(&counts, Metric::PoissonDeviance);
+        assert!(skipped(&regressor, Algorithm::GammaRegressor));
+        assert!(
+            score(&regressor, Algorithm::PoissonRegressor).unwrap()
+                < score(&regressor, Algorithm::Linear).unwrap()
+        );
+        assert!(score(&regressor, Algorithm::TweedieRegressor).is_some());
+        let settings = Settings::default_regression()
+            .only(Algorithm::PoissonRegressor)
+            .with_number_of_folds(3);
+        let mut poisson = SupervisedModel::new_from_vec(x.clone(), counts.clone(), settings);
+        poisson.train();
+        let predictions = poisson.predict_from_vec(x.clone());
+        assert!(predictions.iter().all(|prediction| *prediction >= 0.0));
+
+        // The fit survives saving
+        poisson.save("tests/glm.aml");
+        let loaded = SupervisedModel::new_from_file("tests/glm.aml");
+        std::fs::remove_file("tests/glm.aml").unwrap();
+        assert_eq!(loaded.predict_from_vec(x.clone()), predictions);
+
+        // A Tweedie deviance with a power near one is close to the Poisson deviance
+        let tweedie = train(&counts, Metric::TweedieDeviance(1.001));
+        let (poisson_score, tweedie_score) = (
+            score(&regressor, Algorithm::PoissonRegressor).unwrap(),
+            score(&tweedie, Algorithm::PoissonRegressor).unwrap(),
+        );
+        assert!((poisson_score - tweedie_score).abs() < 0.01 * poisson_score);
+
+        // Positive amounts keep every family, and negative targets none of them
+        let amounts = counts.iter().map(|count| count + 0.5).collect();
+        let regressor = train(&amounts, Metric::GammaDeviance);
+        for algorithm in glms {
+            assert!(!skipped(&regressor, algorithm));
+        }
+        let shifted = counts.iter().map(|count| count - 1.0).collect();
+        let regressor = train(&shifted, Metric::MeanSquaredError);
+        for algorithm in glms {
+            assert!(skipped(&regressor, algorithm));
+        }
+
+        // Unsupported Tweedie powers are skipped the same way instead of panicking
+        let settings = Settings::default_regression()
+            .only(Algorithm::TweedieRegressor)
+            .with_number_of_folds(3)
+            .with_tweedie_regressor_settings(TweedieRegressorParameters::default().with_power(0.5));
+        let mut regressor = SupervisedModel::new_from_vec(x, counts, settings);
+        regressor.train();
+        assert!(skipped(&regressor, Algorithm::TweedieRegressor));
+    }
+
+    #[test]
+    fn test_prediction_interval() {
+        // The spread of the noise grows with the first feature, and the second is irrelevant
+        let mut state = 12345_u64;
+        let mut uniform = move || {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (state >> 40) as f32 / (1u64 << 24) as f32
+        };
+        let mut data = |rows: usize| {
+            let x: Vec<Vec<f32>> = (0..rows)
+                .map(|_| vec![4.0 * uniform(), uniform()])
+                .collect();
+            let y: Vec<f32> = x
+                .iter()
+                .map(|row| 2.0 + 3.0 * row[0] + (0.5 + row[0]) * (2.0 * uniform() - 1.0))
+                .collect();
+            (x, y)
+        };
+        let (x_train, y_train) = data(600);
+        let (x_test, y_test) = data(400);
+
+        let covered = |settings: Settings| {
+            let mut regressor =
+                SupervisedModel::new_from_vec(x_train.clone(), y_train.clone(), settings);
+            regressor.train();
+            let intervals = regressor.predict_interval(x_test.clone(), 0.8).unwrap();
+            assert_eq!(intervals.len(), y_test.len());
+
+            // Intervals are predicted from models kept since training, so they never change
+            regressor.save("tests/prediction_interval.aml");
+            let loaded = SupervisedModel::new_from_file("tests/prediction_interval.aml");
+            std::fs::remove_file("tests/prediction_interval.aml").unwrap();
+            for model in [&regressor, &loaded] {
+                let again = model.predict_interval(x_test.clone(), 0.8).unwrap();
+                for (first, second) in intervals.iter().zip(&again) {
+                    assert_eq!(first.lower(), second.lower());
+                    assert_eq!(first.median(), second.median());
+                    assert_eq!(first.upper(), second.upper());
+                }
+            }
+            for interval in &intervals {
+                assert!(interval.lower() <= interval.median());
+                assert!(interval.median() <= interval.upper());
+            }
+            intervals
+                .iter()
+                .zip(&y_test)
+                .filter(|(interval, y)| interval.lower() <= **y && **y <= interval.upper())
+                .count() as f32
+                / y_test.len() as f32
+        };
+
+        // Quantile regressors, and random forests read as quantile regression forests, cover
+        // close to the requested share of held-out targets
+        let quantile = Settings::default_regression()
+            .only(Algorithm::QuantileRegressor)
+            .sorted_by(Metric::PinballLoss(0.5))
+            .with_number_of_folds(3);
+        let boosting = Settings::default_regression()
+            .only(Algorithm::GradientBoostingQuantileRegressor)
+            .with_number_of_folds(3)
+            .with_gradient_boosting_quantile_regressor_settings(
+                GradientBoostingQuantileRegressorParameters::default().with_boosting(
+                    GradientBoostingParameters::default()
+                        .with_n_estimators(200)
+                        .with_max_depth(2)
+                        .with_min_samples_leaf(10),
+                ),
+            );
+        let forest = Settings::default_regression()
+            .only(Algorithm::RandomForestRegressor)
+            .with_number_of_folds(3)
+            .with_random_forest_regressor_settings(
+                RandomForestRegressorParameters::default().with_min_samples_leaf(10),
+            );
+        for settings in [quantile, boosting, forest] {
+            let coverage = covered(settings);
+            assert!(coverage > 0.7 && coverage < 0.9, "coverage {}", coverage);
+        }
+
+        // Without a best model that predicts quantiles, the best quantile regressor is used
+        let all = Settings::default_regression()
+            .with_number_of_folds(3)
+            .with_quantile_regressor_settings(QuantileRegressorParameters::default());
+        let coverage = covered(all);
+        assert!(coverage > 0.7 && coverage < 0.9, "coverage {}", coverage);
+
+        // Other models give no interval
+        let settings = Settings::default_regression()
+            .only(Algorithm::Linear)
+            .with_number_of_folds(3);
+        let mut linear = SupervisedModel::new_from_vec(x_train.clone(), y_train.clone(), settings);
+        linear.train();
+        assert!(linear.predict_interval(x_test.clone(), 0.8).is_none());
+
+        // A blend of quantiles is not a quantile, so blended models give no interval either
+        let settings = Settings::default_regression()
+            .only(Algorithm::QuantileRegressor)
+            .with_number_of_folds(3)
+            .with_final_model(FinalModel::Blending {
+                algorithm: Algorithm::Linear,
+                meta_training_fraction: 0.15,
+                meta_testing_fraction: 0.15,
+            });
+        let mut blended = SupervisedModel::new_from_vec(x_train.clone(), y_train.clone(), settings);
+        blended.train();
+        assert!(blended
+            .try_predict_interval(x_test.clone(), 0.8)
+            .unwrap()
+            .is_none());
+
+        // Bad coverages, rows and intervals wider than the fitted quantiles are errors
+        let settings = Settings::default_regression()
+            .only(Algorithm::QuantileRegressor)
+            .with_number_of_folds(3);
+        let mut quantile = SupervisedModel::new_from_vec(x_train.clone(), y_train, settings);
+        quantile.train();
+        assert!(matches!(
+            quantile.try_predict_interval(x_test.clone(), 0.0),
+            Err(interval::IntervalError::Coverage(_))
+        ));
+        assert!(matches!(
+            quantile.try_predict_interval(vec![vec![1.0; 3]], 0.8),
+            Err(interval::IntervalError::Schema(_))
+        ));
+        assert!(matches!(
+            quantile.try_predict_interval(x_test.clone(), 0.99),
+            Err(interval::IntervalError::OutOfRange { .. })
+        ));
+        assert!(quantile.try_predict_interval(x_test, 0.95).is_ok());
+    }
+
+    #[test]
+    fn test_progress_observer() {
+        use automl::progress::ProgressObserver;
+        use std::sync::{Arc, Mutex};
+
+        // Count every event the observer receives
+        #[derive(Default)]
+        struct Counts {
+            algorithms: usize,
+            folds: usize,
+            final_models: usize,
+        }
+        struct Counter(Arc<Mutex<Counts>>);
+        impl ProgressObserver for Counter {
+            fn on_algorithm_start(&mut self, _: Algorithm, _: usize, _: usize) {
+                self.0.lock().unwrap().algorithms += 1;
+            }
+            fn on_fold_end(&mut self, _: Algorithm, _: usize, _: usize, _: f32, _: f32) {
+                self.0.lock().unwrap().folds += 1;
+            }
+            fn on_final_model(&mut self, _: Algorithm, _: f32) {
+                self.0.lock().unwrap().final_models += 1;
+            }
+        }
+
+        // Train a single algorithm with three folds
+        let counts = Arc::new(Mutex::new(Counts::default()));
+        let settings = Settings::default_regression()
+            .only(Algorithm::Linear)
+            .with_number_of_folds(3)
+            .verbose(true);
+        let mut regressor = SupervisedModel::new_from_dataset(load_dataset(), settings)
+            .with_progress_observer(Counter(counts.clone()));
+        regressor.train();
+
+        let counts = counts.lock().unwrap();
+        assert_eq!(counts.algorithms, 1);
+        assert_eq!(counts.folds, 3);
+        assert_eq!(counts.final_models, 1);
+    }
+
+    #[test]
+    #[cfg(feature = "display")]
+    fn test_interruptions() {
+        use automl::progress::CancellationToken;
+        use std::time::Duration;
+
+        // Every algorithm stops after its first fold
+        let settings = Settings::default_regression()
+            .with_number_of_folds(3)
+            .with_algorithm_timeout(Duration::ZERO);
+        let mut regressor = SupervisedModel::new_from_dataset(load_dataset(), settings);
+        regressor.train();
+        assert!(format!("{}", regressor).contains("Cut short (algorithm timed out)"));
+
+        // No algorithm is started after cancelling
+        let token = CancellationToken::new();
+        let mut regressor =
+            SupervisedModel::new_from_dataset(load_dataset(), Settings::default_regression())
+                .with_cancellation_token(token.clone());
+        token.cancel();
+        regressor.train();
+        assert!(format!("{}", regressor).contains("Skipped (cancelled)"));
+    }
+
+    #[test]
+    fn test_settings_display() {
+        // Learners left out of the defaults are only shown once they have settings
+        let settings = Settings::default_regression();
+        assert!(!format!("{}", settings).contains("MLP Regressor"));
+        let settings = settings.with_mlp_regressor_settings(MLPRegressorParameters::default());
+        assert!(format!("{}", settings).contains("MLP Regressor"));
+    }
+
+    #[test]
+    fn test_leaderboard() {
+        use automl::progress::Status;
+
+        let settings = Settings::default_regression().with_number_of_folds(3);
+        let mut regressor = SupervisedModel::new_from_dataset(load_dataset(), settings);
+        assert!(regressor.leaderboard().is_empty());
+        regressor.train();
+
+        // Every algorithm is listed, best first
+        let leaderboard = regressor.leaderboard();
+        assert_eq!(leaderboard.len(), 8);
+        assert_eq!(regressor.best_algorithm(), Some(leaderboard[0].algorithm()));
+        for pair in leaderboard.windows(2) {
+            assert!(pair[0].mean_test_score() >= pair[1].mean_test_score());
+        }
+        for entry in &leaderboard {
+            assert_eq!(entry.status(), Status::Completed);
+            assert_eq!(entry.test_scores().len(), 3);
+            assert!(!entry.parameters().is_empty());
+        }
+
+        // Results can be serialized
+        let yaml = serde_yaml::to_string(&leaderboard).unwrap();
+        assert!(yaml.contains("mean_test_score"));
+    }
+
+    #[test]
+    fn test_feature_importance() {
+        // Hold out the last 100 samples
+        let dataset = load_dataset();
+        let x: Vec<Vec<f32>> = dataset
+            .data
+            .chunks(dataset.num_features)
+            .map(|row| row.to_vec())
+            .collect();
+        let (x_train, x_test) = x.split_at(342);
+        let (y_train, y_test) = dataset.target.split_at(342);
+
+        for final_model in [
+            FinalModel::Best,
+            FinalModel::Blending {
+                algorithm: Algorithm::Linear,
+                meta_training_fraction: 0.15,
+                meta_testing_fraction: 0.15,
+            },
+        ] {
+            let settings = Settings::default_regression()
+                .only(Algorithm::Linear)
+                .with_final_model(final_model);
+            let mut regressor =
+                SupervisedModel::new_from_vec(x_train.to_vec(), y_train.to_vec(), settings);
+            regressor.train();
+
+            let importance = regressor.feature_importance(x_test.to_vec(), y_test.to_vec(), 5);
+            assert_eq!(importance.len(), 10);
+            assert_eq!(importance[3].feature(), "x_3");
+            assert!(importance.iter().any(|feature| feature.mean() > 0.0));
+        }
+    }
+
+    #[test]
+    fn test_coefficients() {
+        // The coefficients reproduce the predictions of a linear model
+        let settings = Settings::default_regression().only(Algorithm::Linear);
+        let mut regressor = SupervisedModel::new_from_dataset(load_dataset(), settings);
+        regressor.train();
+        let coefficients = regressor.coefficients(Algorithm::Linear).unwrap();
+        let x = vec![0.01, 0.02, -0.03, 0.04, 0.0, -0.01, 0.02, 0.03, -0.02, 0.01];
+        let manual: f32 = coefficients.coefficients()[0]
+            .iter()
+            .zip(&x)
+            .map(|(c, x)| c * x)
+            .sum::<f32>()
+            + coefficients.intercepts()[0];
+        let predicted = regressor.predict_from_vec(vec![x])[0];
+        assert!((manual - predicted).abs() < 1e-2 * predicted.abs());
+
+        // Interaction terms are named after the features they combine
+        let settings = Settings::default_regression()
+            .only(Algorithm::Ridge)
+            .with_preprocessing(PreProcessing::AddInteractions);
+        let mut regressor = SupervisedModel::new_from_dataset(load_dataset(), settings);
+        regressor.train();
+        let coefficients = regressor.coefficients(Algorithm::Ridge).unwrap();
+        assert_eq!(coefficients.features().len(), 55);
+        assert!(coefficients.coefficient("x_1*x_3").is_some());
+        assert!(regressor.coefficients(Algorithm::Linear).is_none());
+    }
+
+    #[test]
+    fn test_impurity_importance() {
+        let settings = Settings::default_regression()
+            .skip(Algorithm::KNNRegressor)
+            .skip(Algorithm::SVR)
+            .with_extra_trees_regressor_settings(ExtraTreesRegressorParameters::default());
+        let mut regressor = SupervisedModel::new_from_dataset(load_dataset(), settings);
+        regressor.train();
+        for algorithm in [
+            Algorithm::DecisionTreeRegressor,
+            Algorithm::RandomForestRegressor,
+            Algorithm::ExtraTreesRegressor,
+        ] {
+            let importance = regressor.impurity_importance(algorithm).unwrap();
+            assert_eq!(importance.len(), 10);
+            let total: f32 = importance.iter().map(|feature| feature.mean()).sum();
+            assert!((total - 1.0).abs() < 1e-3);
+        }
+        assert!(regressor.impurity_importance(Algorithm::Linear).is_none());
+    }
+
+    #[test]
+    fn test_partial_dependence() {
+        let settings = Settings::default_regression().only(Algorithm::Linear);
+        let mut regressor = SupervisedModel::new_from_dataset(load_dataset(), settings);
+        regressor.train();
+
+        // A linear model has a straight partial dependence with the slope of its coefficient
+        let grid = regressor.feature_grid(2, 5);
+        let dependence = regressor.partial_dependence(2, grid.clone());
+        let slope = (dependence.average()[4] - dependence.average()[0]) / (grid[4] - grid[0]);
+        let coefficient = regressor
+            .coefficients(Algorithm::Linear)
+            .unwrap()
+            .coefficient("x_2")
+            .unwrap();
+        assert!((slope - coefficient).abs() < 1e-2 * coefficient.abs());
+        assert_eq!(dependence.individual().len(), 442);
+        assert!(dependence.to_svg().starts_with("<svg"));
+        assert_eq!(dependence.to_csv().lines().count(), 6);
+
+        // Interaction surfaces cover every pair of grid values
+        let grids = (regressor.feature_grid(2, 4), regressor.feature_grid(8, 3));
+        let dependence = regressor.partial_dependence_2d((2, 8), grids);
+        assert_eq!(dependence.average().len(), 4);
+        assert_eq!(dependence.average()[0].len(), 3);
+        assert_eq!(dependence.to_svg().matches("<rect").count(), 12 + 2);
+    }
+
+    #[test]
+    fn test_shap_additivity() {
+        let x = vec![
+            vec![0.03, -0.04, 0.06, 0.02, -0.01, 0.0, 0.05, -0.02, 0.01, 0.04],
+            vec![
+                -0.05, 0.05, -0.02, -0.03, 0.04, 0.02, -0.06, 0.03, -0.04, 0.0,
+            ],
+        ];
+
+        // TreeSHAP for trees and Kernel SHAP for everything else add up to the prediction
+        for algorithm in [
+            Algorithm::DecisionTreeRegressor,
+            Algorithm::RandomForestRegressor,
+            Algorithm::ExtraTreesRegressor,
+            Algorithm::Linear,
+            Algorithm::KNNRegressor,
+        ] {
+            let settings = Settings::default_regression().only(algorithm);
+            let mut regressor = SupervisedModel::new_from_dataset(load_dataset(), settings);
+            regressor.train();
+            let predictions = regressor.predict_from_vec(x.clone());
+            for (explanation, prediction) in regressor.explain(x.clone()).iter().zip(predictions) {
+                assert!((explanation.output() - prediction).abs() < 1e-3 * prediction.abs());
+            }
+        }
+    }
+
+    #[test]
+    fn test_shap_values() {
+        let x = vec![vec![
+            0.03, -0.04, 0.06, 0.02, -0.01, 0.0, 0.05, -0.02, 0.01, 0.04,
+        ]];
+
+        // A tree with a single split only credits the feature it splits on
+        let settings = Settings::default_regression()
+            .only(Algorithm::DecisionTreeRegressor)
+            .with_decision_tree_regressor_settings(
+                DecisionTreeRegressorParameters::default().with_max_depth(1),
+            );
+        let mut regressor = SupervisedModel::new_from_dataset(load_dataset(), settings);
+        regressor.train();
+        let explanation = &regressor.explain(x.clone())[0];
+        let nonzero = explanation
+            .contributions()
+            .iter()
+            .filter(|value| value.abs() > 1e-6)
+            .count();
+        assert_eq!(nonzero, 1);
+
+        // For a linear model, moving one feature only changes its own contribution, by the
+        // coefficient times the distance moved
+        let settings = Settings::default_regression().only(Algorithm::Linear);
+        let mut regressor = SupervisedModel::new_from_dataset(load_dataset(), settings);
+        regressor.train();
+        let mut moved = x[0].clone();
+        moved[2] += 0.05;
+        let explanations = regressor.explain(vec![x[0].clone(), moved]);
+        let coefficient = regressor
+            .coefficients(Algorithm::Linear)
+            .unwrap()
+            .coefficient("x_2")
+            .unwrap();
+        for j in 0..10 {
+            let change = explanations[1].contributions()[j] - explanations[0].contributions()[j];
+            let expected = if j == 2 { coefficient * 0.05 } else { 0.0 };
+            assert!((change - expected).abs() < 1e-2 * coefficient.abs() * 0.05);
+        }
+    }
+
+    #[test]
+    fn test_feature_names() {
+        let dataset = load_dataset();
+        let x: Vec<Vec<f32>> = dataset
+            .data
+            .chunks(dataset.num_features)
+            .map(|row| row.to_vec())
+            .collect();
+        let names = [
+            "age", "sex", "bmi", "bp", "s1", "s2", "s3", "s4", "s5", "s6",
+        ];
+
+        // Names are carried through preprocessing into the coefficients and explanations
+        let settings = Settings::default_regression()
+            .only(Algorithm::Ridge)
+            .with_preprocessing(PreProcessing::AddPolynomial { order: 2 });
+        let mut regressor =
+            SupervisedModel::new_from_vec_with_names(x.clone(), dataset.target, &names, settings);
+        regressor.train();
+        assert!(regressor.schema().is_named());
+        assert_eq!(regressor.preprocessed_feature_names().len(), 65);
+        let coefficients = regressor.coefficients(Algorithm::Ridge).unwrap();
+        assert!(coefficients.coefficient("bmi*bmi").is_some());
+        assert!(coefficients.coefficient("age*s6").is_some());
+        let explanation = &regressor.explain(vec![x[0].clone()])[0];
+        assert_eq!(explanation.features(), names);
+
+        // The schema is saved with the model
+        regressor.save("tests/feature_names.aml");
+        let loaded = SupervisedModel::new_from_file("tests/feature_names.aml");
+        std::fs::remove_file("tests/feature_names.aml").unwrap();
+        assert_eq!(loaded.schema(), regressor.schema());
+    }
+
+    #[test]
+    fn test_schema_validation() {
+        let settings = Settings::default_regression().only(Algorithm::Linear);
+        let mut regressor = SupervisedModel::new_from_dataset(load_dataset(), settings);
+        regressor.train();
+
+        // Rows are checked before reaching the model
+        assert!(regressor
+            .try_predict_from_vec(vec![vec![0.0; 10]; 3])
+            .is_ok());
+        let error = regressor
+            .try_predict_from_vec(vec![vec![0.0; 10], vec![0.0; 12]])
+            .unwrap_err();
+        assert_eq!(
+            error,
+            schema::SchemaError::RowLength {
+                row: 1,
+                expected: 10,
+                found: 12
+            }
+        );
+        assert_eq!(
+            error.to_string(),
+            "Expected 10 values in every row but row 1 has 12."
+        );
+        #[cfg(feature = "nd")]
+        assert!(regressor
+            .try_predict_from_ndarray(ndarray::Array2::zeros((3, 9)))
+            .is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "Expected 10 values in every row but row 0 has 3.")]
+    fn test_schema_panic() {
+        let settings = Settings::default_regression().only(Algorithm::Linear);
+        let mut regressor = SupervisedModel::new_from_dataset(load_dataset(), settings);
+        regressor.train();
+        regressor.predict_from_vec(vec![vec![0.0; 3]]);
+    }
+
+    #[test]
+    fn test_model_file() {
+        let settings = Settings::default_regression().only(Algorithm::Linear);
+        let mut regressor = SupervisedModel::new_from_dataset(load_dataset(), settings);
+        regressor.train();
+        regressor.save("tests/model_file.aml");
+        let header = file_format::read_header("tests/model_file.aml").unwrap();
+        let bytes = std::fs::read("tests/model_file.aml").unwrap();
+        std::fs::remove_file("tests/model_file.aml").unwrap();
+
+        // The header describes the file and the model loads back
+        assert_eq!(header.format_version(), file_format::FORMAT_VERSION);
+        assert_eq!(header.crate_version(), env!("CARGO_PKG_VERSION"));
+        let check = |bytes: &[u8]| {
+            std::fs::write("tests/model_file_check.aml", bytes).unwrap();
+            let loaded = SupervisedModel::try_new_from_file("tests/model_file_check.aml");
+            std::fs::remove_file("tests/model_file_check.aml").unwrap();
+            loaded
+        };
+        let loaded = check(&bytes).unwrap();
+        let x = vec![vec![0.01; 10]];
+        assert_eq!(
+            loaded.predict_from_vec(x.clone()),
+            regressor.predict_from_vec(x)
+        );
+
+        // Damaged, headerless and newer files are rejected with a reason
+        let mut damaged = bytes.clone();
+        *damaged.last_mut().unwrap() ^= 1;
+        assert!(matches!(
+            check(&damaged),
+            Err(file_format::LoadError::ChecksumMismatch)
+        ));
+        assert!(matches!(
+            check(&bytes[4..]),
+            Err(file_format::LoadError::MissingHeader)
+        ));
+        let mut newer = bytes.clone();
+        newer[4] += 1;
+        assert!(matches!(
+            check(&newer),
+            Err(file_format::LoadError::UnsupportedVersion { .. })
+        ));
+    }
+
+    #[test]
+    fn test_old_format_versions() {
+        // Each file holds a linear model of y = 2 x0 + x1 + 1 saved by an older version of automl
+        let x = vec![vec![3.0, 4.0]];
+        let check = |predictions: Vec<f32>| assert!((predictions[0] - 11.0).abs() < 1e-3);
+
+        // The first version 1 files have no contents in their header
+        let file = "tests/fixtures/format_v1_legacy_model.aml";
+        assert_eq!(file_format::read_header(file).unwrap().format_version(), 1);
+        check(SupervisedModel::new_from_file(file).predict_from_vec(x.clone()));
+
+        for version in 1..file_format::FORMAT_VERSION {
+            let file = format!("tests/fixtures/format_v{}_model.aml", version);
+            assert_eq!(
+                file_format::read_header(&file).unwrap().format_version(),
+                version
+            );
+            check(SupervisedModel::new_from_file(&file).predict_from_vec(x.clone()));
+            let file = format!("tests/fixtures/format_v{}_predictor.aml", version);
+            check(predictor::Predictor::new_from_file(&file).predict_from_vec(x.clone()));
+        }
+    }
+
+    #[test]
+    fn test_predictor() {
+        fn is_send_sync<T: Send + Sync>() {}
+        is_send_sync::<predictor::Predictor>();
+
+        let x = vec![vec![0.01; 10], vec![-0.02; 10], vec![0.03; 10]];
+        for settings in [
+            Settings::default_regression()
+                .with_preprocessing(PreProcessing::ReplaceWithPCA {
+                    number_of_components: 5,
+                })
+                .only(Algorithm::Ridge),
+            Settings::default_regression()
+                .skip(Algorithm::SVR)
+                .with_final_model(FinalModel::Blending {
+                    algorithm: Algorithm::Linear,
+                    meta_training_fraction: 0.15,
+                    meta_testing_fraction: 0.15,
+                }),
+        ] {
+            let mut regressor = SupervisedModel::new_from_dataset(load_dataset(), settings);
+            regressor.train();
+
+            // The saved predictor gives the same predictions from a smaller file
+            regressor.save("tests/predictor_model.aml");
+            regressor.export_predictor().save("tests/predictor.aml");
+            let model_size = std::fs::metadata("tests/predictor_model.aml")
+                .unwrap()
+                .len();
+            let predictor_size = std::fs::metadata("tests/predictor.aml").unwrap().len();
+            let predictor = predictor::Predictor::new_from_file("tests/predictor.aml");
+            let wrong_contents =
+                predictor::Predictor::try_new_from_file("tests/predictor_model.aml");
+            std::fs::remove_file("tests/predictor_model.aml").unwrap();
+            std::fs::remove_file("tests/predictor.aml").unwrap();
+            assert!(predictor_size < model_size);
+            assert_eq!(
+                predictor.predict_from_vec(x.clone()),
+                regressor.predict_from_vec(x.clone())
+            );
+            assert!(matches!(
+                wrong_contents,
+                Err(file_format::LoadError::WrongContents { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn test_concurrent_prediction() {
+        fn is_send_sync<T: Send + Sync>() {}
+        is_send_sync::<SupervisedModel>();
+
+        // A trained model can be shared between threads without a lock
+        let settings = Settings::default_regression()
+            .with_preprocessing(PreProcessing::ReplaceWithSVD {
+                number_of_components: 4,
+            })
+            .only(Algorithm::DecisionTreeRegressor);
+        let mut regressor = SupervisedModel::new_from_dataset(load_dataset(), settings);
+        regressor.train();
+        let regressor = std::sync::Arc::new(regressor);
+        let x = vec![vec![0.01; 10], vec![-0.02; 10]];
+        let expected = regressor.predict_from_vec(x.clone());
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let regressor = regressor.clone();
+                let x = x.clone();
+                std::thread::spawn(move || regressor.predict_from_vec(x))
+            })
+            .collect();
+        for handle in handles {
+            assert_eq!(handle.join().unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_batch_prediction() {
+        use automl::batch::{BatchError, BatchOptions};
+        use automl::csv_options::CsvOptions;
+
+        let dataset = load_dataset();
+        let x: Vec<Vec<f32>> = dataset
+            .data
+            .chunks(dataset.num_features)
+            .map(|row| row.to_vec())
+            .collect();
+        let names = [
+            "age", "sex", "bmi", "bp", "s1", "s2", "s3", "s4", "s5", "s6",
+        ];
+        let settings = Settings::default_regression().only(Algorithm::Ridge);
+        let mut regressor =
+            SupervisedModel::new_from_vec_with_names(x.clone(), dataset.target, &names, settings);
+        regressor.train();
+
+        // Write the data with a quoted ID column in the middle
+        let mut csv = "age,sex,\"id\",bmi,bp,s1,s2,s3,s4,s5,s6\n".to_string();
+        for (i, row) in x.iter().enumerate() {
+            let mut fields: Vec<String> = row.iter().map(|value| value.to_string()).collect();
+            fields.insert(2, format!("\"row {}\"", i));
+            csv.push_str(&fields.join(","));
+            csv.push('\n');
+        }
+        std::fs::write("tests/batch.csv", csv).unwrap();
+
+        // Small chunks give the same predictions as predicting everything at once
+        let options = BatchOptions::default()
+            .with_id_column("id")
+            .with_chunk_size(50);
+        let predictions: Vec<_> = regressor
+            .predict_from_csv_in_batches("tests/batch.csv", options.clone())
+            .unwrap()
+            .map(|prediction| prediction.unwrap())
+            .collect();
+        let expected = regressor.predict_from_vec(x.clone());
+        assert_eq!(predictions.len(), x.len());
+        assert_eq!(predictions[100].id(), Some("row 100"));
+        for (prediction, expected) in predictions.iter().zip(&expected) {
+            assert!((prediction.value() - expected).abs() < 1e-3);
+        }
+
+        // The same works from an exported predictor, writing to a file
+        let rows = regressor
+            .export_predictor()
+            .predict_csv_to_file("tests/batch.csv", "tests/batch_predictions.csv", options)
+            .unwrap();
+        let output = std::fs::read_to_string("tests/batch_predictions.csv").unwrap();
+        std::fs::remove_file("tests/batch_predictions.csv").unwrap();
+        assert_eq!(rows, x.len());
+        assert!(output.starts_with("id,prediction\nrow 0,"));
+
+        // Without the ID column the header does not match the schema
+        let error = regressor
+            .predict_from_csv_in_batches("tests/batch.csv", BatchOptions::default())
+            .err()
+            .unwrap();
+        std::fs::remove_file("tests/batch.csv").unwrap();
+        assert!(matches!(error, BatchError::Schema(_)));
+        assert!(error.to_string().contains("Extra: id"));
+
+        // CSV options passed for one call handle comments, null values and ignored columns
+        let mut csv = "# scored nightly\nage;sex;note;bmi;bp;s1;s2;s3;s4;s5;s6\n".to_string();
+        for row in x.iter().take(3) {
+            let mut fields: Vec<String> = row.iter().map(|value| value.to_string()).collect();
+            fields.insert(2, "'a; b'".to_string());
+            fields[0] = "NA".to_string();
+            csv.push_str(&fields.join(";"));
+            csv.push('\n');
+        }
+        std::fs::write("tests/batch_options.csv", csv).unwrap();
+        let csv_options = CsvOptions::default()
+            .with_delimiter(';')
+            .with_quote_char(Some('\''))
+            .with_comment_prefix("#")
+            .with_null_values(&["NA"])
+            .with_ignored_columns(&["note"]);
+        let options =
+            BatchOptions::default().with_csv_options(csv_options.clone().with_missing_value(0.0));
+        let predictions: Vec<f32> = regressor
+            .predict_from_csv_in_batches("tests/batch_options.csv", options)
+            .unwrap()
+            .map(|prediction| prediction.unwrap().value())
+            .collect();
+        assert_eq!(predictions.len(), 3);
+        let mut zeroed = x[..3].to_vec();
+        for row in &mut zeroed {
+            row[0] = 0.0;
+        }
+        for (prediction, expected) in predictions.iter().zip(regressor.predict_from_vec(zeroed)) {
+            assert!((prediction - expected).abs() < 1e-3);
+        }
+
+        // Without a missing value the null values cannot be read
+        let options = BatchOptions::default().with_csv_options(csv_options);
+        let error = regressor
+            .predict_from_csv_in_batches("tests/batch_options.csv", options)
+            .unwrap()
+            .next()
+            .unwrap()
+            .unwrap_err();
+        std::fs::remove_file("tests/batch_options.csv").unwrap();
+        assert!(matches!(
+            error,
+            BatchError::Parse {
+                line: 3,
+                field: 0,
+                ..
+            }
+        ));
+
+        // Invalid UTF-8 is reported with its line, and regressors have no probabilities
+        std::fs::write(
+            "tests/batch_encoding.csv",
+            b"age,sex,bmi,bp,s1,s2,s3,s4,s5,s6\n\xff\n",
+        )
+        .unwrap();
+        let error = regressor
+            .predict_from_csv_in_batches("tests/batch_encoding.csv", BatchOptions::default())
+            .unwrap()
+            .next()
+            .unwrap()
+            .unwrap_err();
+        assert!(matches!(error, BatchError::Encoding { line: 2 }));
+        let error = regressor
+            .predict_from_csv_in_batches(
+                "tests/batch_encoding.csv",
+                BatchOptions::default().with_probabilities(true),
+            )
+            .err()
+            .unwrap();
+        std::fs::remove_file("tests/batch_encoding.csv").unwrap();
+        assert!(matches!(error, BatchError::NoProbabilities));
+    }
+
+    #[test]
+    #[cfg(feature = "dataframe")]
+    fn test_new_from_dataframe() {
+        use automl::dataframe::{DataFrameOptions, DtypeHandling};
+        use polars::prelude::*;
+
+        let x: Vec<f32> = (0..40).map(|i| i as f32).collect();
+        let df = df!(
+            "id" => (0..40).map(|i| format!("row {}", i)).collect::<Vec<_>>(),
+            "x" => x.clone(),
+            "flag" => (0..40).map(|i| i % 2 == 0).collect::<Vec<_>>(),
+            "noise" => x.iter().map(|x| x.sin()).collect::<Vec<_>>(),
+            "y" => x.iter().map(|x| 2.0 * x).collect::<Vec<_>>()
+        )
+        .unwrap();
+
+        // Text columns are skipped and dropped columns are left out
+        let settings = Settings::default_regression().only(Algorithm::Linear);
+        let options = DataFrameOptions::default()
+            .with_dropped_columns(&["noise"])
+            .with_dtype_handling(DtypeHandling::SkipNonNumeric);
+        let mut regressor =
+            SupervisedModel::new_from_dataframe_with_options(df.clone(), "y", settings, options);
+        assert_eq!(regressor.schema().names(), ["x", "flag"]);
+        regressor.train();
+
+        // Features are picked out by name and the predictions appended as a column
+        let with_predictions = regressor.predict_into_dataframe(&df, "prediction");
+        assert_eq!(with_predictions.width(), df.width() + 1);
+        let predictions = regressor.predict_from_dataframe(&df.select(["flag", "x"]).unwrap());
+        assert!((predictions[10] - 20.0).abs() < 1e-3);
+
+        // Missing features are reported by name
+        let error = regressor
+            .try_predict_from_dataframe(&df.drop("x").unwrap())
+            .unwrap_err();
+        assert!(error.to_string().contains("Missing: x"));
+
+        // Missing values are reported rather than panicking
+        let gaps = df!("x" => [Some(1.0f32), None], "flag" => [true, false]).unwrap();
+        assert_eq!(
+            regressor.try_predict_from_dataframe(&gaps),
+            Err(automl::schema::SchemaError::MissingValues {
+                column: "x".to_string()
+            })
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "cli")]
+    fn test_command_line() {
+        // Run the binary and check that it succeeds
+        fn automl(args: &[&str]) {
+            let output = std::process::Command::new(env!("CARGO_BIN_EXE_automl"))
+                .args(args)
+                .output()
+                .unwrap();
+            assert!(
+                output.status.success(),
+                "{}",
+                String::from_utf8_lossy(&output.stderr)
+            );
+        }
+
+        // Start from the default settings, cut down to one algorithm to keep training quick
+        automl(&["settings", "init", "--out", "tests/cli_settings.yaml"]);
+        Settings::new_from_file("tests/cli_settings.yaml")
+            .only(Algorithm::Linear)
+            .save("tests/cli_settings.yaml");
+
+        automl(&[
+            "train",
+            "--data",
+            "data/diabetes.csv",
+            "--target",
+            "y",
+            "--settings",
+            "tests/cli_settings.yaml",
+            "--out",
+            "tests/cli_model.aml",
+        ]);
+        automl(&[
+            "predict",
+            "--model",
+            "tests/cli_model.aml",
+            "--data",
+            "data/diabetes_without_target.csv",
+            "--out",
+            "tests/cli_predictions.csv",
+        ]);
+
+        // One prediction per row of the file, under a header
+        let predictions = std::fs::read_to_string("tests/cli_predictions.csv").unwrap();
+        let rows = std::fs::read_to_string("data/diabetes_without_target.csv")
+            .unwrap()
+            .lines()
+            .count();
+        std::fs::remove_file("tests/cli_settings.yaml").unwrap();
+        std::fs::remove_file("tests/cli_model.aml").unwrap();
+        std::fs::remove_file("tests/cli_predictions.csv").unwrap();
+        assert!(predictions.starts_with("prediction\n"));
+        assert_eq!(predictions.lines().count(), rows);
+        assert!(predictions
+            .lines()
+            .skip(1)
+            .all(|value| value.parse::<f32>().is_ok()));
+    }
+
+    #[test]
+    #[cfg(feature = "serve")]
+    fn test_prediction_server() {
+        use automl::server::{PredictionServer, ServeError, ServerObserver};
+        use std::io::{Read, Write};
+        use std::sync::{Arc, Mutex};
+
+        // Keep the reload errors the server reports
+        struct Recorder(Arc<Mutex<Vec<String>>>);
+        impl ServerObserver for Recorder {
+            fn on_reload_error(&self, _model_file: &str, error: &ServeError) {
+                self.0.lock().unwrap().push(error.to_string());
+            }
+        }
+
+        // Send a request and return the status code and JSON body
+        fn request(
+            address: std::net::SocketAddr,
+            method: &str,
+            path: &str,
+            content_type: &str,
+            body: &str,
+        ) -> (u16, serde_json::Value) {
+            let mut stream = std::net::TcpStream::connect(address).unwrap();
+            write!(
+                stream,
+                "{} {} HTTP/1.1\r\nHost: localhost\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                method,
+                path,
+                content_type,
+                body.len(),
+                body
+            )
+            .unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+            let status = response[9..12].parse().unwrap();
+            let body = response.split("\r\n\r\n").nth(1).unwrap();
+            (status, serde_json::from_str(body).unwrap())
+        }
+
+        let dataset = load_dataset();
+        let x: Vec<Vec<f32>> = dataset
+            .data
+            .chunks(dataset.num_features)
+            .map(|row| row.to_vec())
+            .collect();
+        let names = [
+            "age", "sex", "bmi", "bp", "s1", "s2", "s3", "s4", "s5", "s6",
+        ];
+        let settings = Settings::default_regression().only(Algorithm::Ridge);
+        let mut regressor = SupervisedModel::new_from_vec_with_names(
+            x.clone(),
+            dataset.target.clone(),
+            &names,
+            settings,
+        );
+        regressor.train();
+        regressor.save("tests/server_model.aml");
+
+        let reload_errors = Arc::new(Mutex::new(vec![]));
+        let server = Arc::new(
+            PredictionServer::new("tests/server_model.aml", "127.0.0.1:0")
+                .unwrap()
+                .with_observer(Recorder(reload_errors.clone())),
+        );
+        let address = server.address();
+        let running = server.clone();
+        let handle = std::thread::spawn(move || running.run());
+
+        // Health and metadata
+        let (status, body) = request(address, "GET", "/health", "text/plain", "");
+        assert_eq!((status, body["status"].as_str()), (200, Some("ok")));
+        let (_, metadata) = request(address, "GET", "/metadata", "text/plain", "");
+        assert_eq!(metadata["algorithm"], Algorithm::Ridge.to_string());
+        assert_eq!(metadata["features"][2]["name"], "bmi");
+        assert!(metadata["scores"]["test"].is_number());
+
+        // JSON arrays, JSON objects and CSV give the same predictions as the model
+        let expected = regressor.predict_from_vec(x[..2].to_vec());
+        let arrays = serde_json::to_string(&x[..2]).unwrap();
+        let objects = serde_json::json!({
+            "rows": x[..2]
+                .iter()
+                .map(|row| names.iter().zip(row).collect::<std::collections::BTreeMap<_, _>>())
+                .collect::<Vec<_>>()
+        })
+        .to_string();
+        let csv: Vec<String> = std::iter::once(names.join(","))
+            .chain(x[..2].iter().map(|row| {
+                row.iter()
+                    .map(|value| value.to_string())
+                    .collect::<Vec<_>>()
+                    .join(",")
+            }))
+            .collect();
+        let csv = csv.join("\n");
+        for (content_type, body) in [
+            ("application/json", arrays),
+            ("application/json", objects),
+            ("text/csv", csv),
+        ] {
+            let (status, body) = request(address, "POST", "/predict", content_type, &body);
+            assert_eq!(status, 200);
+            for (prediction, expected) in body["predictions"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .zip(&expected)
+            {
+                assert!((prediction.as_f64().unwrap() as f32 - expected).abs() < 1e-3);
+            }
+        }
+
+        // Bad input is rejected with a reason
+        let (status, body) = request(address, "POST", "/predict", "application/json", "[[1.0]]");
+        assert_eq!(status, 400);
+        assert!(body["error"]
+            .as_str()
+            .unwrap()
+            .contains("Expected 10 values"));
+        let (status, _) = request(address, "GET", "/predict", "text/plain", "");
+        assert_eq!(status, 405);
+        let bad_csv = format!("{}\n1,2,x,4,5,6,7,8,9,10", names.join(","));
+        let (status, body) = request(address, "POST", "/predict", "text/csv", &bad_csv);
+        assert_eq!(status, 400);
+        assert!(body["error"].as_str().unwrap().contains("\"x\""));
+
+        // Overwriting the model file swaps the model in
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        let mut regressor = SupervisedModel::new_from_vec_with_names(
+            x,
+            dataset.target,
+            &names,
+            Settings::default_regression().only(Algorithm::Linear),
+        );
+        regressor.train();
+        regressor.export_predictor().save("tests/server_model.aml");
+        let (_, metadata) = request(address, "GET", "/metadata", "text/plain", "");
+        assert_eq!(metadata["algorithm"], Algorithm::Linear.to_string());
+        assert!(metadata["scores"].is_null());
+
+        // A file that cannot be loaded leaves the previous model serving and is reported
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        std::fs::write("tests/server_model.aml", "not a model").unwrap();
+        let (_, metadata) = request(address, "GET", "/metadata", "text/plain", "");
+        assert_eq!(metadata["algorithm"], Algorithm::Linear.to_string());
+        assert_eq!(reload_errors.lock().unwrap().len(), 1);
+
+        server.stop();
+        handle.join().unwrap();
+        std::fs::remove_file("tests/server_model.aml").unwrap();
+    }
+
     fn test_from_settings(settings: Settings) {
         // Check training
         let dataset = load_dataset();